@@ -8,6 +8,8 @@ if (process.env.NODE_ENV === 'test' && process.env.VITEST) {
   Object.assign(window, {
     electron: {
       readFile: fs.readFile,
+      writeFile: fs.writeFile,
+      mkdir: fs.mkdir,
       stat: fs.stat,
       readdir: fs.readdir,
       path,
@@ -48,6 +50,20 @@ class FileSystemManager {
     })
   }
 
+  async writeFile(path: string, data: Uint8Array): Promise<void> {
+    // Using local file system only works from desktop and nodejs
+    if (!window?.electron?.writeFile) {
+      return Promise.reject(new Error('No polyfill found for this function'))
+    }
+
+    return this.join(this.dir, path).then(async (filePath) => {
+      await window.electron.mkdir(window.electron.path.dirname(filePath), {
+        recursive: true,
+      })
+      return window.electron.writeFile(filePath, data)
+    })
+  }
+
   async exists(path: string): Promise<boolean | void> {
     // Using local file system only works from desktop.
     if (!window?.electron?.stat) {
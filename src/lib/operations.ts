@@ -294,6 +294,10 @@ export function getOperationLabel(op: Operation): string {
       return op.name ?? 'Anonymous custom function'
     case 'UserDefinedFunctionReturn':
       return 'User function return'
+    case 'LoopIteration':
+      return `Loop iteration ${op.index + 1}`
+    case 'LoopIterationEnd':
+      return 'Loop iteration end'
   }
 }
 
@@ -324,6 +328,7 @@ const operationFilters = [
   isNotUserFunctionWithNoOperations,
   isNotInsideUserFunction,
   isNotUserFunctionReturn,
+  isNotLoopIterationEnd,
 ]
 
 /**
@@ -389,6 +394,13 @@ function isNotUserFunctionReturn(ops: Operation[]): Operation[] {
   return ops.filter((op) => op.type !== 'UserDefinedFunctionReturn')
 }
 
+/**
+ * A filter to exclude LoopIterationEnd operations from a list of operations.
+ */
+function isNotLoopIterationEnd(ops: Operation[]): Operation[] {
+  return ops.filter((op) => op.type !== 'LoopIterationEnd')
+}
+
 export interface EnterEditFlowProps {
   operation: Operation
   artifact?: Artifact
@@ -0,0 +1,390 @@
+//! The `kcl` command line tool.
+//!
+//! Checks, formats, executes and exports KCL files without the app, so that KCL parts can be gated
+//! in CI.
+//! Any problems are printed to stderr and reported with a non-zero exit code.
+
+use std::{
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
+use kcl_lib::{
+    lint::LintLevel, CompilationError, ExecState, ExecutorContext, ExecutorSettings, ExportOptions, FileExportFormat,
+    KclErrorWithOutputs, LintSettings, Program, ProjectConfiguration, UnitLength, PROJECT_SETTINGS_FILE_NAME,
+};
+use miette::{LabeledSpan, MietteDiagnostic, NamedSource, SourceSpan};
+
+/// Work with KCL files from the command line.
+#[derive(Parser, Debug)]
+#[clap(name = "kcl", version, about)]
+struct Opts {
+    #[clap(subcommand)]
+    cmd: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Format KCL files in place.
+    Fmt {
+        /// Don't write anything, just fail if any file is not already formatted.
+        #[clap(long)]
+        check: bool,
+        /// The files to format.
+        #[clap(required = true)]
+        files: Vec<PathBuf>,
+    },
+    /// Check KCL files for style problems.
+    ///
+    /// Fails if there are any warnings or errors. The level of each lint can be set in the
+    /// `[settings.lint.rules]` table of the project's `project.toml`.
+    Lint {
+        /// The files to lint.
+        #[clap(required = true)]
+        files: Vec<PathBuf>,
+    },
+    /// Execute a KCL file.
+    Run {
+        #[clap(flatten)]
+        exec: ExecOpts,
+    },
+    /// Print the AST of a KCL file.
+    Ast {
+        /// Print the AST as JSON rather than in Rust's debug format.
+        #[clap(long)]
+        json: bool,
+        /// The file to parse.
+        file: PathBuf,
+    },
+    /// Execute a KCL file and print the operations it performed as JSON.
+    Ops {
+        #[clap(flatten)]
+        exec: ExecOpts,
+    },
+    /// Execute a KCL file and export the model it makes.
+    Export {
+        /// The format to export to.
+        #[clap(long, value_parser = one_of::<FileExportFormat>(&["fbx", "glb", "gltf", "obj", "ply", "step", "stl"]))]
+        format: FileExportFormat,
+        /// The file to write.
+        #[clap(short, long)]
+        output: PathBuf,
+        /// The length unit of the exported coordinates, for OBJ, PLY and STL. Defaults to the file's
+        /// units, set by its `@settings` or the project.
+        #[clap(long, value_parser = one_of::<UnitLength>(&["cm", "ft", "in", "m", "mm", "yd"]))]
+        units: Option<UnitLength>,
+        /// The coordinate system of the exported file, for OBJ, PLY, STEP and STL. Defaults to zoo.
+        #[clap(long, value_parser = ["zoo", "opengl", "vulkan"])]
+        coords: Option<String>,
+        /// Write a text file rather than a binary one, for FBX, glTF, PLY and STL.
+        #[clap(long)]
+        ascii: bool,
+        #[clap(flatten)]
+        exec: ExecOpts,
+    },
+}
+
+#[derive(clap::Args, Debug)]
+struct ExecOpts {
+    /// Execute without connecting to the engine.
+    /// Geometry is not computed, but everything else is checked.
+    #[clap(long)]
+    mock: bool,
+    /// Reuse the results of executing imported modules from earlier runs, which are cached in the
+    /// project's `.kcl-cache` directory.
+    #[clap(long)]
+    cache: bool,
+    /// The file to execute. Imports are resolved relative to its directory.
+    file: PathBuf,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let opts = Opts::parse();
+    match opts.cmd.run().await {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(err) => {
+            eprintln!("error: {err:#}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+impl Command {
+    /// Run the command, returning whether everything was ok.
+    async fn run(self) -> Result<bool> {
+        match self {
+            Command::Fmt { check, files } => {
+                let mut ok = true;
+                for path in files {
+                    let file = SourceFile::read(&path)?;
+                    let Some(program) = file.parse() else {
+                        ok = false;
+                        continue;
+                    };
+                    let formatted = program.recast();
+                    if formatted == file.code {
+                        continue;
+                    }
+                    if check {
+                        eprintln!("{} is not formatted", file.name);
+                        ok = false;
+                    } else {
+                        std::fs::write(&path, formatted).with_context(|| format!("writing {}", file.name))?;
+                        eprintln!("formatted {}", file.name);
+                    }
+                }
+                Ok(ok)
+            }
+            Command::Lint { files } => {
+                let mut ok = true;
+                for path in files {
+                    let file = SourceFile::read(&path)?;
+                    let Some(program) = file.parse() else {
+                        ok = false;
+                        continue;
+                    };
+                    for finding in program.lint_with_settings(&lint_settings(&path)?)? {
+                        if finding.overridden {
+                            continue;
+                        }
+                        let severity = match finding.level {
+                            LintLevel::Error => miette::Severity::Error,
+                            LintLevel::Warning => miette::Severity::Warning,
+                            LintLevel::Off | LintLevel::Info | LintLevel::Hint => miette::Severity::Advice,
+                        };
+                        if severity != miette::Severity::Advice {
+                            ok = false;
+                        }
+                        let mut diagnostic = MietteDiagnostic::new(finding.finding.title)
+                            .with_code(finding.finding.code)
+                            .with_severity(severity)
+                            .with_label(LabeledSpan::new_with_span(
+                                Some(finding.description),
+                                SourceSpan::from(finding.pos),
+                            ));
+                        if let Some(suggestion) = finding.suggestion {
+                            diagnostic = diagnostic.with_help(format!("{}: `{}`", suggestion.title, suggestion.insert));
+                        }
+                        file.report(diagnostic);
+                    }
+                }
+                Ok(ok)
+            }
+            Command::Run { exec } => Ok(exec.execute().await?.is_some()),
+            Command::Ast { json, file } => {
+                let file = SourceFile::read(&file)?;
+                let Some(program) = file.parse() else {
+                    return Ok(false);
+                };
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&program)?);
+                } else {
+                    println!("{:#?}", program.ast);
+                }
+                Ok(true)
+            }
+            Command::Ops { exec } => {
+                let Some(exec_state) = exec.execute().await? else {
+                    return Ok(false);
+                };
+                let outcome = exec_state.to_wasm_outcome();
+                println!("{}", serde_json::to_string_pretty(&outcome.operations)?);
+                Ok(true)
+            }
+            Command::Export {
+                format,
+                output,
+                units,
+                coords,
+                ascii,
+                exec,
+            } => {
+                if exec.mock {
+                    anyhow::bail!("exporting needs the engine to compute the geometry, so can't be done with --mock");
+                }
+                let options = ExportOptions {
+                    length_unit: units,
+                    coords,
+                    binary: ascii.then_some(false),
+                };
+                let ctx = exec.context().await?;
+                let executed = exec.execute_with(&ctx).await;
+                let exported = match &executed {
+                    Ok(Some(exec_state)) => Some(ctx.export(exec_state, format, options).await),
+                    _ => None,
+                };
+                ctx.close().await;
+                let Some(exported) = exported else {
+                    return executed.map(|_| false);
+                };
+                let mut files = exported.context("exporting")?;
+
+                // Every format is written as a single file.
+                if files.len() != 1 {
+                    anyhow::bail!("the engine exported {} files rather than one", files.len());
+                }
+                let file = files.remove(0);
+                std::fs::write(&output, file.contents).with_context(|| format!("writing {}", output.display()))?;
+                eprintln!("exported {}", output.display());
+                Ok(true)
+            }
+        }
+    }
+}
+
+impl ExecOpts {
+    /// Execute the file, returning `None` if there were any errors.
+    async fn execute(&self) -> Result<Option<ExecState>> {
+        let ctx = self.context().await?;
+        let result = self.execute_with(&ctx).await;
+        ctx.close().await;
+        result
+    }
+
+    /// Make the context to execute the file in.
+    async fn context(&self) -> Result<ExecutorContext> {
+        // Use the project's settings, e.g. its units, as the app would.
+        let mut settings = match project_config(&self.file)? {
+            Some(config) => ExecutorSettings::from(config),
+            None => ExecutorSettings::default(),
+        };
+        settings.with_current_file(std::path::absolute(&self.file)?);
+        if self.cache {
+            settings.with_disk_cache();
+        }
+        let ctx = if self.mock {
+            let mut ctx = ExecutorContext::new_mock().await;
+            ctx.settings = settings;
+            ctx
+        } else {
+            ExecutorContext::new_with_client(settings, None, None).await?
+        };
+        Ok(ctx)
+    }
+
+    /// Execute the file in a context, which is left open so the scene can be used afterwards.
+    /// Returns `None` if there were any errors.
+    async fn execute_with(&self, ctx: &ExecutorContext) -> Result<Option<ExecState>> {
+        let file = SourceFile::read(&self.file)?;
+        let Some(program) = file.parse() else {
+            return Ok(None);
+        };
+
+        let mut exec_state = ExecState::new(&ctx.settings);
+        match ctx.run_with_ui_outputs(&program, &mut exec_state).await {
+            Ok(_) => Ok(Some(exec_state)),
+            Err(KclErrorWithOutputs { error, .. }) => {
+                file.report(kcl_lib::Report {
+                    error,
+                    kcl_source: file.code.clone(),
+                    filename: file.name.clone(),
+                });
+                Ok(None)
+            }
+        }
+    }
+}
+
+/// A KCL file read from disk.
+struct SourceFile {
+    /// The name used for the file in messages.
+    name: String,
+    code: String,
+}
+
+impl SourceFile {
+    fn read(path: &Path) -> Result<Self> {
+        let name = path.display().to_string();
+        let code = std::fs::read_to_string(path).with_context(|| format!("reading {name}"))?;
+        Ok(SourceFile { name, code })
+    }
+
+    /// Parse the file, reporting any errors or warnings.
+    /// Returns `None` if there were any errors.
+    fn parse(&self) -> Option<Program> {
+        let (program, errs) = match Program::parse(&self.code) {
+            Ok(result) => result,
+            Err(error) => {
+                self.report(kcl_lib::Report {
+                    error,
+                    kcl_source: self.code.clone(),
+                    filename: self.name.clone(),
+                });
+                return None;
+            }
+        };
+
+        let mut ok = true;
+        for err in errs {
+            ok &= !err.severity.is_err();
+            self.report(compilation_diagnostic(err));
+        }
+        let program = program.filter(|_| ok)?;
+        for warning in program.typecheck() {
+            self.report(compilation_diagnostic(warning));
+        }
+        Some(program)
+    }
+
+    /// Print a diagnostic about this file to stderr.
+    fn report(&self, diagnostic: impl miette::Diagnostic + Send + Sync + 'static) {
+        let report = miette::Report::new(diagnostic).with_source_code(NamedSource::new(&self.name, self.code.clone()));
+        eprintln!("{report:?}");
+    }
+}
+
+/// Get the lint settings from the `project.toml` of the project which contains a file.
+fn lint_settings(path: &Path) -> Result<LintSettings> {
+    Ok(project_config(path)?
+        .map(|config| config.settings.lint)
+        .unwrap_or_default())
+}
+
+/// Read the `project.toml` of the project which contains a file, if there is one.
+fn project_config(path: &Path) -> Result<Option<ProjectConfiguration>> {
+    let path = std::path::absolute(path)?;
+    for dir in path.ancestors().skip(1) {
+        let settings_path = dir.join(PROJECT_SETTINGS_FILE_NAME);
+        if !settings_path.exists() {
+            continue;
+        }
+        let toml =
+            std::fs::read_to_string(&settings_path).with_context(|| format!("reading {}", settings_path.display()))?;
+        let config = ProjectConfiguration::backwards_compatible_toml_parse(&toml)
+            .with_context(|| format!("parsing {}", settings_path.display()))?;
+        return Ok(Some(config));
+    }
+    Ok(None)
+}
+
+/// Parse an argument which must be one of the given values, so they can be listed in the help.
+fn one_of<T: std::str::FromStr + Clone + Send + Sync + 'static>(
+    values: &'static [&'static str],
+) -> impl clap::builder::TypedValueParser<Value = T> {
+    use clap::builder::TypedValueParser as _;
+
+    clap::builder::PossibleValuesParser::new(values).map(|value| {
+        value
+            .parse()
+            .unwrap_or_else(|_| unreachable!("{value} is a valid value"))
+    })
+}
+
+fn compilation_diagnostic(err: CompilationError) -> MietteDiagnostic {
+    let severity = if err.severity.is_err() {
+        miette::Severity::Error
+    } else {
+        miette::Severity::Warning
+    };
+    let mut diagnostic = MietteDiagnostic::new(err.message)
+        .with_severity(severity)
+        .with_label(LabeledSpan::underline(SourceSpan::from(err.source_range)));
+    if let Some(suggestion) = err.suggestion {
+        diagnostic = diagnostic.with_help(format!("{}: `{}`", suggestion.title, suggestion.insert));
+    }
+    diagnostic
+}
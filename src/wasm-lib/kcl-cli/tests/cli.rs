@@ -0,0 +1,138 @@
+use std::{path::PathBuf, process::Command};
+
+/// Write `code` to a fresh file for a test, returning its path.
+fn kcl_file(test_name: &str, code: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join("kcl-cli-tests").join(test_name);
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("main.kcl");
+    std::fs::write(&path, code).unwrap();
+    path
+}
+
+fn kcl(args: &[&str], file: &PathBuf) -> (i32, String, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_kcl"))
+        .args(args)
+        .arg(file)
+        .env("NO_COLOR", "1")
+        .output()
+        .unwrap();
+    (
+        output.status.code().unwrap(),
+        String::from_utf8(output.stdout).unwrap(),
+        String::from_utf8(output.stderr).unwrap(),
+    )
+}
+
+#[test]
+fn fmt_check() {
+    let file = kcl_file("fmt_check", "x =  1\n");
+    let (code, _, stderr) = kcl(&["fmt", "--check"], &file);
+    assert_eq!(code, 1);
+    assert!(stderr.contains("is not formatted"), "{stderr}");
+
+    let (code, _, _) = kcl(&["fmt"], &file);
+    assert_eq!(code, 0);
+    assert_eq!(std::fs::read_to_string(&file).unwrap(), "x = 1\n");
+
+    let (code, _, _) = kcl(&["fmt", "--check"], &file);
+    assert_eq!(code, 0);
+}
+
+#[test]
+fn lint() {
+    // Lints are only information by default.
+    let file = kcl_file("lint", "foo_bar = 1\n");
+    let (code, _, stderr) = kcl(&["lint"], &file);
+    assert_eq!(code, 0);
+    assert!(stderr.contains("Z0001"), "{stderr}");
+
+    std::fs::write(
+        file.parent().unwrap().join("project.toml"),
+        "[settings.lint.rules]\nZ0001 = \"error\"\n",
+    )
+    .unwrap();
+    let (code, _, stderr) = kcl(&["lint"], &file);
+    assert_eq!(code, 1);
+    assert!(stderr.contains("Z0001"), "{stderr}");
+
+    let file = kcl_file("lint_allow", "@allow(Z0001)\nfoo_bar = 1\n");
+    let (code, _, stderr) = kcl(&["lint"], &file);
+    assert_eq!(code, 0);
+    assert!(stderr.is_empty(), "{stderr}");
+}
+
+#[test]
+fn ast_json() {
+    let file = kcl_file("ast_json", "x = 1\n");
+    let (code, stdout, _) = kcl(&["ast", "--json"], &file);
+    assert_eq!(code, 0);
+    let ast: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(ast["body"][0]["declaration"]["id"]["name"], "x");
+}
+
+#[test]
+fn run_mock() {
+    let file = kcl_file("run_mock", "x = 1\ny = x + 1\n");
+    let (code, _, stderr) = kcl(&["run", "--mock"], &file);
+    assert_eq!(code, 0, "{stderr}");
+
+    let file = kcl_file("run_mock_error", "x = y + 1\n");
+    let (code, _, stderr) = kcl(&["run", "--mock"], &file);
+    assert_eq!(code, 1);
+    assert!(stderr.contains("memory item key `y` is not defined"), "{stderr}");
+}
+
+#[test]
+fn run_uses_project_units() {
+    let file = kcl_file(
+        "run_uses_project_units",
+        "x = 1in + 1mm\nassertEqual(x, 1.03937, 0.00001, \"x should be in inches\")\n",
+    );
+    let (code, _, stderr) = kcl(&["run", "--mock"], &file);
+    assert_eq!(code, 1);
+    assert!(stderr.contains("x should be in inches"), "{stderr}");
+
+    std::fs::write(
+        file.parent().unwrap().join("project.toml"),
+        "[settings.modeling]\nbase_unit = \"in\"\n",
+    )
+    .unwrap();
+    let (code, _, stderr) = kcl(&["run", "--mock"], &file);
+    assert_eq!(code, 0, "{stderr}");
+}
+
+#[test]
+fn ops_mock() {
+    let file = kcl_file("ops_mock", "sketch001 = startSketchOn('XY')\n");
+    let (code, stdout, stderr) = kcl(&["ops", "--mock"], &file);
+    assert_eq!(code, 0, "{stderr}");
+    let ops: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(ops[0]["name"], "startSketchOn");
+}
+
+#[test]
+fn export_needs_engine() {
+    let file = kcl_file("export_needs_engine", "x = 1\n");
+    let output = file.with_extension("stl");
+    let output = output.to_str().unwrap();
+
+    let (code, _, stderr) = kcl(&["export", "--format", "dwg", "-o", output], &file);
+    assert_eq!(code, 2);
+    assert!(
+        stderr.contains("possible values: fbx, glb, gltf, obj, ply, step, stl"),
+        "{stderr}"
+    );
+
+    let (code, _, stderr) = kcl(&["export", "--mock", "--format", "stl", "-o", output], &file);
+    assert_eq!(code, 2);
+    assert!(stderr.contains("can't be done with --mock"), "{stderr}");
+}
+
+#[test]
+fn type_mismatches_are_warnings() {
+    let file = kcl_file("type_mismatches_are_warnings", "width = 10mm\nx = sin(width)\n");
+    let (code, _, stderr) = kcl(&["run", "--mock"], &file);
+    assert_eq!(code, 0, "{stderr}");
+    assert!(stderr.contains("`sin` expects an angle, found a length"), "{stderr}");
+}
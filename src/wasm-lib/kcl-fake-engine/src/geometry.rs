@@ -0,0 +1,569 @@
+//! Planes, and the profiles sketched on them.
+//!
+//! Curves are sampled into polylines, finely enough that measurements of the solids made from them
+//! are accurate to a few parts in ten thousand.
+
+use std::f64::consts::TAU;
+
+use kittycad_modeling_cmds::{
+    length_unit::LengthUnit,
+    shared::{Angle, PathSegment, Point2d, Point3d},
+};
+use uuid::Uuid;
+
+pub(crate) type Point2 = [f64; 2];
+pub(crate) type Vector = [f64; 3];
+
+/// How many points a full circle is sampled into.
+const CIRCLE_SAMPLES: f64 = 256.0;
+/// How many points each Bezier curve is sampled into.
+const BEZIER_SAMPLES: usize = 32;
+/// Lengths shorter than this are treated as zero.
+const EPSILON: f64 = 1e-9;
+
+/// A position and orientation in the scene, which 2D sketch coordinates are relative to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Frame {
+    pub origin: Vector,
+    pub x_axis: Vector,
+    pub y_axis: Vector,
+}
+
+impl Frame {
+    pub fn new(origin: Vector, x_axis: Vector, y_axis: Vector) -> Self {
+        Self {
+            origin,
+            x_axis: normalize(x_axis),
+            y_axis: normalize(y_axis),
+        }
+    }
+
+    pub fn normal(&self) -> Vector {
+        normalize(cross(self.x_axis, self.y_axis))
+    }
+
+    /// Where a point in sketch coordinates is in the scene.
+    pub fn point(&self, [u, v]: Point2) -> Vector {
+        add(self.origin, add(scale(self.x_axis, u), scale(self.y_axis, v)))
+    }
+
+    /// Where a direction in sketch coordinates points in the scene.
+    pub fn direction(&self, [u, v]: Point2) -> Vector {
+        add(scale(self.x_axis, u), scale(self.y_axis, v))
+    }
+
+    /// The same frame moved by some offset.
+    pub fn translated(&self, by: Vector) -> Self {
+        Self {
+            origin: add(self.origin, by),
+            ..*self
+        }
+    }
+
+    /// The same frame facing the other way.
+    pub fn flipped(&self) -> Self {
+        Self {
+            y_axis: scale(self.y_axis, -1.0),
+            ..*self
+        }
+    }
+
+    /// The frame rotated about an axis through a point.
+    pub fn rotated(&self, center: Vector, axis: Vector, radians: f64) -> Self {
+        Self {
+            origin: add(center, rotate(sub(self.origin, center), axis, radians)),
+            x_axis: rotate(self.x_axis, axis, radians),
+            y_axis: rotate(self.y_axis, axis, radians),
+        }
+    }
+}
+
+/// A path sketched on a plane or face, in that surface's coordinates.
+#[derive(Debug, Clone)]
+pub(crate) struct Profile {
+    pub frame: Frame,
+    pub start: Point2,
+    pub segments: Vec<Segment>,
+    pub closed: bool,
+    /// Profiles cut out of this one.
+    pub holes: Vec<Profile>,
+    /// Which way the path was heading where it ended, for tangential arcs.
+    tangent: Point2,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Segment {
+    pub id: Uuid,
+    /// Whether the segment is a straight line, so the face swept from it is flat.
+    pub straight: bool,
+    /// Points along the segment after its start, ending at its end.
+    pub points: Vec<Point2>,
+}
+
+impl Profile {
+    pub fn new(frame: Frame) -> Self {
+        Self {
+            frame,
+            start: [0.0; 2],
+            segments: Vec::new(),
+            closed: false,
+            holes: Vec::new(),
+            tangent: [1.0, 0.0],
+        }
+    }
+
+    /// Where the next segment starts.
+    pub fn pen(&self) -> Point2 {
+        self.segments
+            .last()
+            .and_then(|segment| segment.points.last())
+            .copied()
+            .unwrap_or(self.start)
+    }
+
+    pub fn move_pen(&mut self, to: Point2) -> Result<(), String> {
+        if !self.segments.is_empty() {
+            return Err("Can't move the pen of a path which already has segments".to_owned());
+        }
+        self.start = to;
+        Ok(())
+    }
+
+    pub fn extend(&mut self, id: Uuid, segment: &PathSegment) -> Result<(), String> {
+        if self.closed {
+            return Err("Can't extend a path which has been closed".to_owned());
+        }
+        let pen = self.pen();
+        let relative_to = |relative: bool| if relative { pen } else { [0.0; 2] };
+        let (straight, points, tangent) = match segment {
+            PathSegment::Line { end, relative } => {
+                let end = add2(relative_to(*relative), xy(end));
+                (true, vec![end], normalize2(sub2(end, pen)))
+            }
+            PathSegment::Arc {
+                center,
+                radius,
+                start,
+                end,
+                relative,
+            } => {
+                let center = add2(relative_to(*relative), point2(center));
+                let start = radians(start);
+                let sweep = radians(end) - start;
+                let points = arc(center, radius.0, start, sweep);
+                (false, points, arc_tangent(start + sweep, sweep))
+            }
+            PathSegment::Bezier {
+                control1,
+                control2,
+                end,
+                relative,
+            } => {
+                let origin = relative_to(*relative);
+                let (c1, c2, end) = (
+                    add2(origin, xy(control1)),
+                    add2(origin, xy(control2)),
+                    add2(origin, xy(end)),
+                );
+                let points = bezier(pen, c1, c2, end);
+                let tangent = if distance2(end, c2) > EPSILON {
+                    sub2(end, c2)
+                } else {
+                    sub2(end, pen)
+                };
+                (false, points, normalize2(tangent))
+            }
+            PathSegment::TangentialArc { radius, offset } => {
+                let sweep = radians(offset);
+                // The center is to the left of the pen for counterclockwise arcs, and to the right otherwise.
+                let left = [-self.tangent[1], self.tangent[0]];
+                let side = if sweep > 0.0 { 1.0 } else { -1.0 };
+                let center = add2(pen, scale2(left, side * radius.0));
+                let start = angle_of(sub2(pen, center));
+                (
+                    false,
+                    arc(center, radius.0, start, sweep),
+                    arc_tangent(start + sweep, sweep),
+                )
+            }
+            PathSegment::TangentialArcTo { to, .. } => {
+                let end = add2(pen, xy(to));
+                self.tangential_arc_to(pen, end)
+            }
+            PathSegment::ArcTo {
+                interior,
+                end,
+                relative,
+            } => {
+                let origin = relative_to(*relative);
+                let (interior, end) = (add2(origin, xy(interior)), add2(origin, xy(end)));
+                match circumcenter(pen, interior, end) {
+                    Some(center) => {
+                        let start = angle_of(sub2(pen, center));
+                        let mut sweep = (angle_of(sub2(end, center)) - start).rem_euclid(TAU);
+                        // Go the way around the circle which passes through the interior point.
+                        if (angle_of(sub2(interior, center)) - start).rem_euclid(TAU) > sweep {
+                            sweep -= TAU;
+                        }
+                        let radius = distance2(pen, center);
+                        (
+                            false,
+                            arc(center, radius, start, sweep),
+                            arc_tangent(start + sweep, sweep),
+                        )
+                    }
+                    None => (true, vec![end], normalize2(sub2(end, pen))),
+                }
+            }
+        };
+        if !points.iter().all(|[x, y]| x.is_finite() && y.is_finite()) {
+            return Err(format!("Segment {id} has no finite geometry"));
+        }
+        self.segments.push(Segment { id, straight, points });
+        self.tangent = tangent;
+        Ok(())
+    }
+
+    /// Close the path with a straight line back to its start, if it doesn't end there already.
+    pub fn close(&mut self, id: Uuid) -> Result<(), String> {
+        if self.closed {
+            return Err("The path has already been closed".to_owned());
+        }
+        if self.segments.is_empty() {
+            return Err("Can't close a path with no segments".to_owned());
+        }
+        if distance2(self.pen(), self.start) > EPSILON {
+            self.segments.push(Segment {
+                id,
+                straight: true,
+                points: vec![self.start],
+            });
+        }
+        self.closed = true;
+        Ok(())
+    }
+
+    /// The points around the profile, starting at its start.
+    pub fn outline(&self) -> impl Iterator<Item = Point2> + Clone + '_ {
+        std::iter::once(self.start).chain(self.segments.iter().flat_map(|segment| segment.points.iter().copied()))
+    }
+
+    /// The area enclosed by the profile less its holes, and the centroid of that area.
+    pub fn area_and_centroid(&self) -> (f64, Point2) {
+        let (mut area, mut moment) = signed_area_and_moment(self.outline());
+        // Holes take away area whichever way they go around.
+        if area < 0.0 {
+            (area, moment) = (-area, scale2(moment, -1.0));
+        }
+        for hole in &self.holes {
+            let (hole_area, hole_moment) = signed_area_and_moment(hole.outline());
+            let side = hole_area.signum();
+            area -= hole_area * side;
+            moment = sub2(moment, scale2(hole_moment, side));
+        }
+        if area.abs() < EPSILON {
+            return (0.0, self.start);
+        }
+        (area, scale2(moment, 1.0 / area))
+    }
+
+    /// The length around the profile and its holes.
+    pub fn perimeter(&self) -> f64 {
+        let own: f64 = self
+            .outline()
+            .zip(self.outline().skip(1))
+            .map(|(a, b)| distance2(a, b))
+            .sum();
+        own + self.holes.iter().map(Profile::perimeter).sum::<f64>()
+    }
+
+    fn tangential_arc_to(&self, pen: Point2, end: Point2) -> (bool, Vec<Point2>, Point2) {
+        let chord = sub2(end, pen);
+        let left = [-self.tangent[1], self.tangent[0]];
+        // The center is on the line through the pen perpendicular to the tangent, equidistant from
+        // the pen and the end.
+        let along_left = dot2(chord, left);
+        if along_left.abs() < EPSILON {
+            return (true, vec![end], normalize2(chord));
+        }
+        let signed_radius = dot2(chord, chord) / (2.0 * along_left);
+        let center = add2(pen, scale2(left, signed_radius));
+        let start = angle_of(sub2(pen, center));
+        let sweep = if signed_radius > 0.0 {
+            (angle_of(sub2(end, center)) - start).rem_euclid(TAU)
+        } else {
+            -(start - angle_of(sub2(end, center))).rem_euclid(TAU)
+        };
+        (
+            false,
+            arc(center, signed_radius.abs(), start, sweep),
+            arc_tangent(start + sweep, sweep),
+        )
+    }
+}
+
+/// The signed area enclosed by a polyline, closed back to its first point, and its first moment of area.
+fn signed_area_and_moment(points: impl Iterator<Item = Point2> + Clone) -> (f64, Point2) {
+    let first = points.clone().next();
+    let (mut area, mut mx, mut my) = (0.0, 0.0, 0.0);
+    let mut previous = None;
+    for p in points.chain(first) {
+        if let Some(q) = previous {
+            let [x0, y0]: Point2 = q;
+            let [x1, y1] = p;
+            let cross = x0 * y1 - x1 * y0;
+            area += cross;
+            mx += (x0 + x1) * cross;
+            my += (y0 + y1) * cross;
+        }
+        previous = Some(p);
+    }
+    (area / 2.0, [mx / 6.0, my / 6.0])
+}
+
+/// Points along an arc after its start, ending at its end.
+fn arc(center: Point2, radius: f64, start: f64, sweep: f64) -> Vec<Point2> {
+    let n = ((sweep.abs() / TAU) * CIRCLE_SAMPLES).ceil().max(1.0) as usize;
+    (1..=n)
+        .map(|i| {
+            let angle = start + sweep * (i as f64 / n as f64);
+            [center[0] + radius * angle.cos(), center[1] + radius * angle.sin()]
+        })
+        .collect()
+}
+
+/// Which way an arc is heading where it ends.
+fn arc_tangent(end: f64, sweep: f64) -> Point2 {
+    let side = if sweep >= 0.0 { 1.0 } else { -1.0 };
+    [-end.sin() * side, end.cos() * side]
+}
+
+fn bezier(p0: Point2, p1: Point2, p2: Point2, p3: Point2) -> Vec<Point2> {
+    (1..=BEZIER_SAMPLES)
+        .map(|i| {
+            let t = i as f64 / BEZIER_SAMPLES as f64;
+            let u = 1.0 - t;
+            let weights = [u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t];
+            [p0, p1, p2, p3]
+                .iter()
+                .zip(weights)
+                .fold([0.0; 2], |sum, (p, w)| add2(sum, scale2(*p, w)))
+        })
+        .collect()
+}
+
+/// The center of the circle through three points, unless they're in a line.
+fn circumcenter([ax, ay]: Point2, [bx, by]: Point2, [cx, cy]: Point2) -> Option<Point2> {
+    let d = 2.0 * (ax * (by - cy) + bx * (cy - ay) + cx * (ay - by));
+    if d.abs() < EPSILON {
+        return None;
+    }
+    let (a2, b2, c2) = (ax * ax + ay * ay, bx * bx + by * by, cx * cx + cy * cy);
+    Some([
+        (a2 * (by - cy) + b2 * (cy - ay) + c2 * (ay - by)) / d,
+        (a2 * (cx - bx) + b2 * (ax - cx) + c2 * (bx - ax)) / d,
+    ])
+}
+
+pub(crate) fn vector(p: &Point3d<f64>) -> Vector {
+    [p.x, p.y, p.z]
+}
+
+pub(crate) fn position(p: &Point3d<LengthUnit>) -> Vector {
+    [p.x.0, p.y.0, p.z.0]
+}
+
+pub(crate) fn xy(p: &Point3d<LengthUnit>) -> Point2 {
+    [p.x.0, p.y.0]
+}
+
+fn point2(p: &Point2d<LengthUnit>) -> Point2 {
+    [p.x.0, p.y.0]
+}
+
+pub(crate) fn radians(angle: &Angle) -> f64 {
+    angle.to_degrees().to_radians()
+}
+
+fn angle_of([x, y]: Point2) -> f64 {
+    y.atan2(x)
+}
+
+pub(crate) fn add(a: Vector, b: Vector) -> Vector {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+pub(crate) fn sub(a: Vector, b: Vector) -> Vector {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+pub(crate) fn scale(a: Vector, s: f64) -> Vector {
+    [a[0] * s, a[1] * s, a[2] * s]
+}
+
+pub(crate) fn dot(a: Vector, b: Vector) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: Vector, b: Vector) -> Vector {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+pub(crate) fn normalize(a: Vector) -> Vector {
+    let length = dot(a, a).sqrt();
+    if length < EPSILON {
+        a
+    } else {
+        scale(a, 1.0 / length)
+    }
+}
+
+/// Rotate a vector about an axis through the origin, by Rodrigues' formula.
+fn rotate(v: Vector, axis: Vector, radians: f64) -> Vector {
+    let k = normalize(axis);
+    let (sin, cos) = radians.sin_cos();
+    add(
+        add(scale(v, cos), scale(cross(k, v), sin)),
+        scale(k, dot(k, v) * (1.0 - cos)),
+    )
+}
+
+fn add2(a: Point2, b: Point2) -> Point2 {
+    [a[0] + b[0], a[1] + b[1]]
+}
+
+fn sub2(a: Point2, b: Point2) -> Point2 {
+    [a[0] - b[0], a[1] - b[1]]
+}
+
+fn scale2(a: Point2, s: f64) -> Point2 {
+    [a[0] * s, a[1] * s]
+}
+
+fn dot2(a: Point2, b: Point2) -> f64 {
+    a[0] * b[0] + a[1] * b[1]
+}
+
+fn distance2(a: Point2, b: Point2) -> f64 {
+    dot2(sub2(a, b), sub2(a, b)).sqrt()
+}
+
+fn normalize2(a: Point2) -> Point2 {
+    let length = dot2(a, a).sqrt();
+    if length < EPSILON {
+        a
+    } else {
+        scale2(a, 1.0 / length)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use kittycad_modeling_cmds::units::UnitAngle;
+
+    use super::*;
+
+    const XY: Frame = Frame {
+        origin: [0.0; 3],
+        x_axis: [1.0, 0.0, 0.0],
+        y_axis: [0.0, 1.0, 0.0],
+    };
+
+    fn at(x: f64, y: f64) -> Point3d<LengthUnit> {
+        Point3d {
+            x: LengthUnit(x),
+            y: LengthUnit(y),
+            z: LengthUnit(0.0),
+        }
+    }
+
+    fn degrees(value: f64) -> Angle {
+        Angle {
+            unit: UnitAngle::Degrees,
+            value,
+        }
+    }
+
+    #[test]
+    fn square_with_a_hole() {
+        let mut square = Profile::new(XY);
+        for (x, y) in [(10.0, 0.0), (10.0, 10.0), (0.0, 10.0)] {
+            square
+                .extend(
+                    Uuid::new_v4(),
+                    &PathSegment::Line {
+                        end: at(x, y),
+                        relative: false,
+                    },
+                )
+                .unwrap();
+        }
+        square.close(Uuid::new_v4()).unwrap();
+        assert_eq!(square.segments.len(), 4);
+        assert_eq!(square.perimeter(), 40.0);
+        assert_eq!(square.area_and_centroid(), (100.0, [5.0, 5.0]));
+
+        let mut hole = Profile::new(XY);
+        hole.move_pen([2.0, 1.0]).unwrap();
+        hole.extend(
+            Uuid::new_v4(),
+            &PathSegment::Arc {
+                center: Point2d {
+                    x: LengthUnit(1.0),
+                    y: LengthUnit(1.0),
+                },
+                radius: LengthUnit(1.0),
+                start: degrees(0.0),
+                end: degrees(360.0),
+                relative: false,
+            },
+        )
+        .unwrap();
+        hole.close(Uuid::new_v4()).unwrap();
+        assert_eq!(hole.segments.len(), 1, "the circle already ends at its start");
+        square.holes.push(hole);
+
+        let (area, [x, _]) = square.area_and_centroid();
+        assert!((area - (100.0 - std::f64::consts::PI)).abs() < 1e-3, "{area}");
+        assert!(x > 5.0, "the hole is left of center, so the centroid moves right");
+    }
+
+    #[test]
+    fn tangential_arcs_continue_the_path() {
+        let mut path = Profile::new(XY);
+        path.extend(
+            Uuid::new_v4(),
+            &PathSegment::Line {
+                end: at(10.0, 0.0),
+                relative: false,
+            },
+        )
+        .unwrap();
+        // A counterclockwise half turn ends up above where it starts, heading back.
+        path.extend(
+            Uuid::new_v4(),
+            &PathSegment::TangentialArc {
+                radius: LengthUnit(5.0),
+                offset: degrees(180.0),
+            },
+        )
+        .unwrap();
+        let [x, y] = path.pen();
+        assert!((x - 10.0).abs() < 1e-9 && (y - 10.0).abs() < 1e-9, "{x}, {y}");
+        // Its end is relative to the pen.
+        path.extend(
+            Uuid::new_v4(),
+            &PathSegment::TangentialArcTo {
+                to: at(-10.0, -10.0),
+                angle_snap_increment: None,
+            },
+        )
+        .unwrap();
+        let [x, y] = path.pen();
+        assert!(x.abs() < 1e-9 && y.abs() < 1e-9, "{x}, {y}");
+    }
+}
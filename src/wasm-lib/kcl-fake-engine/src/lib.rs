@@ -0,0 +1,160 @@
+//! A fake modeling engine, for running KCL without the KittyCAD API.
+//!
+//! It accepts WebSocket connections just like the engine's `/ws/modeling/commands` endpoint, and
+//! answers modeling commands and batches of them over the same protocol. It keeps track of the
+//! planes, paths, solids, faces and edges each command makes, so queries about faces and the edges
+//! between them get answers which agree with each other, and extrusions are measured exactly.
+//!
+//! Nothing is rendered, so snapshots are blank. Transforms and patterns don't move anything, and
+//! fillets and chamfers are checked but not made. Importing and exporting aren't supported.
+use std::{collections::HashMap, net::SocketAddr};
+
+use futures::{SinkExt, StreamExt};
+use kcmc::{
+    ok_response::OkModelingCmdResponse,
+    websocket::{
+        ApiError, BatchResponse, ErrorCode, FailureWebSocketResponse, ModelingBatch, ModelingCmdReq,
+        OkWebSocketResponseData, SuccessWebSocketResponse, WebSocketRequest, WebSocketResponse,
+    },
+};
+use kittycad_modeling_cmds as kcmc;
+use tokio::{
+    net::{TcpListener, TcpStream},
+    task::JoinHandle,
+};
+use tokio_tungstenite::tungstenite::Message as WsMsg;
+use uuid::Uuid;
+
+use crate::scene::Scene;
+
+mod geometry;
+mod scene;
+
+/// A fake engine, listening for connections until it's dropped.
+#[derive(Debug)]
+pub struct FakeEngine {
+    addr: SocketAddr,
+    server: JoinHandle<()>,
+}
+
+impl FakeEngine {
+    /// Start listening for connections. Each one gets a scene of its own.
+    pub async fn start(listen_on: SocketAddr) -> anyhow::Result<Self> {
+        let listener = TcpListener::bind(listen_on).await?;
+        let addr = listener.local_addr()?;
+        let server = tokio::spawn(async move {
+            loop {
+                let Ok((stream, peer)) = listener.accept().await else {
+                    continue;
+                };
+                tokio::spawn(async move {
+                    if let Err(e) = serve(stream).await {
+                        eprintln!("Connection from {peer} failed: {e}");
+                    }
+                });
+            }
+        });
+        Ok(Self { addr, server })
+    }
+
+    /// Where the engine is listening, including the port the OS chose if it was asked for port 0.
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// The address to give KCL's executor, in place of the KittyCAD API's.
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+}
+
+impl Drop for FakeEngine {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+/// Answer the requests from one client until it disconnects.
+async fn serve(stream: TcpStream) -> anyhow::Result<()> {
+    let (mut write, mut read) = tokio_tungstenite::accept_async(stream).await?.split();
+    let mut scene = Scene::default();
+    while let Some(msg) = read.next().await {
+        let request: Result<WebSocketRequest, String> = match msg? {
+            WsMsg::Text(text) => serde_json::from_str(&text).map_err(|e| e.to_string()),
+            // The engine accepts BSON, which is how files to import are sent.
+            WsMsg::Binary(bytes) => bson::from_slice(&bytes).map_err(|e| e.to_string()),
+            WsMsg::Close(_) => break,
+            _ => continue,
+        };
+        let response = match request {
+            Ok(request) => respond(&mut scene, request),
+            Err(message) => Some(failure(None, ErrorCode::InvalidJson, message)),
+        };
+        if let Some(response) = response {
+            write.send(WsMsg::Text(serde_json::to_string(&response)?)).await?;
+        }
+    }
+    Ok(())
+}
+
+fn respond(scene: &mut Scene, request: WebSocketRequest) -> Option<WebSocketResponse> {
+    match request {
+        WebSocketRequest::ModelingCmdReq(ModelingCmdReq { cmd, cmd_id }) => {
+            let id = *cmd_id.as_ref();
+            let response = match scene.handle(id, &cmd) {
+                Ok(modeling_response) => success(Some(id), OkWebSocketResponseData::Modeling { modeling_response }),
+                Err(message) => failure(Some(id), ErrorCode::BadRequest, message),
+            };
+            Some(response)
+        }
+        WebSocketRequest::ModelingCmdBatchReq(ModelingBatch {
+            requests,
+            batch_id,
+            responses,
+        }) => {
+            let mut results = HashMap::with_capacity(requests.len());
+            for ModelingCmdReq { cmd, cmd_id } in requests {
+                match scene.handle(*cmd_id.as_ref(), &cmd) {
+                    Ok(response) => {
+                        let response = if responses {
+                            response
+                        } else {
+                            OkModelingCmdResponse::Empty {}
+                        };
+                        results.insert(cmd_id, BatchResponse::Success { response });
+                    }
+                    // Like the engine, stop at the first command which fails.
+                    Err(message) => {
+                        let errors = vec![ApiError {
+                            error_code: ErrorCode::BadRequest,
+                            message,
+                        }];
+                        results.insert(cmd_id, BatchResponse::Failure { errors });
+                        break;
+                    }
+                }
+            }
+            let resp = OkWebSocketResponseData::ModelingBatch { responses: results };
+            Some(success(Some(*batch_id.as_ref()), resp))
+        }
+        WebSocketRequest::Ping {} => Some(success(None, OkWebSocketResponseData::Pong {})),
+        // There's no video stream to negotiate or report on.
+        _ => None,
+    }
+}
+
+fn success(request_id: Option<Uuid>, resp: OkWebSocketResponseData) -> WebSocketResponse {
+    WebSocketResponse::Success(SuccessWebSocketResponse {
+        success: true,
+        request_id,
+        resp,
+    })
+}
+
+fn failure(request_id: Option<Uuid>, error_code: ErrorCode, message: String) -> WebSocketResponse {
+    WebSocketResponse::Failure(FailureWebSocketResponse {
+        success: false,
+        request_id,
+        errors: vec![ApiError { error_code, message }],
+    })
+}
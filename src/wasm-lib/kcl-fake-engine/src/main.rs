@@ -0,0 +1,18 @@
+//! Runs a fake modeling engine, which KCL can be executed against without the KittyCAD API.
+//! Point KCL at it by setting $ZOO_HOST to the URL it prints.
+use std::net::SocketAddr;
+
+use kcl_fake_engine::FakeEngine;
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let mut pargs = pico_args::Arguments::from_env();
+    let listen_on: SocketAddr = pargs
+        .opt_value_from_str("--listen-on")?
+        .unwrap_or("127.0.0.1:8080".parse().unwrap());
+
+    let engine = FakeEngine::start(listen_on).await?;
+    println!("Fake engine listening at {}", engine.url());
+    tokio::signal::ctrl_c().await?;
+    Ok(())
+}
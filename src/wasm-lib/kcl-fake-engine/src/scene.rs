@@ -0,0 +1,854 @@
+//! What the fake engine knows about the scene, and how it answers each modeling command.
+//!
+//! Each solid is kept as the list of features which built it: the profile swept to make it, and
+//! any profiles extruded from its faces afterwards. A feature has a wall for each segment of its
+//! profile, and caps at its ends. Each wall knows the edge opposite the segment it was swept from,
+//! and the edge it shares with the next wall, so adjacency queries are answered consistently.
+
+use std::{collections::HashMap, f64::consts::TAU, io::Cursor};
+
+use kcmc::{
+    base64::Base64Data,
+    each_cmd as mcmd,
+    length_unit::LengthUnit,
+    ok_response::{output, OkModelingCmdResponse},
+    shared::{ExtrusionFaceCapType, Point3d},
+    units::UnitLength,
+    ImageFormat, ModelingCmd,
+};
+use kittycad_modeling_cmds as kcmc;
+use uuid::Uuid;
+
+use crate::geometry::{add, position, radians, scale, sub, vector, xy, Frame, Profile, Vector};
+
+/// How big snapshots are, in pixels.
+const SNAPSHOT_SIZE: u32 = 64;
+
+#[derive(Debug)]
+pub(crate) struct Scene {
+    /// The units of every length in commands and responses.
+    units: UnitLength,
+    planes: HashMap<Uuid, Frame>,
+    faces: HashMap<Uuid, Face>,
+    paths: HashMap<Uuid, Path>,
+    solids: HashMap<Uuid, Solid>,
+    /// What's being sketched on, while in sketch mode.
+    sketching_on: Option<Surface>,
+}
+
+impl Default for Scene {
+    fn default() -> Self {
+        Self {
+            units: UnitLength::Millimeters,
+            planes: Default::default(),
+            faces: Default::default(),
+            paths: Default::default(),
+            solids: Default::default(),
+            sketching_on: None,
+        }
+    }
+}
+
+/// A plane, or the face of a solid, to sketch on.
+#[derive(Debug, Clone, Copy)]
+struct Surface {
+    frame: Frame,
+    /// The solid whose face this is.
+    solid: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Face {
+    /// The solid the face is part of. Faces of closed paths aren't part of any solid.
+    solid: Option<Uuid>,
+    /// The face's plane, unless it's curved.
+    frame: Option<Frame>,
+}
+
+#[derive(Debug, Clone)]
+struct Path {
+    profile: Profile,
+    /// The solid whose face the path was sketched on.
+    on_solid: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Solid {
+    features: Vec<Feature>,
+}
+
+#[derive(Debug, Clone)]
+struct Feature {
+    walls: Vec<Wall>,
+    /// Whether the last wall meets the first.
+    closed: bool,
+    start_cap: Option<Uuid>,
+    end_cap: Option<Uuid>,
+    /// How the feature changed the solid's measurements. Only extrusions are measured.
+    measures: Option<Measures>,
+}
+
+/// The face swept from a segment of a profile.
+#[derive(Debug, Clone, Copy)]
+struct Wall {
+    /// The segment the wall was swept from, which is the edge it shares with the start cap.
+    curve_id: Uuid,
+    face_id: Uuid,
+    /// The edge the wall shares with the end cap.
+    opposite_edge: Uuid,
+    /// The edge the wall shares with the next wall.
+    next_edge: Uuid,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Measures {
+    volume: f64,
+    surface_area: f64,
+    /// The volume times the center of mass, so measurements of several features can be added up.
+    moment: Vector,
+}
+
+impl std::ops::Add for Measures {
+    type Output = Measures;
+
+    fn add(self, other: Measures) -> Measures {
+        Measures {
+            volume: self.volume + other.volume,
+            surface_area: self.surface_area + other.surface_area,
+            moment: add(self.moment, other.moment),
+        }
+    }
+}
+
+/// How a profile was swept into a solid.
+enum Sweep {
+    Extrude { distance: f64 },
+    Revolve { center: Vector, axis: Vector, angle: f64 },
+    Along,
+}
+
+impl Solid {
+    fn feature_with_edge(&self, edge_id: Uuid) -> Option<&Feature> {
+        self.features
+            .iter()
+            .find(|feature| feature.walls.iter().any(|wall| wall.curve_id == edge_id))
+    }
+
+    fn has_edge(&self, edge_id: Uuid) -> bool {
+        self.features
+            .iter()
+            .flat_map(|feature| &feature.walls)
+            .any(|wall| wall.curve_id == edge_id || wall.opposite_edge == edge_id || wall.next_edge == edge_id)
+    }
+
+    /// The wall swept from a segment, and the walls before and after it.
+    fn walls_around(&self, curve_id: Uuid) -> Option<(Option<Wall>, Wall, Option<Wall>)> {
+        self.features.iter().find_map(|feature| {
+            let i = feature.walls.iter().position(|wall| wall.curve_id == curve_id)?;
+            let walls = &feature.walls;
+            let previous = match i {
+                0 => walls.last().filter(|_| feature.closed),
+                _ => walls.get(i - 1),
+            };
+            let next = walls.get(i + 1).or_else(|| walls.first().filter(|_| feature.closed));
+            Some((previous.copied(), walls[i], next.copied()))
+        })
+    }
+}
+
+impl Scene {
+    /// Carry out a command, and work out the engine's response to it.
+    pub(crate) fn handle(&mut self, id: Uuid, cmd: &ModelingCmd) -> Result<OkModelingCmdResponse, String> {
+        let response = match cmd {
+            ModelingCmd::SceneClearAll(_) => {
+                *self = Scene {
+                    units: self.units,
+                    ..Default::default()
+                };
+                OkModelingCmdResponse::Empty {}
+            }
+            ModelingCmd::SetSceneUnits(mcmd::SetSceneUnits { unit }) => {
+                self.units = *unit;
+                OkModelingCmdResponse::Empty {}
+            }
+            ModelingCmd::MakePlane(mcmd::MakePlane {
+                origin, x_axis, y_axis, ..
+            }) => {
+                let frame = Frame::new(position(origin), vector(x_axis), vector(y_axis));
+                self.planes.insert(id, frame);
+                OkModelingCmdResponse::Empty {}
+            }
+            ModelingCmd::EnableSketchMode(mcmd::EnableSketchMode { entity_id, .. }) => {
+                self.sketching_on = Some(self.surface(*entity_id)?);
+                OkModelingCmdResponse::Empty {}
+            }
+            ModelingCmd::SketchModeDisable(_) => {
+                self.sketching_on = None;
+                OkModelingCmdResponse::Empty {}
+            }
+            ModelingCmd::StartPath(_) => {
+                let surface = self
+                    .sketching_on
+                    .ok_or_else(|| "Paths can only be started in sketch mode".to_owned())?;
+                let path = Path {
+                    profile: Profile::new(surface.frame),
+                    on_solid: surface.solid,
+                };
+                self.paths.insert(id, path);
+                OkModelingCmdResponse::Empty {}
+            }
+            ModelingCmd::MovePathPen(mcmd::MovePathPen { path, to }) => {
+                self.path_mut(*path.as_ref())?.profile.move_pen(xy(to))?;
+                OkModelingCmdResponse::Empty {}
+            }
+            ModelingCmd::ExtendPath(mcmd::ExtendPath { path, segment }) => {
+                self.path_mut(*path.as_ref())?.profile.extend(id, segment)?;
+                OkModelingCmdResponse::Empty {}
+            }
+            ModelingCmd::ClosePath(mcmd::ClosePath { path_id }) => {
+                let profile = &mut self.path_mut(*path_id)?.profile;
+                profile.close(id)?;
+                let face_id = Uuid::new_v4();
+                let face = Face {
+                    solid: None,
+                    frame: Some(profile.frame),
+                };
+                self.faces.insert(face_id, face);
+                OkModelingCmdResponse::ClosePath(output::ClosePath { face_id })
+            }
+            ModelingCmd::Solid2dAddHole(mcmd::Solid2dAddHole { object_id, hole_id }) => {
+                let hole = self.path(*hole_id)?.profile.clone();
+                self.path_mut(*object_id)?.profile.holes.push(hole);
+                OkModelingCmdResponse::Empty {}
+            }
+            ModelingCmd::Extrude(mcmd::Extrude { target, distance, .. }) => {
+                let sweep = Sweep::Extrude { distance: distance.0 };
+                self.sweep(*target.as_ref(), sweep)?;
+                OkModelingCmdResponse::Empty {}
+            }
+            ModelingCmd::Revolve(mcmd::Revolve {
+                target,
+                origin,
+                axis,
+                axis_is_2d,
+                angle,
+                ..
+            }) => {
+                let frame = self.path(*target.as_ref())?.profile.frame;
+                let (center, axis) = if *axis_is_2d {
+                    (frame.point(xy(origin)), frame.direction([axis.x, axis.y]))
+                } else {
+                    (position(origin), vector(axis))
+                };
+                let angle = radians(angle);
+                self.sweep(*target.as_ref(), Sweep::Revolve { center, axis, angle })?;
+                OkModelingCmdResponse::Empty {}
+            }
+            ModelingCmd::RevolveAboutEdge(mcmd::RevolveAboutEdge {
+                target, edge_id, angle, ..
+            }) => {
+                let (center, axis) = self.segment_line(*edge_id)?;
+                let angle = radians(angle);
+                self.sweep(*target.as_ref(), Sweep::Revolve { center, axis, angle })?;
+                OkModelingCmdResponse::Empty {}
+            }
+            ModelingCmd::Sweep(mcmd::Sweep { target, .. }) => {
+                self.sweep(*target.as_ref(), Sweep::Along)?;
+                OkModelingCmdResponse::Empty {}
+            }
+            ModelingCmd::Loft(mcmd::Loft { section_ids, .. }) => {
+                self.loft(id, section_ids)?;
+                OkModelingCmdResponse::Loft(output::Loft { solid_id: id })
+            }
+            ModelingCmd::Solid3dGetExtrusionFaceInfo(mcmd::Solid3dGetExtrusionFaceInfo { object_id, edge_id }) => {
+                let feature = self
+                    .solid(*object_id)?
+                    .feature_with_edge(*edge_id)
+                    .ok_or_else(|| not_on_solid(*edge_id, *object_id))?;
+                let walls = feature.walls.iter().map(|wall| output::ExtrusionFaceInfo {
+                    curve_id: Some(wall.curve_id),
+                    face_id: Some(wall.face_id),
+                    cap: ExtrusionFaceCapType::None,
+                });
+                let caps = [
+                    (feature.start_cap, ExtrusionFaceCapType::Bottom),
+                    (feature.end_cap, ExtrusionFaceCapType::Top),
+                ]
+                .into_iter()
+                .filter_map(|(face_id, cap)| {
+                    Some(output::ExtrusionFaceInfo {
+                        curve_id: None,
+                        face_id: Some(face_id?),
+                        cap,
+                    })
+                });
+                let faces = walls.chain(caps).collect();
+                OkModelingCmdResponse::Solid3dGetExtrusionFaceInfo(output::Solid3dGetExtrusionFaceInfo { faces })
+            }
+            ModelingCmd::Solid3dGetOppositeEdge(mcmd::Solid3dGetOppositeEdge {
+                object_id,
+                edge_id,
+                face_id,
+            }) => {
+                let (_, wall, _) = self.walls_around(*object_id, *edge_id, *face_id)?;
+                OkModelingCmdResponse::Solid3dGetOppositeEdge(output::Solid3dGetOppositeEdge {
+                    edge: wall.opposite_edge,
+                })
+            }
+            ModelingCmd::Solid3dGetNextAdjacentEdge(mcmd::Solid3dGetNextAdjacentEdge {
+                object_id,
+                edge_id,
+                face_id,
+            }) => {
+                let (_, wall, next) = self.walls_around(*object_id, *edge_id, *face_id)?;
+                OkModelingCmdResponse::Solid3dGetNextAdjacentEdge(output::Solid3dGetNextAdjacentEdge {
+                    edge: next.map(|_| wall.next_edge),
+                })
+            }
+            ModelingCmd::Solid3dGetPrevAdjacentEdge(mcmd::Solid3dGetPrevAdjacentEdge {
+                object_id,
+                edge_id,
+                face_id,
+            }) => {
+                let (previous, _, _) = self.walls_around(*object_id, *edge_id, *face_id)?;
+                OkModelingCmdResponse::Solid3dGetPrevAdjacentEdge(output::Solid3dGetPrevAdjacentEdge {
+                    edge: previous.map(|wall| wall.next_edge),
+                })
+            }
+            ModelingCmd::Solid3dFilletEdge(mcmd::Solid3dFilletEdge { object_id, edge_id, .. }) => {
+                // Fillets and chamfers are only checked, not modelled: they don't change any IDs.
+                if !self.solid(*object_id)?.has_edge(*edge_id) {
+                    return Err(not_on_solid(*edge_id, *object_id));
+                }
+                OkModelingCmdResponse::Empty {}
+            }
+            ModelingCmd::FaceIsPlanar(mcmd::FaceIsPlanar { object_id }) => {
+                let face = self
+                    .faces
+                    .get(object_id)
+                    .ok_or_else(|| format!("No face with ID {object_id}"))?;
+                let point = |[x, y, z]: Vector| Point3d { x, y, z };
+                OkModelingCmdResponse::FaceIsPlanar(output::FaceIsPlanar {
+                    origin: face.frame.map(|frame| point(frame.origin).map(LengthUnit)),
+                    x_axis: face.frame.map(|frame| point(frame.x_axis)),
+                    y_axis: face.frame.map(|frame| point(frame.y_axis)),
+                    z_axis: face.frame.map(|frame| point(frame.normal())),
+                })
+            }
+            ModelingCmd::EntityLinearPatternTransform(mcmd::EntityLinearPatternTransform {
+                entity_id,
+                transform,
+                transforms,
+            }) => {
+                let copies = if transforms.is_empty() {
+                    transform.len()
+                } else {
+                    transforms.len()
+                };
+                let entity_ids = self.copy(*entity_id, copies)?;
+                OkModelingCmdResponse::EntityLinearPatternTransform(output::EntityLinearPatternTransform { entity_ids })
+            }
+            ModelingCmd::EntityLinearPattern(mcmd::EntityLinearPattern {
+                entity_id,
+                num_repetitions,
+                ..
+            }) => {
+                let entity_ids = self.copy(*entity_id, *num_repetitions as usize)?;
+                OkModelingCmdResponse::EntityLinearPattern(output::EntityLinearPattern { entity_ids })
+            }
+            ModelingCmd::EntityCircularPattern(mcmd::EntityCircularPattern {
+                entity_id,
+                num_repetitions,
+                ..
+            }) => {
+                let entity_ids = self.copy(*entity_id, *num_repetitions as usize)?;
+                OkModelingCmdResponse::EntityCircularPattern(output::EntityCircularPattern { entity_ids })
+            }
+            ModelingCmd::Volume(mcmd::Volume {
+                entity_ids,
+                output_unit,
+            }) => {
+                let cubic_meters = self.measure(entity_ids)?.volume * self.meters().powi(3);
+                OkModelingCmdResponse::Volume(output::Volume {
+                    volume: cubic_meters / output_unit.as_measurement(1.0).as_cubic_meters(),
+                    output_unit: *output_unit,
+                })
+            }
+            ModelingCmd::SurfaceArea(mcmd::SurfaceArea {
+                entity_ids,
+                output_unit,
+            }) => {
+                let square_meters = self.measure(entity_ids)?.surface_area * self.meters().powi(2);
+                OkModelingCmdResponse::SurfaceArea(output::SurfaceArea {
+                    surface_area: square_meters / output_unit.as_measurement(1.0).as_square_meters(),
+                    output_unit: *output_unit,
+                })
+            }
+            ModelingCmd::CenterOfMass(mcmd::CenterOfMass {
+                entity_ids,
+                output_unit,
+            }) => {
+                let measures = self.measure(entity_ids)?;
+                if measures.volume == 0.0 {
+                    return Err("Solids with no volume have no center of mass".to_owned());
+                }
+                let factor = self.meters() / output_unit.as_measurement(1.0).as_meters();
+                let [x, y, z] = scale(measures.moment, factor / measures.volume);
+                OkModelingCmdResponse::CenterOfMass(output::CenterOfMass {
+                    center_of_mass: Point3d { x, y, z },
+                    output_unit: *output_unit,
+                })
+            }
+            ModelingCmd::Mass(mcmd::Mass {
+                entity_ids,
+                material_density,
+                material_density_unit,
+                output_unit,
+            }) => {
+                let cubic_meters = self.measure(entity_ids)?.volume * self.meters().powi(3);
+                let kilograms = cubic_meters
+                    * material_density_unit
+                        .as_measurement(*material_density)
+                        .as_kilograms_per_cubic_meter();
+                OkModelingCmdResponse::Mass(output::Mass {
+                    mass: kilograms / output_unit.as_measurement(1.0).as_kilograms(),
+                    output_unit: *output_unit,
+                })
+            }
+            ModelingCmd::TakeSnapshot(mcmd::TakeSnapshot { format }) => {
+                let ImageFormat::Png = format else {
+                    return Err(format!("Snapshots can only be taken as PNG, not {format:?}"));
+                };
+                OkModelingCmdResponse::TakeSnapshot(output::TakeSnapshot {
+                    contents: Base64Data(blank_png()?),
+                })
+            }
+            ModelingCmd::Export(_) => return Err("The fake engine can't export models".to_owned()),
+            ModelingCmd::ImportFiles(_) => return Err("The fake engine can't import models".to_owned()),
+            // Everything else only changes how the scene looks, which isn't modelled.
+            _ => OkModelingCmdResponse::Empty {},
+        };
+        Ok(response)
+    }
+
+    /// How long the scene's unit of length is, in meters.
+    fn meters(&self) -> f64 {
+        self.units.as_measurement(1.0).as_meters()
+    }
+
+    fn surface(&self, id: Uuid) -> Result<Surface, String> {
+        if let Some(frame) = self.planes.get(&id) {
+            return Ok(Surface {
+                frame: *frame,
+                solid: None,
+            });
+        }
+        match self.faces.get(&id) {
+            Some(Face {
+                solid,
+                frame: Some(frame),
+            }) => Ok(Surface {
+                frame: *frame,
+                solid: *solid,
+            }),
+            Some(_) => Err(format!("Face {id} isn't planar, so it can't be sketched on")),
+            None => Err(format!("No plane or face with ID {id}")),
+        }
+    }
+
+    fn path(&self, id: Uuid) -> Result<&Path, String> {
+        self.paths.get(&id).ok_or_else(|| format!("No path with ID {id}"))
+    }
+
+    fn path_mut(&mut self, id: Uuid) -> Result<&mut Path, String> {
+        self.paths.get_mut(&id).ok_or_else(|| format!("No path with ID {id}"))
+    }
+
+    fn solid(&self, id: Uuid) -> Result<&Solid, String> {
+        self.solids.get(&id).ok_or_else(|| format!("No solid with ID {id}"))
+    }
+
+    /// The walls around the wall swept from a segment, checking they're all part of the solid.
+    fn walls_around(
+        &self,
+        object_id: Uuid,
+        curve_id: Uuid,
+        face_id: Uuid,
+    ) -> Result<(Option<Wall>, Wall, Option<Wall>), String> {
+        let walls = self
+            .solid(object_id)?
+            .walls_around(curve_id)
+            .ok_or_else(|| not_on_solid(curve_id, object_id))?;
+        if walls.1.face_id != face_id {
+            return Err(format!("Face {face_id} isn't next to edge {curve_id}"));
+        }
+        Ok(walls)
+    }
+
+    /// A point on the line through a segment's ends, and the line's direction.
+    fn segment_line(&self, segment_id: Uuid) -> Result<(Vector, Vector), String> {
+        self.paths
+            .values()
+            .find_map(|path| {
+                let profile = &path.profile;
+                let i = profile.segments.iter().position(|segment| segment.id == segment_id)?;
+                let start = match i {
+                    0 => profile.start,
+                    _ => *profile.segments[i - 1].points.last()?,
+                };
+                let end = *profile.segments[i].points.last()?;
+                let start = profile.frame.point(start);
+                Some((start, sub(profile.frame.point(end), start)))
+            })
+            .ok_or_else(|| format!("No segment with ID {segment_id}"))
+    }
+
+    /// Make a solid by sweeping a path, or add a feature to the solid whose face it was sketched on.
+    fn sweep(&mut self, path_id: Uuid, sweep: Sweep) -> Result<(), String> {
+        let Path { profile, on_solid } = self.path(path_id)?.clone();
+        let solid_id = on_solid.unwrap_or(path_id);
+        let frame = profile.frame;
+        let (start_cap, end_cap) = match sweep {
+            Sweep::Extrude { distance } => {
+                let end = frame.translated(scale(frame.normal(), distance));
+                // Caps face out of the solid.
+                if distance >= 0.0 {
+                    (Some(frame.flipped()), Some(end))
+                } else {
+                    (Some(frame), Some(end.flipped()))
+                }
+            }
+            Sweep::Revolve { angle, .. } if angle.abs() >= TAU => (None, None),
+            Sweep::Revolve { center, axis, angle } => (Some(frame), Some(frame.rotated(center, axis, angle))),
+            Sweep::Along => (Some(frame), None),
+        };
+        // Extruding from a face merges the extrusion's start with the face.
+        let start_cap = start_cap.filter(|_| on_solid.is_none());
+        let (start_cap, end_cap) = if profile.closed {
+            (start_cap, end_cap)
+        } else {
+            (None, None)
+        };
+
+        let mut walls = Vec::with_capacity(profile.segments.len());
+        let mut from = profile.start;
+        for segment in &profile.segments {
+            let to = *segment.points.last().unwrap_or(&from);
+            let wall = Wall {
+                curve_id: segment.id,
+                face_id: Uuid::new_v4(),
+                opposite_edge: Uuid::new_v4(),
+                next_edge: Uuid::new_v4(),
+            };
+            let face_frame = match sweep {
+                Sweep::Extrude { distance } if segment.straight => Some(Frame::new(
+                    frame.point(from),
+                    frame.direction([to[0] - from[0], to[1] - from[1]]),
+                    scale(frame.normal(), distance.signum()),
+                )),
+                _ => None,
+            };
+            self.add_face(wall.face_id, solid_id, face_frame);
+            walls.push(wall);
+            from = to;
+        }
+
+        let measures = match sweep {
+            Sweep::Extrude { distance } if profile.closed => Some(extrusion_measures(&profile, distance, on_solid)),
+            _ => None,
+        };
+        let start_cap = start_cap.map(|frame| self.add_face(Uuid::new_v4(), solid_id, Some(frame)));
+        let end_cap = end_cap.map(|frame| self.add_face(Uuid::new_v4(), solid_id, Some(frame)));
+        let feature = Feature {
+            walls,
+            closed: profile.closed,
+            start_cap,
+            end_cap,
+            measures,
+        };
+        match on_solid {
+            Some(solid_id) => {
+                let solid = self
+                    .solids
+                    .get_mut(&solid_id)
+                    .ok_or_else(|| format!("The solid {solid_id} which path {path_id} was sketched on is gone"))?;
+                solid.features.push(feature);
+            }
+            None => {
+                self.solids.insert(
+                    path_id,
+                    Solid {
+                        features: vec![feature],
+                    },
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn loft(&mut self, id: Uuid, section_ids: &[Uuid]) -> Result<(), String> {
+        let (Some(first), Some(last)) = (section_ids.first(), section_ids.last()) else {
+            return Err("Lofts need at least one section".to_owned());
+        };
+        let first = self.path(*first)?.profile.clone();
+        let last = self.path(*last)?.profile.frame;
+        let walls: Vec<Wall> = first
+            .segments
+            .iter()
+            .map(|segment| Wall {
+                curve_id: segment.id,
+                face_id: self.add_face(Uuid::new_v4(), id, None),
+                opposite_edge: Uuid::new_v4(),
+                next_edge: Uuid::new_v4(),
+            })
+            .collect();
+        let feature = Feature {
+            walls,
+            closed: first.closed,
+            start_cap: Some(self.add_face(Uuid::new_v4(), id, Some(first.frame.flipped()))),
+            end_cap: Some(self.add_face(Uuid::new_v4(), id, Some(last))),
+            measures: None,
+        };
+        self.solids.insert(
+            id,
+            Solid {
+                features: vec![feature],
+            },
+        );
+        Ok(())
+    }
+
+    fn add_face(&mut self, face_id: Uuid, solid_id: Uuid, frame: Option<Frame>) -> Uuid {
+        let face = Face {
+            solid: Some(solid_id),
+            frame,
+        };
+        self.faces.insert(face_id, face);
+        face_id
+    }
+
+    /// Copy a path or solid, as patterns do. The copies aren't moved, and keep the original's
+    /// segment, face and edge IDs, just as the engine's copies do.
+    fn copy(&mut self, entity_id: Uuid, copies: usize) -> Result<Vec<Uuid>, String> {
+        let path = self.paths.get(&entity_id).cloned();
+        let solid = self.solids.get(&entity_id).cloned();
+        if path.is_none() && solid.is_none() {
+            return Err(format!("No path or solid with ID {entity_id} to copy"));
+        }
+        let ids: Vec<Uuid> = (0..copies).map(|_| Uuid::new_v4()).collect();
+        for id in &ids {
+            if let Some(path) = &path {
+                self.paths.insert(*id, path.clone());
+            }
+            if let Some(solid) = &solid {
+                self.solids.insert(*id, solid.clone());
+            }
+        }
+        Ok(ids)
+    }
+
+    fn measure(&self, entity_ids: &[Uuid]) -> Result<Measures, String> {
+        let mut total = Measures::default();
+        for id in entity_ids {
+            for feature in &self.solid(*id)?.features {
+                let measures = feature
+                    .measures
+                    .ok_or_else(|| format!("The fake engine can only measure extrusions, and {id} isn't one"))?;
+                total = total + measures;
+            }
+        }
+        Ok(total)
+    }
+}
+
+/// Measure a profile extruded into a prism, or added to the solid whose face it's on.
+fn extrusion_measures(profile: &Profile, distance: f64, on_solid: Option<Uuid>) -> Measures {
+    let (area, centroid) = profile.area_and_centroid();
+    let frame = profile.frame;
+    let normal = frame.normal();
+    // On a face, extruding outward adds material and inward removes it. Either way, the
+    // profile's area is swapped from one face to another, and the walls are added.
+    let (volume, caps) = match on_solid {
+        Some(_) => (area * distance, 0.0),
+        None => (area * distance.abs(), 2.0 * area),
+    };
+    let center = add(frame.point(centroid), scale(normal, distance / 2.0));
+    Measures {
+        volume,
+        surface_area: caps + profile.perimeter() * distance.abs(),
+        moment: scale(center, volume),
+    }
+}
+
+fn not_on_solid(edge_id: Uuid, object_id: Uuid) -> String {
+    format!("Edge {edge_id} isn't part of solid {object_id}")
+}
+
+/// What snapshots look like, since the fake engine doesn't render anything.
+fn blank_png() -> Result<Vec<u8>, String> {
+    let image = image::RgbaImage::from_pixel(SNAPSHOT_SIZE, SNAPSHOT_SIZE, image::Rgba([255, 255, 255, 255]));
+    let mut png = Cursor::new(Vec::new());
+    image
+        .write_to(&mut png, image::ImageFormat::Png)
+        .map_err(|e| format!("Couldn't encode the snapshot: {e}"))?;
+    Ok(png.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use kcmc::{shared::PathSegment, units::UnitVolume};
+
+    use super::*;
+
+    fn at(x: f64, y: f64) -> Point3d<LengthUnit> {
+        Point3d {
+            x: LengthUnit(x),
+            y: LengthUnit(y),
+            z: LengthUnit(0.0),
+        }
+    }
+
+    /// Extrude a 10 by 10 square on the XY plane, and return its ID and its segments' IDs.
+    fn cube(scene: &mut Scene, height: f64) -> (Uuid, Vec<Uuid>) {
+        let plane = Uuid::new_v4();
+        let path = Uuid::new_v4();
+        let segments: Vec<Uuid> = (0..4).map(|_| Uuid::new_v4()).collect();
+        let mut commands = vec![
+            (
+                plane,
+                ModelingCmd::from(mcmd::MakePlane {
+                    origin: at(0.0, 0.0),
+                    x_axis: Point3d { x: 1.0, y: 0.0, z: 0.0 },
+                    y_axis: Point3d { x: 0.0, y: 1.0, z: 0.0 },
+                    size: LengthUnit(100.0),
+                    clobber: false,
+                    hide: None,
+                }),
+            ),
+            (
+                Uuid::new_v4(),
+                ModelingCmd::from(mcmd::EnableSketchMode {
+                    entity_id: plane,
+                    ortho: false,
+                    animated: false,
+                    adjust_camera: false,
+                    planar_normal: None,
+                }),
+            ),
+            (path, ModelingCmd::from(mcmd::StartPath::default())),
+        ];
+        for (id, (x, y)) in segments.iter().zip([(10.0, 0.0), (10.0, 10.0), (0.0, 10.0)]) {
+            let segment = PathSegment::Line {
+                end: at(x, y),
+                relative: false,
+            };
+            let cmd = mcmd::ExtendPath {
+                path: path.into(),
+                segment,
+            };
+            commands.push((*id, ModelingCmd::from(cmd)));
+        }
+        commands.push((segments[3], ModelingCmd::from(mcmd::ClosePath { path_id: path })));
+        commands.push((
+            Uuid::new_v4(),
+            ModelingCmd::from(mcmd::Extrude {
+                target: path.into(),
+                distance: LengthUnit(height),
+                faces: None,
+            }),
+        ));
+        for (id, cmd) in commands {
+            scene.handle(id, &cmd).unwrap();
+        }
+        (path, segments)
+    }
+
+    fn face_info(scene: &mut Scene, object_id: Uuid, edge_id: Uuid) -> Vec<output::ExtrusionFaceInfo> {
+        let cmd = ModelingCmd::from(mcmd::Solid3dGetExtrusionFaceInfo { object_id, edge_id });
+        match scene.handle(Uuid::new_v4(), &cmd).unwrap() {
+            OkModelingCmdResponse::Solid3dGetExtrusionFaceInfo(info) => info.faces,
+            other => panic!("unexpected response {other:?}"),
+        }
+    }
+
+    #[test]
+    fn adjacent_edges_agree() {
+        let mut scene = Scene::default();
+        let (cube, segments) = cube(&mut scene, 10.0);
+        let faces = face_info(&mut scene, cube, segments[0]);
+        assert_eq!(faces.len(), 6);
+        assert_eq!(faces[4].cap, ExtrusionFaceCapType::Bottom);
+        assert_eq!(faces[5].cap, ExtrusionFaceCapType::Top);
+
+        let adjacent = |scene: &mut Scene, i: usize, next: bool| {
+            let (object_id, edge_id, face_id) = (cube, segments[i], faces[i].face_id.unwrap());
+            let cmd = if next {
+                ModelingCmd::from(mcmd::Solid3dGetNextAdjacentEdge {
+                    object_id,
+                    edge_id,
+                    face_id,
+                })
+            } else {
+                ModelingCmd::from(mcmd::Solid3dGetPrevAdjacentEdge {
+                    object_id,
+                    edge_id,
+                    face_id,
+                })
+            };
+            match scene.handle(Uuid::new_v4(), &cmd).unwrap() {
+                OkModelingCmdResponse::Solid3dGetNextAdjacentEdge(r) => r.edge.unwrap(),
+                OkModelingCmdResponse::Solid3dGetPrevAdjacentEdge(r) => r.edge.unwrap(),
+                other => panic!("unexpected response {other:?}"),
+            }
+        };
+        // The edge after each wall is the edge before the next one, all the way around.
+        for i in 0..4 {
+            assert_eq!(adjacent(&mut scene, i, true), adjacent(&mut scene, (i + 1) % 4, false));
+        }
+
+        // Asking about a face which isn't next to the edge is an error.
+        let cmd = ModelingCmd::from(mcmd::Solid3dGetOppositeEdge {
+            object_id: cube,
+            edge_id: segments[0],
+            face_id: faces[1].face_id.unwrap(),
+        });
+        scene.handle(Uuid::new_v4(), &cmd).unwrap_err();
+    }
+
+    #[test]
+    fn measure_in_other_units() {
+        let mut scene = Scene::default();
+        scene
+            .handle(
+                Uuid::new_v4(),
+                &ModelingCmd::from(mcmd::SetSceneUnits {
+                    unit: UnitLength::Centimeters,
+                }),
+            )
+            .unwrap();
+        // Extruding downward still makes a cube.
+        let (cube, _) = cube(&mut scene, -10.0);
+        let cmd = ModelingCmd::from(mcmd::Volume {
+            entity_ids: vec![cube],
+            output_unit: UnitVolume::Liters,
+        });
+        let OkModelingCmdResponse::Volume(volume) = scene.handle(Uuid::new_v4(), &cmd).unwrap() else {
+            panic!("expected a volume");
+        };
+        assert!((volume.volume - 1.0).abs() < 1e-9, "{}", volume.volume);
+
+        let cmd = ModelingCmd::from(mcmd::CenterOfMass {
+            entity_ids: vec![cube],
+            output_unit: UnitLength::Millimeters,
+        });
+        let OkModelingCmdResponse::CenterOfMass(center) = scene.handle(Uuid::new_v4(), &cmd).unwrap() else {
+            panic!("expected a center of mass");
+        };
+        let [x, y, z] = [
+            center.center_of_mass.x,
+            center.center_of_mass.y,
+            center.center_of_mass.z,
+        ];
+        assert!((x - 50.0).abs() < 1e-9 && (y - 50.0).abs() < 1e-9 && (z + 50.0).abs() < 1e-9);
+    }
+}
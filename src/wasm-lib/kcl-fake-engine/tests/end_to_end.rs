@@ -0,0 +1,96 @@
+//! Execute KCL against the fake engine, over the same WebSocket connection the real engine is used with.
+
+use std::collections::HashMap;
+
+use kcl_fake_engine::FakeEngine;
+use kcl_lib::{ExecOutcome, ExecState, ExecutorContext, ExecutorSettings, KclError, Program};
+
+async fn run(code: &str) -> Result<ExecOutcome, KclError> {
+    let engine = FakeEngine::start("127.0.0.1:0".parse().unwrap()).await.unwrap();
+    let ctx =
+        ExecutorContext::new_with_client(ExecutorSettings::default(), Some("fake".to_owned()), Some(engine.url()))
+            .await
+            .unwrap();
+    let program = Program::parse_no_errs(code).unwrap();
+    let mut exec_state = ExecState::new(&ctx.settings);
+    let result = ctx.run(&program, &mut exec_state).await;
+    ctx.close().await;
+    result.map(|_| exec_state.to_wasm_outcome())
+}
+
+/// How many artifacts of each type are in the artifact graph.
+fn artifact_counts(outcome: &ExecOutcome) -> HashMap<String, usize> {
+    let graph = serde_json::to_value(&outcome.artifact_graph).unwrap();
+    let mut counts = HashMap::new();
+    for artifact in graph["map"].as_object().unwrap().values() {
+        *counts.entry(artifact["type"].as_str().unwrap().to_owned()).or_default() += 1;
+    }
+    counts
+}
+
+fn number(outcome: &ExecOutcome, name: &str) -> f64 {
+    outcome.variables[name].as_f64().unwrap()
+}
+
+const CUBE: &str = r#"cube = startSketchOn('XY')
+  |> startProfileAt([0, 0], %)
+  |> line(end = [10, 0], tag = $base)
+  |> line(end = [0, 10])
+  |> line(end = [-10, 0])
+  |> close()
+  |> extrude(length = 10)
+"#;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn cube_artifact_graph() {
+    let code = CUBE.to_owned()
+        + "  |> fillet({ radius = 1, tags = [getOppositeEdge(base), getNextAdjacentEdge(base)] }, %)
+size = volume(cube)
+";
+    let outcome = run(&code).await.unwrap();
+
+    let counts = artifact_counts(&outcome);
+    assert_eq!(counts["wall"], 4);
+    assert_eq!(counts["cap"], 2);
+    // Each wall has an edge opposite its segment, and an edge shared with the next wall.
+    assert_eq!(counts["sweepEdge"], 8);
+    assert!((number(&outcome, "size") - 1000.0).abs() < 1e-6);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn sketch_on_face() {
+    let code = CUBE.to_owned()
+        + "
+bump = startSketchOn(cube, 'end')
+  |> startProfileAt([2, 2], %)
+  |> line(end = [2, 0])
+  |> line(end = [0, 2])
+  |> line(end = [-2, 0])
+  |> close()
+  |> extrude(length = 5)
+size = volume(bump)
+";
+    let outcome = run(&code).await.unwrap();
+
+    // The bump adds its own walls, and a cap on its end but not its start. The cube's end cap
+    // is in the graph as the plane the bump was sketched on.
+    let counts = artifact_counts(&outcome);
+    assert_eq!(counts["sweep"], 2);
+    assert_eq!(counts["wall"], 8);
+    assert_eq!(counts["sweepEdge"], 16);
+    assert_eq!(counts["cap"], 2);
+    assert_eq!(counts["plane"], 2);
+    // The bump is part of the cube.
+    assert!((number(&outcome, "size") - 1020.0).abs() < 1e-6);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn engine_errors_are_reported() {
+    let code = r#"ring = startSketchOn('XY')
+  |> circle({ center = [10, 0], radius = 2 }, %)
+  |> revolve({ axis = 'y' }, %)
+size = volume(ring)
+"#;
+    let err = run(code).await.unwrap_err();
+    assert!(err.message().contains("can only measure extrusions"), "{err:?}");
+}
@@ -1,7 +1,9 @@
 //! Executes KCL programs.
 //! The server reuses the same engine session for each KCL program it receives.
 use std::{
+    io::Cursor,
     net::SocketAddr,
+    path::{Component, Path},
     sync::{
         atomic::{AtomicUsize, Ordering},
         Arc,
@@ -9,13 +11,21 @@ use std::{
     time::Duration,
 };
 
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use hyper::{
     body::Bytes,
-    header::CONTENT_TYPE,
+    header::{CONTENT_DISPOSITION, CONTENT_TYPE},
     service::{make_service_fn, service_fn},
-    Body, Error, Response, Server,
+    Body, Error, Response, Server, StatusCode,
+};
+use kcl_lib::{
+    test_server::{
+        parse_with_warnings, ExecuteErrorResponse, ExecuteRequestBody, ExecuteResponse, ExportRequestBody,
+        ProjectRequestBody, RequestBody, Snapshot,
+    },
+    CameraView, CompilationError, ExecError, ExecState, ExecutorContext, FileExportFormat, KclErrorWithOutputs,
+    Program, UnitLength,
 };
-use kcl_lib::{test_server::RequestBody, ExecState, ExecutorContext, Program, UnitLength};
 use tokio::{
     sync::{mpsc, oneshot},
     task::JoinHandle,
@@ -33,6 +43,9 @@ pub struct ServerArgs {
     /// This is useful for testing a local engine instance.
     /// Overridden by the $ZOO_HOST environment variable.
     pub engine_address: Option<String>,
+    /// Execute programs with a mock engine instead of connecting to one.
+    /// Nothing can be rendered or exported in this mode.
+    pub mock: bool,
 }
 
 impl ServerArgs {
@@ -43,8 +56,11 @@ impl ServerArgs {
                 .unwrap_or("0.0.0.0:3333".parse().unwrap()),
             num_engine_conns: pargs.opt_value_from_str("--num-engine-conns")?.unwrap_or(1),
             engine_address: pargs.opt_value_from_str("--engine-address")?,
+            mock: pargs.contains("--mock"),
         };
-        if let Ok(addr) = std::env::var("ZOO_HOST") {
+        if args.mock {
+            println!("Using a mock engine, so snapshots and exports are unavailable");
+        } else if let Ok(addr) = std::env::var("ZOO_HOST") {
             println!("Overriding engine address via $ZOO_HOST");
             args.engine_address = Some(addr);
         }
@@ -53,9 +69,23 @@ impl ServerArgs {
     }
 }
 
+/// What the client wants done with a KCL program.
+#[derive(Debug, Clone, Copy)]
+enum Endpoint {
+    /// Respond with a PNG snapshot.
+    Snapshot,
+    /// Respond with the model exported to a file.
+    Export,
+    /// Respond with the outputs of the program, and snapshots of it, as JSON.
+    Execute,
+    /// Like `Execute`, but for a project of several files.
+    ExecuteProject,
+}
+
 /// Sent from the server to each worker.
 struct WorkerReq {
-    /// A KCL program, in UTF-8.
+    endpoint: Endpoint,
+    /// The request JSON, which contains a KCL program or project.
     body: Bytes,
     /// A channel to send the HTTP response back.
     resp: oneshot::Sender<Response<Body>>,
@@ -64,18 +94,29 @@ struct WorkerReq {
 /// Each worker has a connection to the engine, and accepts
 /// KCL programs. When it receives one (over the mpsc channel)
 /// it executes it and returns the result via a oneshot channel.
-fn start_worker(i: u8, engine_addr: Option<String>) -> mpsc::Sender<WorkerReq> {
+fn start_worker(i: u8, engine_addr: Option<String>, mock: bool) -> mpsc::Sender<WorkerReq> {
     println!("Starting worker {i}");
     // Make a work queue for this worker.
     let (tx, mut rx) = mpsc::channel(1);
     tokio::task::spawn(async move {
-        let state = ExecutorContext::new_for_unit_test(UnitLength::Mm, engine_addr)
-            .await
-            .unwrap();
+        let state = if mock {
+            let mut state = ExecutorContext::new_mock().await;
+            state.settings.units = UnitLength::Mm;
+            state
+        } else {
+            ExecutorContext::new_for_unit_test(UnitLength::Mm, engine_addr)
+                .await
+                .unwrap()
+        };
         println!("Worker {i} ready");
         while let Some(req) = rx.recv().await {
             let req: WorkerReq = req;
-            let resp = snapshot_endpoint(req.body, state.clone()).await;
+            let resp = match req.endpoint {
+                Endpoint::Snapshot => snapshot_endpoint(req.body, state.clone()).await,
+                Endpoint::Export => export_endpoint(req.body, state.clone()).await,
+                Endpoint::Execute => execute_endpoint(req.body, state.clone()).await,
+                Endpoint::ExecuteProject => project_endpoint(req.body, state.clone()).await,
+            };
             if req.resp.send(resp).is_err() {
                 println!("\tWorker {i} exiting");
             }
@@ -95,9 +136,10 @@ pub async fn start_server(args: ServerArgs) -> anyhow::Result<()> {
         listen_on,
         num_engine_conns,
         engine_address,
+        mock,
     } = args;
     let workers: Vec<_> = (0..num_engine_conns)
-        .map(|i| start_worker(i, engine_address.clone()))
+        .map(|i| start_worker(i, engine_address.clone(), mock))
         .collect();
     let state = Arc::new(ServerState {
         workers,
@@ -133,6 +175,13 @@ pub async fn start_server(args: ServerArgs) -> anyhow::Result<()> {
 }
 
 async fn handle_request(req: hyper::Request<Body>, state3: Arc<ServerState>) -> Result<Response<Body>, Error> {
+    // Snapshots were the only endpoint once, so they're served from every other path.
+    let endpoint = match req.uri().path() {
+        "/export" => Endpoint::Export,
+        "/execute" => Endpoint::Execute,
+        "/execute-project" => Endpoint::ExecuteProject,
+        _ => Endpoint::Snapshot,
+    };
     let body = hyper::body::to_bytes(req.into_body()).await?;
 
     // Round robin requests between each available worker.
@@ -141,7 +190,13 @@ async fn handle_request(req: hyper::Request<Body>, state3: Arc<ServerState>) ->
     // println!("Sending request {req_num} to worker {worker_id}");
     let worker = state3.workers[worker_id].clone();
     let (tx, rx) = oneshot::channel();
-    let req_sent = worker.send(WorkerReq { body, resp: tx }).await;
+    let req_sent = worker
+        .send(WorkerReq {
+            endpoint,
+            body,
+            resp: tx,
+        })
+        .await;
     req_sent.unwrap();
     let resp = rx.await.unwrap();
     Ok(resp)
@@ -152,18 +207,205 @@ async fn handle_request(req: hyper::Request<Body>, state3: Arc<ServerState>) ->
 /// Malformed requests are HTTP Bad Request.
 /// Successful requests contain a PNG as the body.
 async fn snapshot_endpoint(body: Bytes, ctxt: ExecutorContext) -> Response<Body> {
+    if ctxt.is_mock() {
+        return unavailable_in_mock_mode("Snapshots are");
+    }
     let body = match serde_json::from_slice::<RequestBody>(body.as_ref()) {
         Ok(bd) => bd,
         Err(e) => return bad_request(format!("Invalid request JSON: {e}")),
     };
-    let RequestBody { kcl_program, test_name } = body;
+    let RequestBody {
+        kcl_program,
+        test_name,
+        view,
+    } = body;
+
+    if let Err(resp) = execute(&kcl_program, &test_name, &ctxt).await {
+        return resp;
+    }
+    let snapshot = match ctxt.prepare_snapshot_from(&view).await {
+        Ok(s) => s,
+        Err(e) => return kcl_err(e),
+    };
+    eprintln!("\tServing response");
+    let png_bytes = snapshot.contents.0;
+    let mut resp = Response::new(Body::from(png_bytes));
+    resp.headers_mut().insert(CONTENT_TYPE, "image/png".parse().unwrap());
+    resp
+}
+
+/// Execute a KCL program, then respond with the model exported to a file.
+/// Errors are reported like for snapshots.
+/// Successful requests contain the file as the body, and its name in the Content-Disposition header.
+async fn export_endpoint(body: Bytes, ctxt: ExecutorContext) -> Response<Body> {
+    if ctxt.is_mock() {
+        return unavailable_in_mock_mode("Exports are");
+    }
+    let body = match serde_json::from_slice::<ExportRequestBody>(body.as_ref()) {
+        Ok(bd) => bd,
+        Err(e) => return bad_request(format!("Invalid request JSON: {e}")),
+    };
+    let ExportRequestBody {
+        kcl_program,
+        test_name,
+        format,
+        options,
+    } = body;
+
+    let exec_state = match execute(&kcl_program, &test_name, &ctxt).await {
+        Ok(exec_state) => exec_state,
+        Err(resp) => return resp,
+    };
+    let mut files = match ctxt.export(&exec_state, format, options).await {
+        Ok(files) => files,
+        Err(e) => return kcl_err(e),
+    };
+    // Every format is exported as a single file, e.g. glTF has its buffers embedded.
+    if files.len() != 1 {
+        return bad_gateway(format!(
+            "Expected the engine to export one file, but it exported {}",
+            files.len()
+        ));
+    }
+    let file = files.remove(0);
+    eprintln!("\tServing response");
+    let content_type = match format {
+        FileExportFormat::Glb => "model/gltf-binary",
+        FileExportFormat::Gltf => "model/gltf+json",
+        FileExportFormat::Obj => "model/obj",
+        FileExportFormat::Step => "model/step",
+        FileExportFormat::Stl => "model/stl",
+        FileExportFormat::Fbx | FileExportFormat::Ply => "application/octet-stream",
+    };
+    let disposition = format!("attachment; filename=\"{}\"", file.name);
+    let mut resp = Response::new(Body::from(file.contents));
+    resp.headers_mut().insert(CONTENT_TYPE, content_type.parse().unwrap());
+    match disposition.parse() {
+        Ok(disposition) => {
+            resp.headers_mut().insert(CONTENT_DISPOSITION, disposition);
+        }
+        Err(_) => eprintln!("\tCan't send the file name {}", file.name),
+    }
+    resp
+}
+
+/// Execute a KCL program, then respond with its outputs and a snapshot from each view, as JSON.
+/// KCL errors respond with HTTP Bad Gateway, or HTTP Bad Request if the program doesn't parse,
+/// and the error as JSON.
+/// Malformed requests are HTTP Bad Request.
+async fn execute_endpoint(body: Bytes, ctxt: ExecutorContext) -> Response<Body> {
+    let body = match serde_json::from_slice::<ExecuteRequestBody>(body.as_ref()) {
+        Ok(bd) => bd,
+        Err(e) => return bad_request(format!("Invalid request JSON: {e}")),
+    };
+    let ExecuteRequestBody {
+        kcl_program,
+        test_name,
+        views,
+    } = body;
+    execute_to_json(&kcl_program, &test_name, &views, &ctxt).await
+}
 
-    let program = match Program::parse_no_errs(&kcl_program) {
+/// Unzip a project of KCL files, execute one of them, and respond like the execute endpoint.
+async fn project_endpoint(body: Bytes, mut ctxt: ExecutorContext) -> Response<Body> {
+    let body = match serde_json::from_slice::<ProjectRequestBody>(body.as_ref()) {
+        Ok(bd) => bd,
+        Err(e) => return bad_request(format!("Invalid request JSON: {e}")),
+    };
+    let ProjectRequestBody {
+        project_zip,
+        main_file,
+        test_name,
+        views,
+    } = body;
+    if !Path::new(&main_file)
+        .components()
+        .all(|c| matches!(c, Component::Normal(_)))
+    {
+        return bad_request(format!(
+            "The main file {main_file} must be a relative path inside the project"
+        ));
+    }
+    let project_zip = match BASE64.decode(project_zip) {
+        Ok(zip) => zip,
+        Err(e) => return bad_request(format!("Invalid base64 in the project zip: {e}")),
+    };
+    // The project is deleted when this is dropped, after executing it.
+    let project_dir = match unzip_project(&project_zip) {
+        Ok(dir) => dir,
+        Err(e) => return bad_request(format!("Invalid project zip: {e}")),
+    };
+    let main_path = project_dir.path().join(&main_file);
+    let kcl_program = match std::fs::read_to_string(&main_path) {
+        Ok(program) => program,
+        Err(e) => return bad_request(format!("Could not read {main_file} from the project: {e}")),
+    };
+    // Imports are resolved relative to the main file.
+    ctxt.settings.with_current_file(main_path);
+    execute_to_json(&kcl_program, &test_name, &views, &ctxt).await
+}
+
+fn unzip_project(zip: &[u8]) -> anyhow::Result<tempfile::TempDir> {
+    let dir = tempfile::tempdir()?;
+    let mut archive = zip::ZipArchive::new(Cursor::new(zip))?;
+    // Entries which would be written outside the directory are rejected.
+    archive.extract(dir.path())?;
+    Ok(dir)
+}
+
+/// Execute a KCL program in a fresh scene, then take a snapshot from each view.
+/// The response is JSON, for success or for KCL errors.
+async fn execute_to_json(
+    kcl_program: &str,
+    test_name: &str,
+    views: &[CameraView],
+    ctxt: &ExecutorContext,
+) -> Response<Body> {
+    if ctxt.is_mock() && !views.is_empty() {
+        return unavailable_in_mock_mode("Snapshots are");
+    }
+    let (program, warnings) = match parse_with_warnings(kcl_program) {
+        Ok(parsed) => parsed,
+        Err(err) => {
+            eprintln!("\tBad request");
+            return json_response(StatusCode::BAD_REQUEST, &*err);
+        }
+    };
+    let mut exec_state = ExecState::new(&ctxt.settings);
+    if let Err(error) = run(&program, test_name, ctxt, &mut exec_state).await {
+        return kcl_err_json(error, warnings);
+    }
+    let mut snapshots = Vec::with_capacity(views.len());
+    for view in views {
+        match ctxt.prepare_snapshot_from(view).await {
+            Ok(snapshot) => snapshots.push(Snapshot {
+                view: view.clone(),
+                png: BASE64.encode(snapshot.contents.0),
+            }),
+            Err(ExecError::Kcl(error)) => return kcl_err_json(*error, warnings),
+            Err(e) => return kcl_err(e),
+        }
+    }
+    eprintln!("\tServing response");
+    let outcome = exec_state.to_wasm_outcome();
+    json_response(
+        StatusCode::OK,
+        &ExecuteResponse {
+            outcome,
+            warnings,
+            snapshots,
+        },
+    )
+}
+
+/// Execute a KCL program in a fresh scene.
+/// Errors are returned as the response to send.
+async fn execute(kcl_program: &str, test_name: &str, ctxt: &ExecutorContext) -> Result<ExecState, Response<Body>> {
+    let program = match Program::parse_no_errs(kcl_program) {
         Ok(pr) => pr,
-        Err(e) => return bad_request(format!("Parse error: {e}")),
+        Err(e) => return Err(bad_request(format!("Parse error: {e}"))),
     };
 
-    eprintln!("Executing {test_name}");
     let mut exec_state = ExecState::new(&ctxt.settings);
     // This is a shitty source range, I don't know what else to use for it though.
     // There's no actual KCL associated with this reset_scene call.
@@ -171,25 +413,27 @@ async fn snapshot_endpoint(body: Bytes, ctxt: ExecutorContext) -> Response<Body>
         .send_clear_scene(&mut exec_state, kcl_lib::SourceRange::default())
         .await
     {
-        return kcl_err(e);
+        return Err(kcl_err(e));
     }
+    run(&program, test_name, ctxt, &mut exec_state).await.map_err(kcl_err)?;
+    Ok(exec_state)
+}
+
+/// Run a parsed KCL program, logging if it takes a long time.
+async fn run(
+    program: &Program,
+    test_name: &str,
+    ctxt: &ExecutorContext,
+    exec_state: &mut ExecState,
+) -> Result<(), KclErrorWithOutputs> {
+    eprintln!("Executing {test_name}");
     // Let users know if the test is taking a long time.
     let (done_tx, done_rx) = oneshot::channel::<()>();
     let timer = time_until(done_rx);
-    if let Err(e) = ctxt.run(&program, &mut exec_state).await {
-        return kcl_err(e);
-    }
-    let snapshot = match ctxt.prepare_snapshot().await {
-        Ok(s) => s,
-        Err(e) => return kcl_err(e),
-    };
+    let result = ctxt.run_with_ui_outputs(program, exec_state).await;
     let _ = done_tx.send(());
     timer.abort();
-    eprintln!("\tServing response");
-    let png_bytes = snapshot.contents.0;
-    let mut resp = Response::new(Body::from(png_bytes));
-    resp.headers_mut().insert(CONTENT_TYPE, "image/png".parse().unwrap());
-    resp
+    result.map(|_| ())
 }
 
 fn bad_request(msg: String) -> Response<Body> {
@@ -211,6 +455,33 @@ fn kcl_err(err: impl std::fmt::Display) -> Response<Body> {
     bad_gateway(format!("{err}"))
 }
 
+/// Mock engines execute programs, but can't render or export them.
+fn unavailable_in_mock_mode(what: &str) -> Response<Body> {
+    eprintln!("\tNot available in mock mode");
+    let mut resp = Response::new(Body::from(format!(
+        "{what} not available in mock mode, because there's no engine to make them"
+    )));
+    *resp.status_mut() = StatusCode::NOT_IMPLEMENTED;
+    resp
+}
+
+fn kcl_err_json(error: KclErrorWithOutputs, warnings: Vec<CompilationError>) -> Response<Body> {
+    eprintln!("\tBad KCL");
+    json_response(StatusCode::BAD_GATEWAY, &ExecuteErrorResponse { error, warnings })
+}
+
+fn json_response(status: StatusCode, body: &impl serde::Serialize) -> Response<Body> {
+    let json = match serde_json::to_vec(body) {
+        Ok(json) => json,
+        Err(e) => return bad_gateway(format!("Could not serialize the response: {e}")),
+    };
+    let mut resp = Response::new(Body::from(json));
+    *resp.status_mut() = status;
+    resp.headers_mut()
+        .insert(CONTENT_TYPE, "application/json".parse().unwrap());
+    resp
+}
+
 fn time_until(done: oneshot::Receiver<()>) -> JoinHandle<()> {
     tokio::task::spawn(async move {
         let period = 10;
@@ -84,6 +84,34 @@ fn is_false(b: &bool) -> bool {
     !b
 }
 
+impl StdLibFnData {
+    /// The function's signature, e.g. `sqrt(num: number) -> number`.
+    pub fn fn_signature(&self) -> String {
+        fn_signature(&self.name, &self.args, self.return_value.as_ref())
+    }
+}
+
+fn fn_signature(name: &str, args: &[StdLibFnArg], return_value: Option<&StdLibFnArg>) -> String {
+    let mut signature = String::new();
+    signature.push_str(&format!("{}(", name));
+    for (i, arg) in args.iter().enumerate() {
+        if i > 0 {
+            signature.push_str(", ");
+        }
+        if arg.required {
+            signature.push_str(&format!("{}: {}", arg.name, arg.type_));
+        } else {
+            signature.push_str(&format!("{}?: {}", arg.name, arg.type_));
+        }
+    }
+    signature.push(')');
+    if let Some(return_value) = return_value {
+        signature.push_str(&format!(" -> {}", return_value.type_));
+    }
+
+    signature
+}
+
 impl StdLibFnArg {
     /// If the argument is a primitive.
     pub fn is_primitive(&self) -> Result<bool> {
@@ -407,24 +435,7 @@ pub trait StdLibFn: std::fmt::Debug + Send + Sync {
     }
 
     fn fn_signature(&self) -> String {
-        let mut signature = String::new();
-        signature.push_str(&format!("{}(", self.name()));
-        for (i, arg) in self.args(false).iter().enumerate() {
-            if i > 0 {
-                signature.push_str(", ");
-            }
-            if arg.required {
-                signature.push_str(&format!("{}: {}", arg.name, arg.type_));
-            } else {
-                signature.push_str(&format!("{}?: {}", arg.name, arg.type_));
-            }
-        }
-        signature.push(')');
-        if let Some(return_value) = self.return_value(false) {
-            signature.push_str(&format!(" -> {}", return_value.type_));
-        }
-
-        signature
+        fn_signature(&self.name(), &self.args(false), self.return_value(false).as_ref())
     }
 
     fn to_completion_item(&self) -> Result<CompletionItem> {
@@ -31,6 +31,8 @@ pub struct EngineConnection {
     batch: Arc<Mutex<Vec<(WebSocketRequest, SourceRange)>>>,
     batch_end: Arc<Mutex<IndexMap<uuid::Uuid, (WebSocketRequest, SourceRange)>>>,
     artifact_commands: Arc<Mutex<Vec<ArtifactCommand>>>,
+    /// The stand-in response to each command, so the artifact graph can be built from them.
+    responses: Arc<Mutex<IndexMap<Uuid, WebSocketResponse>>>,
     execution_kind: Arc<Mutex<ExecutionKind>>,
 }
 
@@ -40,6 +42,7 @@ impl EngineConnection {
             batch: Arc::new(Mutex::new(Vec::new())),
             batch_end: Arc::new(Mutex::new(IndexMap::new())),
             artifact_commands: Arc::new(Mutex::new(Vec::new())),
+            responses: Arc::new(Mutex::new(IndexMap::new())),
             execution_kind: Default::default(),
         })
     }
@@ -49,7 +52,7 @@ impl EngineConnection {
         cmd: &ModelingCmd,
         cmd_id: ModelingCmdId,
         id_to_source_range: &HashMap<Uuid, SourceRange>,
-    ) -> Result<(), KclError> {
+    ) -> Result<OkModelingCmdResponse, KclError> {
         let cmd_id = *cmd_id.as_ref();
         let range = id_to_source_range
             .get(&cmd_id)
@@ -63,7 +66,51 @@ impl EngineConnection {
             range,
             command: cmd.clone(),
         });
-        Ok(())
+
+        let response = mock_response(cmd);
+        self.responses.lock().unwrap().insert(
+            cmd_id,
+            WebSocketResponse::Success(SuccessWebSocketResponse {
+                request_id: Some(cmd_id),
+                resp: OkWebSocketResponseData::Modeling {
+                    modeling_response: response.clone(),
+                },
+                success: true,
+            }),
+        );
+        Ok(response)
+    }
+}
+
+/// The response to a command. Queries get deterministic stand-in answers, so that code which uses
+/// them can be executed without an engine; everything else gets an empty response.
+pub(crate) fn mock_response(cmd: &ModelingCmd) -> OkModelingCmdResponse {
+    match cmd {
+        ModelingCmd::Volume(volume) => OkModelingCmdResponse::Volume(kcmc::output::Volume {
+            volume: 1.0,
+            output_unit: volume.output_unit,
+        }),
+        ModelingCmd::SurfaceArea(area) => OkModelingCmdResponse::SurfaceArea(kcmc::output::SurfaceArea {
+            surface_area: 1.0,
+            output_unit: area.output_unit,
+        }),
+        ModelingCmd::CenterOfMass(center) => OkModelingCmdResponse::CenterOfMass(kcmc::output::CenterOfMass {
+            center_of_mass: kcmc::shared::Point3d { x: 0.0, y: 0.0, z: 0.0 },
+            output_unit: center.output_unit,
+        }),
+        ModelingCmd::Mass(mass) => OkModelingCmdResponse::Mass(kcmc::output::Mass {
+            mass: 1.0,
+            output_unit: mass.output_unit,
+        }),
+        // Every face is the XY plane.
+        ModelingCmd::FaceIsPlanar(_) => OkModelingCmdResponse::FaceIsPlanar(kcmc::output::FaceIsPlanar {
+            origin: Some(kcmc::shared::Point3d::default()),
+            x_axis: Some(kcmc::shared::Point3d { x: 1.0, y: 0.0, z: 0.0 }),
+            y_axis: Some(kcmc::shared::Point3d { x: 0.0, y: 1.0, z: 0.0 }),
+            z_axis: Some(kcmc::shared::Point3d { x: 0.0, y: 0.0, z: 1.0 }),
+        }),
+        ModelingCmd::Export(_) => OkModelingCmdResponse::Export(kcmc::output::Export { files: Vec::new() }),
+        _ => OkModelingCmdResponse::Empty {},
     }
 }
 
@@ -78,7 +125,7 @@ impl crate::engine::EngineManager for EngineConnection {
     }
 
     fn responses(&self) -> IndexMap<Uuid, WebSocketResponse> {
-        IndexMap::new()
+        self.responses.lock().unwrap().clone()
     }
 
     fn take_artifact_commands(&self) -> Vec<ArtifactCommand> {
@@ -111,6 +158,8 @@ impl crate::engine::EngineManager for EngineConnection {
         _id_generator: &mut IdGenerator,
         _source_range: SourceRange,
     ) -> Result<(), KclError> {
+        // Responses from before the scene was cleared belong to no artifact any more.
+        self.responses.lock().unwrap().clear();
         Ok(())
     }
 
@@ -130,13 +179,8 @@ impl crate::engine::EngineManager for EngineConnection {
                 // Create the empty responses.
                 let mut responses = HashMap::with_capacity(requests.len());
                 for request in requests {
-                    self.handle_command(&request.cmd, request.cmd_id, &id_to_source_range)?;
-                    responses.insert(
-                        request.cmd_id,
-                        BatchResponse::Success {
-                            response: OkModelingCmdResponse::Empty {},
-                        },
-                    );
+                    let response = self.handle_command(&request.cmd, request.cmd_id, &id_to_source_range)?;
+                    responses.insert(request.cmd_id, BatchResponse::Success { response });
                 }
                 Ok(WebSocketResponse::Success(SuccessWebSocketResponse {
                     request_id: Some(id),
@@ -145,13 +189,11 @@ impl crate::engine::EngineManager for EngineConnection {
                 }))
             }
             WebSocketRequest::ModelingCmdReq(request) => {
-                self.handle_command(&request.cmd, request.cmd_id, &id_to_source_range)?;
+                let modeling_response = self.handle_command(&request.cmd, request.cmd_id, &id_to_source_range)?;
 
                 Ok(WebSocketResponse::Success(SuccessWebSocketResponse {
                     request_id: Some(id),
-                    resp: OkWebSocketResponseData::Modeling {
-                        modeling_response: OkModelingCmdResponse::Empty {},
-                    },
+                    resp: OkWebSocketResponseData::Modeling { modeling_response },
                     success: true,
                 }))
             }
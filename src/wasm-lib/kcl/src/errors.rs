@@ -388,6 +388,16 @@ impl CompilationError {
         }
     }
 
+    pub(crate) fn warn(source_range: SourceRange, message: impl ToString) -> CompilationError {
+        CompilationError {
+            source_range,
+            message: message.to_string(),
+            suggestion: None,
+            severity: Severity::Warning,
+            tag: Tag::None,
+        }
+    }
+
     pub(crate) fn fatal(source_range: SourceRange, message: impl ToString) -> CompilationError {
         CompilationError {
             source_range,
@@ -460,7 +470,7 @@ pub enum Tag {
     None,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, ts_rs::TS)]
+#[derive(Debug, Clone, Serialize, Deserialize, ts_rs::TS, schemars::JsonSchema)]
 #[ts(export)]
 pub struct Suggestion {
     pub title: String,
@@ -16,6 +16,7 @@ pub(crate) const SETTINGS: &str = "settings";
 pub(crate) const SETTINGS_UNIT_LENGTH: &str = "defaultLengthUnit";
 pub(crate) const SETTINGS_UNIT_ANGLE: &str = "defaultAngleUnit";
 pub(super) const NO_PRELUDE: &str = "no_prelude";
+pub(crate) const ALLOW: &str = "allow";
 
 pub(super) const IMPORT_FORMAT: &str = "format";
 pub(super) const IMPORT_FORMAT_VALUES: [&str; 9] = ["fbx", "gltf", "glb", "obj", "ply", "sldprt", "stp", "step", "stl"];
@@ -49,6 +50,16 @@ pub(super) fn expect_properties<'a>(
     })?)
 }
 
+/// The codes of the lints suppressed by any `@allow(Z0001, ...)` annotations.
+pub(crate) fn allowed_lints(attrs: &[Node<Annotation>]) -> Vec<&str> {
+    attrs
+        .iter()
+        .filter(|attr| attr.name() == Some(ALLOW))
+        .flat_map(|attr| attr.properties.iter().flatten())
+        .map(|prop| &*prop.key.name)
+        .collect()
+}
+
 pub(super) fn expect_ident(expr: &Expr) -> Result<&str, KclError> {
     match expr {
         Expr::Identifier(id) => Ok(&id.name),
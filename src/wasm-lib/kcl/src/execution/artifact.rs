@@ -14,6 +14,7 @@ use uuid::Uuid;
 
 use crate::{
     errors::KclErrorDetails,
+    execution::kcl_value::UnitLen,
     parsing::ast::types::{Node, Program},
     KclError, SourceRange,
 };
@@ -280,6 +281,38 @@ pub struct Helix {
     pub code_ref: CodeRef,
 }
 
+/// A screw thread on a hole or shaft. Cosmetic threads have no geometry, so
+/// this is the only record of them.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, ts_rs::TS)]
+#[ts(export_to = "Artifact.ts")]
+#[serde(rename_all = "camelCase")]
+pub struct Thread {
+    pub id: ArtifactId,
+    pub sub_type: ThreadSubType,
+    /// The thread designation, like `M8x1.25` or `1/4-20 UNC`.
+    pub standard: String,
+    /// True for a thread in a hole, false for a thread on a shaft.
+    pub internal: bool,
+    pub major_diameter: f64,
+    pub pitch: f64,
+    pub length: f64,
+    /// The units of the diameter, pitch and length.
+    pub units: UnitLen,
+    pub solid_id: ArtifactId,
+    /// The cylindrical face which is threaded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub face_id: Option<ArtifactId>,
+    pub code_ref: CodeRef,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, ts_rs::TS)]
+#[ts(export_to = "Artifact.ts")]
+#[serde(rename_all = "camelCase")]
+pub enum ThreadSubType {
+    /// Only recorded, not modeled. Modeled threads need solids to be joined and cut.
+    Cosmetic,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, ts_rs::TS)]
 #[ts(export_to = "Artifact.ts")]
 #[serde(tag = "type", rename_all = "camelCase")]
@@ -307,6 +340,7 @@ pub enum Artifact {
     EdgeCut(EdgeCut),
     EdgeCutEdge(EdgeCutEdge),
     Helix(Helix),
+    Thread(Thread),
 }
 
 impl Artifact {
@@ -325,6 +359,7 @@ impl Artifact {
             Artifact::EdgeCut(a) => a.id,
             Artifact::EdgeCutEdge(a) => a.id,
             Artifact::Helix(a) => a.id,
+            Artifact::Thread(a) => a.id,
         }
     }
 
@@ -345,6 +380,7 @@ impl Artifact {
             Artifact::EdgeCut(a) => Some(&a.code_ref),
             Artifact::EdgeCutEdge(_) => None,
             Artifact::Helix(a) => Some(&a.code_ref),
+            Artifact::Thread(a) => Some(&a.code_ref),
         }
     }
 
@@ -365,6 +401,7 @@ impl Artifact {
             Artifact::EdgeCut(a) => a.merge(new),
             Artifact::EdgeCutEdge(_) => Some(new),
             Artifact::Helix(_) => Some(new),
+            Artifact::Thread(_) => Some(new),
         }
     }
 }
@@ -500,6 +537,14 @@ pub(super) fn build_artifact_graph(
         }
     }
 
+    // Threads aren't created by any one engine command, so they're recorded
+    // during execution.
+    for artifact in exec_artifacts.values() {
+        if let Artifact::Thread(_) = artifact {
+            merge_artifact_into_map(&mut map, artifact.clone());
+        }
+    }
+
     Ok(ArtifactGraph { map })
 }
 
@@ -80,6 +80,7 @@ impl Artifact {
             Artifact::EdgeCut(a) => vec![a.consumed_edge_id],
             Artifact::EdgeCutEdge(a) => vec![a.edge_cut_id],
             Artifact::Helix(a) => a.axis_id.map(|id| vec![id]).unwrap_or_default(),
+            Artifact::Thread(a) => vec![a.face_id.unwrap_or(a.solid_id)],
         }
     }
 
@@ -162,6 +163,11 @@ impl Artifact {
                 // Note: Don't include these since they're parents: axis_id.
                 Vec::new()
             }
+            Artifact::Thread(_) => {
+                // Note: Don't include these since they're parents: face_id,
+                // solid_id.
+                Vec::new()
+            }
         }
     }
 }
@@ -230,7 +236,8 @@ impl ArtifactGraph {
                 | Artifact::SweepEdge(_)
                 | Artifact::EdgeCut(_)
                 | Artifact::EdgeCutEdge(_)
-                | Artifact::Helix(_) => false,
+                | Artifact::Helix(_)
+                | Artifact::Thread(_) => false,
             };
             if !grouped {
                 ungrouped.push(id);
@@ -355,6 +362,15 @@ impl ArtifactGraph {
                     code_ref_display(&helix.code_ref)
                 )?;
             }
+            Artifact::Thread(thread) => {
+                writeln!(
+                    output,
+                    "{prefix}{}[\"Thread {:?}<br>{:?}\"]",
+                    id,
+                    thread.sub_type,
+                    code_ref_display(&thread.code_ref)
+                )?;
+            }
         }
         Ok(())
     }
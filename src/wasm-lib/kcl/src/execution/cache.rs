@@ -2,12 +2,16 @@
 
 use std::sync::Arc;
 
+use indexmap::IndexMap;
 use itertools::{EitherOrBoth, Itertools};
 use tokio::sync::RwLock;
 
 use crate::{
     execution::{annotations, memory::ProgramMemory, ExecState, ExecutorSettings},
+    fs::FileManager,
+    modules::{ModuleId, ModuleInfo, ModulePath, ModuleRepr},
     parsing::ast::types::{Annotation, Node, Program},
+    source_range::SourceRange,
     walk::Node as WalkNode,
 };
 
@@ -174,6 +178,37 @@ pub(super) async fn get_changed_program(old: CacheInformation<'_>, new: CacheInf
     generate_changed_program(old_ast, new_ast, reapply_settings)
 }
 
+/// Find the imported KCL modules whose source has changed since they were loaded, along with
+/// their new ASTs. Changes to comments or whitespace are ignored. Modules are only parsed again if
+/// their source has changed, and the digests of their sources are updated.
+///
+/// Returns `None` if a module can no longer be read or parsed, in which case the whole program
+/// should be re-executed so the error is reported.
+pub(super) async fn get_changed_modules(
+    fs: &FileManager,
+    module_infos: &mut IndexMap<ModuleId, ModuleInfo>,
+) -> Option<Vec<(ModuleId, Node<Program>)>> {
+    let mut changed = Vec::new();
+    for info in module_infos.values_mut() {
+        // Modules from the standard library can't change.
+        let (ModulePath::Local(_), ModuleRepr::Kcl(old_ast, _)) = (&info.path, &info.repr) else {
+            continue;
+        };
+        let source = info.path.source(fs, SourceRange::default()).await.ok()?;
+        let source_digest = crate::modules::source_digest(&source);
+        if info.source_digest == Some(source_digest) {
+            continue;
+        }
+        let mut new_ast = crate::parsing::parse_str(&source, info.id).parse_errs_as_err().ok()?;
+        let mut old_ast = old_ast.clone();
+        if old_ast.compute_digest() != new_ast.compute_digest() {
+            changed.push((info.id, new_ast));
+        }
+        info.source_digest = Some(source_digest);
+    }
+    Some(changed)
+}
+
 /// Force-generate a new CacheResult, even if one shouldn't be made. The
 /// way in which this gets invoked should always be through
 /// [get_changed_program]. This is purely to contain the logic on
@@ -251,7 +286,7 @@ fn generate_changed_program(old_ast: Node<Program>, mut new_ast: Node<Program>,
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::execution::parse_execute;
+    use crate::execution::{parse_execute, parse_execute_with_settings};
 
     #[tokio::test(flavor = "multi_thread")]
     async fn test_get_changed_program_same_code() {
@@ -572,4 +607,72 @@ startSketchOn('XY')
             }
         );
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_get_changed_modules_reexecutes_dependents() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        std::fs::write(dir.join("a.kcl"), "export a = 1\n").unwrap();
+        std::fs::write(dir.join("b.kcl"), "import a from \"a.kcl\"\nexport b = a + 10\n").unwrap();
+        std::fs::write(dir.join("c.kcl"), "export c = 100\n").unwrap();
+        let code = r#"import b from "b.kcl"
+import c from "c.kcl"
+total = b + c
+"#;
+
+        let mut settings = ExecutorSettings::default();
+        settings.with_current_file(dir.join("main.kcl"));
+        let (program, ctx, mut exec_state) = parse_execute_with_settings(code, settings).await.unwrap();
+        let id =
+            |exec_state: &ExecState, name: &str| exec_state.id_for_module(&ModulePath::Local(dir.join(name))).unwrap();
+        let is_cached = |exec_state: &ExecState, name: &str| {
+            matches!(
+                exec_state.global.module_infos[&id(exec_state, name)].repr,
+                ModuleRepr::Kcl(_, Some(_))
+            )
+        };
+
+        let changed = get_changed_modules(&ctx.fs, &mut exec_state.global.module_infos).await;
+        assert_eq!(changed, Some(Vec::new()));
+
+        // Comments don't count as changes.
+        std::fs::write(dir.join("c.kcl"), "// The answer.\nexport c = 100\n").unwrap();
+        std::fs::write(dir.join("a.kcl"), "export a = 2\n").unwrap();
+        let changed = get_changed_modules(&ctx.fs, &mut exec_state.global.module_infos)
+            .await
+            .unwrap();
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed[0].0, id(&exec_state, "a.kcl"));
+        // The new source is remembered, so it isn't parsed again next time.
+        assert_eq!(
+            exec_state.global.module_infos[&id(&exec_state, "c.kcl")].source_digest,
+            Some(crate::modules::source_digest("// The answer.\nexport c = 100\n"))
+        );
+
+        exec_state.reset_changed_modules(&ctx.settings, changed);
+        assert!(!is_cached(&exec_state, "a.kcl"));
+        assert!(!is_cached(&exec_state, "b.kcl"));
+        assert!(is_cached(&exec_state, "c.kcl"));
+
+        ctx.run(&program, &mut exec_state).await.unwrap();
+        let total = exec_state.memory().get("total", SourceRange::default()).unwrap();
+        assert_eq!(total.as_f64().unwrap(), 112.0);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_get_changed_modules_unreadable() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        std::fs::write(dir.join("a.kcl"), "export a = 1\n").unwrap();
+
+        let mut settings = ExecutorSettings::default();
+        settings.with_current_file(dir.join("main.kcl"));
+        let (_, ctx, mut exec_state) = parse_execute_with_settings("import a from \"a.kcl\"\n", settings)
+            .await
+            .unwrap();
+
+        std::fs::remove_file(dir.join("a.kcl")).unwrap();
+        let changed = get_changed_modules(&ctx.fs, &mut exec_state.global.module_infos).await;
+        assert_eq!(changed, None);
+    }
 }
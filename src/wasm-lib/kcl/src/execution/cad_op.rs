@@ -41,6 +41,16 @@ pub enum Operation {
         source_range: SourceRange,
     },
     UserDefinedFunctionReturn,
+    /// The start of one run of the body of a `for` loop. The operations until the matching
+    /// `LoopIterationEnd` were done by this run.
+    #[serde(rename_all = "camelCase")]
+    LoopIteration {
+        /// Which run of the loop this is, starting from 0.
+        index: usize,
+        /// The source range of the loop in the source code.
+        source_range: SourceRange,
+    },
+    LoopIterationEnd,
 }
 
 impl Operation {
@@ -48,7 +58,10 @@ impl Operation {
     pub(crate) fn set_std_lib_call_is_error(&mut self, is_err: bool) {
         match self {
             Self::StdLibCall { ref mut is_error, .. } => *is_error = is_err,
-            Self::UserDefinedFunctionCall { .. } | Self::UserDefinedFunctionReturn => {}
+            Self::UserDefinedFunctionCall { .. }
+            | Self::UserDefinedFunctionReturn
+            | Self::LoopIteration { .. }
+            | Self::LoopIterationEnd => {}
         }
     }
 }
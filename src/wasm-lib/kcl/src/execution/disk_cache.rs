@@ -0,0 +1,465 @@
+//! A cache of the results of executing imported modules, stored on disk so that later runs, e.g.
+//! re-opening a project or re-running it in CI, can skip re-executing modules which haven't changed.
+//!
+//! Each module has one entry, which is replaced whenever the module is executed again. The entry
+//! is only used if its key matches: a hash of everything which affects a module's result, i.e., the
+//! AST digests and source of the module and the modules it imports (recursively), the module's ID
+//! and the executor's units. The cache holds at most [`MAX_CACHE_ENTRIES`] entries of at most
+//! [`MAX_ENTRY_BYTES`] each, so it can't grow without bound.
+
+use async_recursion::async_recursion;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest as _, Sha256};
+
+use crate::{
+    engine::ExecutionKind,
+    errors::KclError,
+    execution::{EnvironmentRef, ExecState, ExecutorContext, KclValue},
+    fs::FileSystem,
+    modules::{ModuleId, ModulePath, ModuleRepr},
+    parsing::ast::types::{BodyItem, ImportPath, Node, Program},
+    source_range::SourceRange,
+};
+
+/// The name of the directory in a project where the cache is stored.
+pub(super) const CACHE_DIR_NAME: &str = ".kcl-cache";
+
+/// Bump this whenever the format of a cache entry changes, so that old entries are ignored.
+const CACHE_VERSION: u32 = 3;
+
+/// The most modules the cache holds results for. Once it's full, results for other modules aren't
+/// stored.
+const MAX_CACHE_ENTRIES: usize = 128;
+
+/// The largest an entry can be. Bigger results aren't stored, they're cheaper to recompute than to
+/// keep around.
+const MAX_ENTRY_BYTES: usize = 1024 * 1024;
+
+/// The result of executing a module, as stored in the cache.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+struct CachedModule {
+    /// The key the module had when it was executed. The entry is stale if the key has changed
+    /// since.
+    key: String,
+    /// The module's top-level variables.
+    bindings: Vec<(String, KclValue)>,
+    /// The names of the variables the module exports.
+    exports: Vec<String>,
+    /// The modules which were imported while executing the module, in the order they were opened.
+    /// Values in the module may refer to them by ID, so they must have the same IDs when the module
+    /// is restored.
+    dependencies: Vec<(ModulePath, ModuleId)>,
+    /// The IDs generated while executing the module. Restoring the module advances the ID
+    /// generator past them, so that everything executed afterwards gets the same IDs as it would
+    /// if the module had been executed.
+    ids: Vec<uuid::Uuid>,
+}
+
+impl CachedModule {
+    /// Capture a module's result, if every value in it can be restored by a later run.
+    fn new(
+        key: String,
+        exec_state: &ExecState,
+        env_ref: EnvironmentRef,
+        exports: Vec<String>,
+        dependencies: Vec<(ModulePath, ModuleId)>,
+        ids: Vec<uuid::Uuid>,
+    ) -> Option<Self> {
+        let mut bindings = Vec::new();
+        for (name, value) in exec_state.memory().find_all_in_env(env_ref, |_| true) {
+            let restorable = match value {
+                // Functions implemented in Rust can't be serialized.
+                KclValue::Function { func: Some(_), .. } => false,
+                // Functions declared in the module are restored into its environment, but
+                // closures over other environments can't be.
+                KclValue::Function { memory, .. } => memory.same_env(&env_ref),
+                value => !contains_function(value),
+            };
+            if !restorable {
+                return None;
+            }
+            bindings.push((name.clone(), value.clone()));
+        }
+
+        Some(CachedModule {
+            key,
+            bindings,
+            exports,
+            dependencies,
+            ids,
+        })
+    }
+}
+
+fn contains_function(value: &KclValue) -> bool {
+    match value {
+        KclValue::Function { .. } => true,
+        KclValue::Array { value, .. } => value.iter().any(contains_function),
+        KclValue::Object { value, .. } => value.values().any(contains_function),
+        _ => false,
+    }
+}
+
+impl ExecutorContext {
+    /// Execute a module to get its exported items, reusing the result of a previous run from the
+    /// on-disk cache if it's enabled and the module hasn't changed.
+    pub(super) async fn exec_module_with_disk_cache(
+        &self,
+        program: &Node<Program>,
+        module_id: ModuleId,
+        path: &ModulePath,
+        exec_state: &mut ExecState,
+        exec_kind: ExecutionKind,
+        source_range: SourceRange,
+    ) -> Result<(EnvironmentRef, Vec<String>), KclError> {
+        let key = match &self.settings.cache_directory {
+            Some(_) => self.module_cache_key(path, module_id).await,
+            None => None,
+        };
+        let entry_name = entry_name(path, module_id);
+
+        if let Some(key) = &key {
+            if let Some(entry) = self.read_cache_entry(&entry_name, key).await {
+                if let Some(result) = self.restore_module(entry, exec_state, source_range).await {
+                    crate::log::log(format!("Restored module {path} from the cache"));
+                    return Ok(result);
+                }
+            }
+        }
+
+        let first_new_module = exec_state.next_module_id().as_usize();
+        let first_id = exec_state.global.id_generator.next_id;
+        let (_, env_ref, exports) = self
+            .exec_module_from_ast(program, path, exec_state, exec_kind, source_range)
+            .await?;
+
+        if let Some(key) = key {
+            let dependencies = self.module_dependencies(program, first_new_module, exec_state);
+            let ids = exec_state.global.id_generator.ids_since(first_id);
+            if let Some(entry) = CachedModule::new(key, exec_state, env_ref, exports.clone(), dependencies, ids) {
+                self.write_cache_entry(&entry_name, &entry).await;
+            }
+        }
+
+        Ok((env_ref, exports))
+    }
+
+    /// The modules a module depends on: those it imports directly, and those which were opened
+    /// for the first time while it was executed.
+    fn module_dependencies(
+        &self,
+        program: &Node<Program>,
+        first_new_module: usize,
+        exec_state: &ExecState,
+    ) -> Vec<(ModulePath, ModuleId)> {
+        let mut dependencies: Vec<_> = exec_state
+            .global
+            .module_infos
+            .values()
+            .filter(|info| info.id.as_usize() >= first_new_module)
+            .map(|info| (info.path.clone(), info.id))
+            .collect();
+        for item in &program.body {
+            let BodyItem::ImportStatement(import) = item else {
+                continue;
+            };
+            let path = ModulePath::from_import_path(&import.path, &self.settings.project_directory);
+            if let Some(id) = exec_state.id_for_module(&path) {
+                if !dependencies.iter().any(|(p, _)| p == &path) {
+                    dependencies.push((path, id));
+                }
+            }
+        }
+        dependencies.sort_by_key(|(_, id)| id.as_usize());
+        dependencies
+    }
+
+    /// Restore a module from the cache. Returns `None` if its dependencies can't be given the same
+    /// IDs as when it was cached, in which case the module must be executed.
+    async fn restore_module(
+        &self,
+        entry: CachedModule,
+        exec_state: &mut ExecState,
+        source_range: SourceRange,
+    ) -> Option<(EnvironmentRef, Vec<String>)> {
+        for (path, id) in entry.dependencies {
+            if let Some(existing) = exec_state.id_for_module(&path) {
+                if existing != id {
+                    return None;
+                }
+                continue;
+            }
+            if exec_state.next_module_id() != id {
+                return None;
+            }
+            // Open the module without executing it, as if it had been imported.
+            let source = path.source(&self.fs, source_range).await.ok()?;
+            let parsed = crate::parsing::parse_str(&source, id).parse_errs_as_err().ok()?;
+            exec_state.add_module(id, path, ModuleRepr::Kcl(parsed, None), Some(&source));
+        }
+
+        exec_state.global.id_generator.replay(&entry.ids);
+        let env_ref = exec_state.mut_memory().restore_root_env(entry.bindings);
+        Some((env_ref, entry.exports))
+    }
+
+    /// Compute the cache key for a module, or `None` if the module can't be cached.
+    async fn module_cache_key(&self, path: &ModulePath, module_id: ModuleId) -> Option<String> {
+        let mut hasher = Sha256::new();
+        hasher.update(CACHE_VERSION.to_le_bytes());
+        hasher.update(module_id.as_usize().to_le_bytes());
+        hasher.update(serde_json::to_vec(&self.settings.units).ok()?);
+        self.hash_module(path, &mut hasher, &mut Vec::new()).await?;
+        Some(format!("{:x}", hasher.finalize()))
+    }
+
+    /// Hash a module and the modules it imports.
+    #[async_recursion]
+    async fn hash_module(&self, path: &ModulePath, hasher: &mut Sha256, stack: &mut Vec<ModulePath>) -> Option<()> {
+        if stack.contains(path) {
+            // Circular imports are an error when the module is executed.
+            return None;
+        }
+        let source = path.source(&self.fs, SourceRange::default()).await.ok()?;
+        let mut program = crate::parsing::parse_str(&source, ModuleId::default())
+            .parse_errs_as_err()
+            .ok()?;
+        program.compute_digest();
+        hasher.update(program.digest?);
+        // The digest ignores formatting and comments, but cached values refer to source ranges.
+        hasher.update(source.len().to_le_bytes());
+        hasher.update(source.as_bytes());
+
+        stack.push(path.clone());
+        for item in &program.body {
+            let BodyItem::ImportStatement(import) = item else {
+                continue;
+            };
+            match &import.path {
+                ImportPath::Kcl { .. } | ImportPath::Std { .. } => {
+                    let path = ModulePath::from_import_path(&import.path, &self.settings.project_directory);
+                    self.hash_module(&path, hasher, stack).await?;
+                }
+                // Foreign geometry has to be sent to the engine, so can't be cached.
+                ImportPath::Foreign { .. } => return None,
+            }
+        }
+        stack.pop();
+        Some(())
+    }
+
+    /// Read a module's entry from the cache, if it's there and was stored with the given key.
+    async fn read_cache_entry(&self, entry_name: &str, key: &str) -> Option<CachedModule> {
+        let path = self.settings.cache_directory.as_ref()?.join(entry_name);
+        if !self.fs.exists(&path, SourceRange::default()).await.ok()? {
+            return None;
+        }
+        let bytes = self.fs.read(&path, SourceRange::default()).await.ok()?;
+        let entry: CachedModule = serde_json::from_slice(&bytes).ok()?;
+        (entry.key == key).then_some(entry)
+    }
+
+    /// Write a module's entry to the cache, replacing any older one. Failing to write isn't an
+    /// error, the module will just be executed again next time.
+    async fn write_cache_entry(&self, entry_name: &str, entry: &CachedModule) {
+        let Some(dir) = &self.settings.cache_directory else {
+            return;
+        };
+        // Go via a `Value` to drop the duplicate `type` keys which some AST nodes (e.g., in function
+        // bodies) serialize with, since they can't be deserialized.
+        let Ok(bytes) = serde_json::to_value(entry).and_then(|value| serde_json::to_vec(&value)) else {
+            return;
+        };
+        if bytes.len() > MAX_ENTRY_BYTES {
+            return;
+        }
+        let path = dir.join(entry_name);
+        let replacing = self.fs.exists(&path, SourceRange::default()).await.unwrap_or(false);
+        if !replacing {
+            // The directory won't exist until the first entry is written.
+            let entries = self
+                .fs
+                .get_all_files(dir, SourceRange::default())
+                .await
+                .map(|files| files.len())
+                .unwrap_or_default();
+            if entries >= MAX_CACHE_ENTRIES {
+                crate::log::log(format!("The execution cache is full, not caching {entry_name}"));
+                return;
+            }
+        }
+        if let Err(e) = self.fs.write(path, bytes, SourceRange::default()).await {
+            crate::log::log(format!("Failed to write to the execution cache: {e}"));
+        }
+    }
+}
+
+/// The name of the file a module's entry is stored in.
+fn entry_name(path: &ModulePath, module_id: ModuleId) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(path.to_string().as_bytes());
+    hasher.update(module_id.as_usize().to_le_bytes());
+    format!("{:x}.json", hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::execution::{parse_execute_with_settings, ExecutorSettings};
+
+    const BRACKET: &str = r#"export width = 10mm
+export holes = [1, 2, 3]
+export fn double(x) {
+  return x * 2
+}
+export fn quadruple(x) {
+  return double(double(x))
+}
+"#;
+
+    const MAIN: &str = r#"import width, holes, quadruple from "bracket.kcl"
+w = quadruple(width)
+n = holes[2]
+"#;
+
+    /// A fresh, empty project directory, which is removed when it's dropped.
+    fn project_dir() -> tempfile::TempDir {
+        tempfile::tempdir().unwrap()
+    }
+
+    fn settings(dir: &std::path::Path) -> ExecutorSettings {
+        let mut settings = ExecutorSettings::default();
+        settings.with_current_file(dir.join("main.kcl"));
+        settings.with_disk_cache();
+        settings
+    }
+
+    fn cache_entries(dir: &std::path::Path) -> Vec<std::path::PathBuf> {
+        match std::fs::read_dir(dir.join(CACHE_DIR_NAME)) {
+            Ok(entries) => entries.map(|e| e.unwrap().path()).collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn imported_module_is_restored_from_disk() {
+        let tmp = project_dir();
+        let dir = tmp.path();
+        std::fs::write(dir.join("bracket.kcl"), BRACKET).unwrap();
+        std::fs::write(dir.join("main.kcl"), MAIN).unwrap();
+
+        let (_, _, cold) = parse_execute_with_settings(MAIN, settings(dir)).await.unwrap();
+        let entries = cache_entries(dir);
+        assert_eq!(entries.len(), 1);
+
+        // Tamper with the entry, so we can tell it's used rather than the module being executed.
+        let entry = std::fs::read_to_string(&entries[0]).unwrap();
+        std::fs::write(&entries[0], entry.replace("\"value\":10.0", "\"value\":20.0")).unwrap();
+
+        let (_, _, warm) = parse_execute_with_settings(MAIN, settings(dir)).await.unwrap();
+        let get = |state: &ExecState, name: &str| {
+            state
+                .memory()
+                .get(name, SourceRange::default())
+                .unwrap()
+                .as_f64()
+                .unwrap()
+        };
+        assert_eq!(get(&cold, "w"), 40.0);
+        assert_eq!(get(&warm, "w"), 80.0);
+        assert_eq!(get(&warm, "n"), 3.0);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn restored_module_advances_id_generator() {
+        let tmp = project_dir();
+        let dir = tmp.path();
+        let plate = "export plate = startSketchOn('XY')\n  |> circle({ center = [0, 0], radius = 5 }, %)\n";
+        std::fs::write(dir.join("plate.kcl"), plate).unwrap();
+        let main = r#"import plate from "plate.kcl"
+hole = startSketchOn('XZ')
+  |> circle({ center = [0, 0], radius = 1 }, %)
+"#;
+        std::fs::write(dir.join("main.kcl"), main).unwrap();
+
+        let (program, ctx, mut exec_state) = parse_execute_with_settings(main, settings(dir)).await.unwrap();
+        assert_eq!(cache_entries(dir).len(), 1);
+        let cold_ids = exec_state.global.id_generator.clone();
+        let hole_id = |state: &ExecState| match state.memory().get("hole", SourceRange::default()).unwrap() {
+            KclValue::Sketch { value } => value.id,
+            value => panic!("expected a sketch, found {value:?}"),
+        };
+        let cold_hole = hole_id(&exec_state);
+
+        // Re-run with the same generator, as the LSP and the app do.
+        exec_state.reset(&ctx.settings);
+        ctx.run(&program, &mut exec_state).await.unwrap();
+        assert_eq!(hole_id(&exec_state), cold_hole);
+        assert_eq!(exec_state.global.id_generator, cold_ids);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn changed_module_is_executed_again() {
+        let tmp = project_dir();
+        let dir = tmp.path();
+        std::fs::write(dir.join("bracket.kcl"), BRACKET).unwrap();
+        std::fs::write(dir.join("main.kcl"), MAIN).unwrap();
+        parse_execute_with_settings(MAIN, settings(dir)).await.unwrap();
+
+        std::fs::write(dir.join("bracket.kcl"), BRACKET.replace("10mm", "5mm")).unwrap();
+        let (_, _, exec_state) = parse_execute_with_settings(MAIN, settings(dir)).await.unwrap();
+        let w = exec_state.memory().get("w", SourceRange::default()).unwrap();
+        assert_eq!(w.as_f64().unwrap(), 20.0);
+        // The new version of the module replaces the old one.
+        assert_eq!(cache_entries(dir).len(), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn full_cache_is_not_written_to() {
+        let tmp = project_dir();
+        let dir = tmp.path();
+        std::fs::write(dir.join("bracket.kcl"), BRACKET).unwrap();
+        std::fs::create_dir_all(dir.join(CACHE_DIR_NAME)).unwrap();
+        for i in 0..MAX_CACHE_ENTRIES {
+            std::fs::write(dir.join(CACHE_DIR_NAME).join(format!("{i}.json")), "{}").unwrap();
+        }
+
+        let (_, _, exec_state) = parse_execute_with_settings(MAIN, settings(dir)).await.unwrap();
+        let w = exec_state.memory().get("w", SourceRange::default()).unwrap();
+        assert_eq!(w.as_f64().unwrap(), 40.0);
+        assert_eq!(cache_entries(dir).len(), MAX_CACHE_ENTRIES);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn closures_are_not_cached() {
+        let tmp = project_dir();
+        let dir = tmp.path();
+        let module = r#"fn adder(x) {
+  fn add(y) {
+    return x + y
+  }
+  return add
+}
+export addTwo = adder(2)
+"#;
+        std::fs::write(dir.join("adder.kcl"), module).unwrap();
+        let main = "import addTwo from \"adder.kcl\"\nx = addTwo(1)\n";
+        let (_, _, exec_state) = parse_execute_with_settings(main, settings(dir)).await.unwrap();
+        let x = exec_state.memory().get("x", SourceRange::default()).unwrap();
+        assert_eq!(x.as_f64().unwrap(), 3.0);
+        assert!(cache_entries(dir).is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn no_cache_without_cache_directory() {
+        let tmp = project_dir();
+        let dir = tmp.path();
+        std::fs::write(dir.join("bracket.kcl"), BRACKET).unwrap();
+        let mut settings = ExecutorSettings::default();
+        settings.with_current_file(dir.join("main.kcl"));
+        parse_execute_with_settings(MAIN, settings).await.unwrap();
+        assert!(cache_entries(dir).is_empty());
+    }
+}
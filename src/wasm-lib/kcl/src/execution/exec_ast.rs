@@ -18,8 +18,8 @@ use crate::{
     modules::{ModuleId, ModulePath, ModuleRepr},
     parsing::ast::types::{
         Annotation, ArrayExpression, ArrayRangeExpression, BinaryExpression, BinaryOperator, BinaryPart, BodyItem,
-        CallExpression, CallExpressionKw, Expr, FunctionExpression, IfExpression, ImportPath, ImportSelector,
-        ItemVisibility, LiteralIdentifier, LiteralValue, MemberExpression, MemberObject, Node, NodeRef,
+        CallExpression, CallExpressionKw, Expr, ForExpression, FunctionExpression, IfExpression, ImportPath,
+        ImportSelector, ItemVisibility, LiteralIdentifier, LiteralValue, MemberExpression, MemberObject, Node, NodeRef,
         ObjectExpression, PipeExpression, Program, TagDeclarator, UnaryExpression, UnaryOperator,
     },
     source_range::SourceRange,
@@ -224,6 +224,10 @@ impl ExecutorContext {
                     }
                     last_expr = None;
                 }
+                // Types are only used to check the program before it's executed.
+                BodyItem::TypeDeclaration(_) => {
+                    last_expr = None;
+                }
                 BodyItem::ReturnStatement(return_statement) => {
                     let metadata = Metadata::from(return_statement);
 
@@ -291,7 +295,7 @@ impl ExecutorContext {
                 let source = resolved_path.source(&self.fs, source_range).await?;
                 // TODO handle parsing errors properly
                 let parsed = crate::parsing::parse_str(&source, id).parse_errs_as_err()?;
-                exec_state.add_module(id, resolved_path, ModuleRepr::Kcl(parsed, None));
+                exec_state.add_module(id, resolved_path, ModuleRepr::Kcl(parsed, None), Some(&source));
 
                 Ok(id)
             }
@@ -304,7 +308,7 @@ impl ExecutorContext {
                 let path = resolved_path.expect_path();
                 let format = super::import::format_from_annotations(attrs, path, source_range)?;
                 let geom = super::import::import_foreign(path, format, exec_state, self, source_range).await?;
-                exec_state.add_module(id, resolved_path, ModuleRepr::Foreign(geom));
+                exec_state.add_module(id, resolved_path, ModuleRepr::Foreign(geom), None);
                 Ok(id)
             }
             ImportPath::Std { .. } => {
@@ -315,7 +319,7 @@ impl ExecutorContext {
                 let id = exec_state.next_module_id();
                 let source = resolved_path.source(&self.fs, source_range).await?;
                 let parsed = crate::parsing::parse_str(&source, id).parse_errs_as_err().unwrap();
-                exec_state.add_module(id, resolved_path, ModuleRepr::Kcl(parsed, None));
+                exec_state.add_module(id, resolved_path, ModuleRepr::Kcl(parsed, None), Some(&source));
                 Ok(id)
             }
         }
@@ -336,9 +340,9 @@ impl ExecutorContext {
             ModuleRepr::Root => Err(exec_state.circular_import_error(&path, source_range)),
             ModuleRepr::Kcl(_, Some((env_ref, items))) => Ok((*env_ref, items.clone())),
             ModuleRepr::Kcl(program, cache) => self
-                .exec_module_from_ast(program, &path, exec_state, exec_kind, source_range)
+                .exec_module_with_disk_cache(program, module_id, &path, exec_state, exec_kind, source_range)
                 .await
-                .map(|(_, er, items)| {
+                .map(|(er, items)| {
                     *cache = Some((er, items.clone()));
                     (er, items)
                 }),
@@ -380,7 +384,7 @@ impl ExecutorContext {
         result
     }
 
-    async fn exec_module_from_ast(
+    pub(super) async fn exec_module_from_ast(
         &self,
         program: &Node<Program>,
         path: &ModulePath,
@@ -499,6 +503,7 @@ impl ExecutorContext {
             Expr::MemberExpression(member_expression) => member_expression.get_result(exec_state)?,
             Expr::UnaryExpression(unary_expression) => unary_expression.get_result(exec_state, self).await?,
             Expr::IfExpression(expr) => expr.get_result(exec_state, self).await?,
+            Expr::ForExpression(expr) => expr.get_result(exec_state, self).await?,
             Expr::LabelledExpression(expr) => {
                 let result = self
                     .execute_expr(&expr.expr, exec_state, metadata, statement_kind)
@@ -620,6 +625,17 @@ impl Node<MemberExpression> {
             (KclValue::Solid { value }, Property::String(prop)) if prop == "sketch" => Ok(KclValue::Sketch {
                 value: Box::new(value.sketch),
             }),
+            (KclValue::Solid { value }, Property::String(prop)) if prop == "tags" => Ok(KclValue::Object {
+                meta: vec![Metadata {
+                    source_range: SourceRange::from(self.clone()),
+                }],
+                value: value
+                    .sketch
+                    .tags
+                    .iter()
+                    .map(|(k, tag)| (k.to_owned(), KclValue::TagIdentifier(Box::new(tag.to_owned()))))
+                    .collect(),
+            }),
             (KclValue::Sketch { value: sk }, Property::String(prop)) if prop == "tags" => Ok(KclValue::Object {
                 meta: vec![Metadata {
                     source_range: SourceRange::from(self.clone()),
@@ -703,36 +719,54 @@ impl Node<BinaryExpression> {
         let left = parse_number_as_f64(&left_value, self.left.clone().into())?;
         let right = parse_number_as_f64(&right_value, self.right.clone().into())?;
 
+        // Both operands are converted into the module's default units, so e.g. `1inch + 1mm` is
+        // the same as `1mm + 1inch`, and can be passed to std functions which assume those units.
+        let (left, left_ty) = if self.operator == BinaryOperator::Pow {
+            (left, left_value.numeric_type())
+        } else {
+            left_value
+                .numeric_type()
+                .convert_to_units(left, exec_state.length_unit(), exec_state.angle_unit())
+        };
+        let (right, right_ty) = if self.operator == BinaryOperator::Pow {
+            (right, right_value.numeric_type())
+        } else {
+            right_value
+                .numeric_type()
+                .convert_to_units(right, exec_state.length_unit(), exec_state.angle_unit())
+        };
+        let ty = NumericType::binary_result(&self.operator, &left_ty, &right_ty);
+
         let value = match self.operator {
             BinaryOperator::Add => KclValue::Number {
                 value: left + right,
                 meta,
-                ty: NumericType::Unknown,
+                ty,
             },
             BinaryOperator::Sub => KclValue::Number {
                 value: left - right,
                 meta,
-                ty: NumericType::Unknown,
+                ty,
             },
             BinaryOperator::Mul => KclValue::Number {
                 value: left * right,
                 meta,
-                ty: NumericType::Unknown,
+                ty,
             },
             BinaryOperator::Div => KclValue::Number {
                 value: left / right,
                 meta,
-                ty: NumericType::Unknown,
+                ty,
             },
             BinaryOperator::Mod => KclValue::Number {
                 value: left % right,
                 meta,
-                ty: NumericType::Unknown,
+                ty,
             },
             BinaryOperator::Pow => KclValue::Number {
                 value: left.powf(right),
                 meta,
-                ty: NumericType::Unknown,
+                ty,
             },
             BinaryOperator::Neq => KclValue::Bool {
                 value: left != right,
@@ -1404,6 +1438,54 @@ impl Node<IfExpression> {
     }
 }
 
+impl Node<ForExpression> {
+    #[async_recursion]
+    pub async fn get_result(&self, exec_state: &mut ExecState, ctx: &ExecutorContext) -> Result<KclValue, KclError> {
+        let metadata = Metadata::from(&*self.iterable);
+        let iterable = ctx
+            .execute_expr(&self.iterable, exec_state, &metadata, StatementKind::Expression)
+            .await?;
+        let Some(items) = iterable.as_array() else {
+            return Err(KclError::Semantic(KclErrorDetails {
+                message: format!(
+                    "Expected an array to loop over, but found {}",
+                    iterable.human_friendly_type()
+                ),
+                source_ranges: vec![(&*self.iterable).into()],
+            }));
+        };
+
+        let mut results = Vec::with_capacity(items.len());
+        for (index, item) in items.iter().enumerate() {
+            exec_state.mod_local.operations.push(Operation::LoopIteration {
+                index,
+                source_range: self.into(),
+            });
+
+            // Each run of the body gets its own scope, so it can declare the same names again.
+            exec_state.mut_memory().push_new_env_for_scope();
+            let result = match exec_state
+                .mut_memory()
+                .add(self.var.name.clone(), item.clone(), (&self.var).into())
+            {
+                Ok(()) => ctx.exec_program(&self.body, exec_state, BodyType::Block).await,
+                Err(e) => Err(e),
+            };
+            exec_state.mut_memory().pop_env();
+
+            exec_state.mod_local.operations.push(Operation::LoopIterationEnd);
+            // Block must end in an expression, so this has to be Some.
+            // Enforced by the parser.
+            results.push(result?.unwrap());
+        }
+
+        Ok(KclValue::Array {
+            value: results,
+            meta: vec![self.into()],
+        })
+    }
+}
+
 #[derive(Debug)]
 enum Property {
     UInt(usize),
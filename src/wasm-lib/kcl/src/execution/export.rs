@@ -0,0 +1,156 @@
+//! Exporting the scene to CAD and mesh file formats.
+
+pub use kcmc::shared::FileExportFormat;
+use kcmc::{
+    coord::System,
+    format::{fbx, gltf, obj, ply, step, stl, OutputFormat, Selection},
+};
+use kittycad_modeling_cmds as kcmc;
+use serde::{Deserialize, Serialize};
+
+use super::{annotations, import::ZOO_COORD_SYSTEM};
+use crate::{
+    errors::{KclError, KclErrorDetails},
+    settings::types::UnitLength,
+};
+
+/// How to write an exported file. Options only apply to the formats which support them, and are
+/// ignored by the others. They are named like the options for importing a file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportOptions {
+    /// The length unit of the file's coordinates, for OBJ, PLY and STL. Defaults to the units of
+    /// the program which was executed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub length_unit: Option<UnitLength>,
+    /// The coordinate system of the file, for OBJ, PLY, STEP and STL. One of `zoo`, `opengl` or
+    /// `vulkan`. Defaults to `zoo`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coords: Option<String>,
+    /// Whether to write a binary file rather than a text file, for FBX, glTF, PLY and STL.
+    /// Defaults to binary. GLB is always binary.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub binary: Option<bool>,
+}
+
+impl ExportOptions {
+    /// The format the engine should write, with these options applied.
+    pub(super) fn output_format(
+        &self,
+        format: FileExportFormat,
+        default_units: UnitLength,
+    ) -> Result<OutputFormat, KclError> {
+        let coords = self.coords()?;
+        let units = self.length_unit.unwrap_or(default_units).into();
+        let binary = self.binary.unwrap_or(true);
+
+        Ok(match format {
+            FileExportFormat::Fbx => OutputFormat::Fbx(fbx::export::Options {
+                storage: if binary {
+                    fbx::export::Storage::Binary
+                } else {
+                    fbx::export::Storage::Ascii
+                },
+                created: None,
+            }),
+            // A glTF file with its buffers embedded, so every export is a single file.
+            FileExportFormat::Gltf | FileExportFormat::Glb => OutputFormat::Gltf(gltf::export::Options {
+                storage: if binary || format == FileExportFormat::Glb {
+                    gltf::export::Storage::Binary
+                } else {
+                    gltf::export::Storage::Embedded
+                },
+                presentation: Default::default(),
+            }),
+            FileExportFormat::Obj => OutputFormat::Obj(obj::export::Options { coords, units }),
+            FileExportFormat::Ply => OutputFormat::Ply(ply::export::Options {
+                coords,
+                selection: Selection::DefaultScene,
+                storage: if binary {
+                    ply::export::Storage::BinaryLittleEndian
+                } else {
+                    ply::export::Storage::Ascii
+                },
+                units,
+            }),
+            FileExportFormat::Step => OutputFormat::Step(step::export::Options { coords, created: None }),
+            FileExportFormat::Stl => OutputFormat::Stl(stl::export::Options {
+                coords,
+                selection: Selection::DefaultScene,
+                storage: if binary {
+                    stl::export::Storage::Binary
+                } else {
+                    stl::export::Storage::Ascii
+                },
+                units,
+            }),
+        })
+    }
+
+    fn coords(&self) -> Result<System, KclError> {
+        let Some(coords) = &self.coords else {
+            return Ok(ZOO_COORD_SYSTEM);
+        };
+
+        annotations::IMPORT_COORDS_VALUES
+            .iter()
+            .find(|(name, _)| name == coords)
+            .map(|(_, system)| **system)
+            .ok_or_else(|| {
+                KclError::Semantic(KclErrorDetails {
+                    message: format!(
+                        "Unknown coordinate system: {coords}, expected one of: {}",
+                        annotations::IMPORT_COORDS_VALUES
+                            .iter()
+                            .map(|(n, _)| *n)
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                    source_ranges: vec![],
+                })
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use kcmc::{coord::OPENGL, units::UnitLength as EngineUnitLength};
+
+    use super::*;
+
+    #[test]
+    fn output_format_applies_options() {
+        let options = ExportOptions {
+            length_unit: Some(UnitLength::In),
+            coords: Some("opengl".to_owned()),
+            binary: Some(false),
+        };
+        let OutputFormat::Stl(stl) = options.output_format(FileExportFormat::Stl, UnitLength::Mm).unwrap() else {
+            panic!("expected STL options");
+        };
+        assert_eq!(stl.units, EngineUnitLength::Inches);
+        assert_eq!(stl.coords, *OPENGL);
+        assert_eq!(stl.storage, stl::export::Storage::Ascii);
+
+        let OutputFormat::Obj(obj) = ExportOptions::default()
+            .output_format(FileExportFormat::Obj, UnitLength::Cm)
+            .unwrap()
+        else {
+            panic!("expected OBJ options");
+        };
+        assert_eq!(obj.units, EngineUnitLength::Centimeters);
+        assert_eq!(obj.coords, ZOO_COORD_SYSTEM);
+    }
+
+    #[test]
+    fn unknown_coords() {
+        let options = ExportOptions {
+            coords: Some("directx".to_owned()),
+            ..Default::default()
+        };
+        let err = options
+            .output_format(FileExportFormat::Step, UnitLength::Mm)
+            .unwrap_err();
+        assert!(err.message().contains("Unknown coordinate system: directx"));
+    }
+}
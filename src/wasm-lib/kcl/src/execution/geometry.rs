@@ -675,6 +675,16 @@ impl Point3d {
     }
 }
 
+impl From<[f64; 3]> for Point3d {
+    fn from(p: [f64; 3]) -> Self {
+        Self {
+            x: p[0],
+            y: p[1],
+            z: p[2],
+        }
+    }
+}
+
 impl From<Point3d> for Point3D {
     fn from(p: Point3d) -> Self {
         Self { x: p.x, y: p.y, z: p.z }
@@ -796,6 +806,17 @@ pub enum Path {
         /// True if the arc is counterclockwise.
         ccw: bool,
     },
+    /// A cubic Bézier curve, like a piece of a spline.
+    Bezier {
+        #[serde(flatten)]
+        base: BasePath,
+        /// The control point the curve leaves its start towards.
+        #[ts(type = "[number, number]")]
+        control1: [f64; 2],
+        /// The control point the curve arrives at its end from.
+        #[ts(type = "[number, number]")]
+        control2: [f64; 2],
+    },
 }
 
 /// What kind of path is this?
@@ -809,6 +830,7 @@ enum PathType {
     Horizontal,
     AngledLineTo,
     Arc,
+    Bezier,
 }
 
 impl From<&Path> for PathType {
@@ -822,6 +844,7 @@ impl From<&Path> for PathType {
             Path::AngledLineTo { .. } => Self::AngledLineTo,
             Path::Base { .. } => Self::Base,
             Path::Arc { .. } => Self::Arc,
+            Path::Bezier { .. } => Self::Bezier,
         }
     }
 }
@@ -837,6 +860,7 @@ impl Path {
             Path::TangentialArc { base, .. } => base.geo_meta.id,
             Path::Circle { base, .. } => base.geo_meta.id,
             Path::Arc { base, .. } => base.geo_meta.id,
+            Path::Bezier { base, .. } => base.geo_meta.id,
         }
     }
 
@@ -850,6 +874,7 @@ impl Path {
             Path::TangentialArc { base, .. } => base.tag.clone(),
             Path::Circle { base, .. } => base.tag.clone(),
             Path::Arc { base, .. } => base.tag.clone(),
+            Path::Bezier { base, .. } => base.tag.clone(),
         }
     }
 
@@ -863,6 +888,7 @@ impl Path {
             Path::TangentialArc { base, .. } => base,
             Path::Circle { base, .. } => base,
             Path::Arc { base, .. } => base,
+            Path::Bezier { base, .. } => base,
         }
     }
 
@@ -903,6 +929,22 @@ impl Path {
                 // TODO: Call engine utils to figure this out.
                 linear_distance(self.get_from(), self.get_to())
             }
+            Self::Bezier {
+                base,
+                control1,
+                control2,
+            } => {
+                // Close enough for a smooth curve.
+                let points: Vec<_> = (0..=BEZIER_LENGTH_SAMPLES)
+                    .map(|i| {
+                        bezier_point(
+                            [base.from, *control1, *control2, base.to],
+                            i as f64 / BEZIER_LENGTH_SAMPLES as f64,
+                        )
+                    })
+                    .collect();
+                points.windows(2).map(|w| linear_distance(&w[0], &w[1])).sum()
+            }
         }
     }
 
@@ -916,6 +958,7 @@ impl Path {
             Path::TangentialArc { base, .. } => Some(base),
             Path::Circle { base, .. } => Some(base),
             Path::Arc { base, .. } => Some(base),
+            Path::Bezier { base, .. } => Some(base),
         }
     }
 
@@ -938,10 +981,25 @@ impl Path {
                 let base = self.get_base();
                 GetTangentialInfoFromPathsResult::PreviousPoint(base.from)
             }
+            // A Bézier curve arrives at its end heading away from its second control point.
+            Path::Bezier { control2, .. } => GetTangentialInfoFromPathsResult::PreviousPoint(*control2),
         }
     }
 }
 
+/// How many straight lines to measure the length of a Bézier curve along.
+const BEZIER_LENGTH_SAMPLES: usize = 32;
+
+/// The point a fraction `t` of the way along a cubic Bézier curve, by its parameter.
+pub(crate) fn bezier_point([p0, p1, p2, p3]: [[f64; 2]; 4], t: f64) -> [f64; 2] {
+    let s = 1.0 - t;
+    let weights = [s * s * s, 3.0 * s * s * t, 3.0 * s * t * t, t * t * t];
+    [
+        weights[0] * p0[0] + weights[1] * p1[0] + weights[2] * p2[0] + weights[3] * p3[0],
+        weights[0] * p0[1] + weights[1] * p1[1] + weights[2] * p2[1] + weights[3] * p3[1],
+    ]
+}
+
 /// Compute the straight-line distance between a pair of (2D) points.
 #[rustfmt::skip]
 fn linear_distance(
@@ -1031,6 +1089,16 @@ impl ExtrudeSurface {
         }
     }
 
+    /// The id of the face in the engine.
+    pub fn face_id(&self) -> uuid::Uuid {
+        match self {
+            ExtrudeSurface::ExtrudePlane(ep) => ep.face_id,
+            ExtrudeSurface::ExtrudeArc(ea) => ea.face_id,
+            ExtrudeSurface::Fillet(f) => f.face_id,
+            ExtrudeSurface::Chamfer(c) => c.face_id,
+        }
+    }
+
     pub fn get_tag(&self) -> Option<Node<TagDeclarator>> {
         match self {
             ExtrudeSurface::ExtrudePlane(ep) => ep.tag.clone(),
@@ -13,7 +13,8 @@ use crate::{
     },
     parsing::{
         ast::types::{
-            DefaultParamVal, FunctionExpression, KclNone, Literal, LiteralValue, Node, TagDeclarator, TagNode,
+            BinaryOperator, DefaultParamVal, FunctionExpression, KclNone, Literal, LiteralValue, Node, TagDeclarator,
+            TagNode,
         },
         token::NumericSuffix,
     },
@@ -462,6 +463,15 @@ impl KclValue {
         }
     }
 
+    /// The units of a number, or `Unknown` if this isn't a number.
+    pub fn numeric_type(&self) -> NumericType {
+        if let KclValue::Number { ty, .. } = &self {
+            ty.clone()
+        } else {
+            NumericType::Unknown
+        }
+    }
+
     pub fn as_bool(&self) -> Option<bool> {
         if let KclValue::Bool { value, meta: _ } = &self {
             Some(*value)
@@ -640,11 +650,59 @@ impl NumericType {
         NumericType::Known(UnitType::Count)
     }
 
+    /// The type of two numbers used together, e.g. the legs of a triangle. The numbers must be
+    /// in the same units, see [`NumericType::convert_to`].
     pub fn combine(self, other: &NumericType) -> NumericType {
-        if &self == other {
-            self
-        } else {
-            NumericType::Unknown
+        Self::binary_result(&BinaryOperator::Add, &self, other)
+    }
+
+    /// Convert a number of this type into the units of `target`. Only lengths, areas and angles
+    /// are converted, and only into the units of the same kind of quantity.
+    pub fn convert_to(&self, value: f64, target: &NumericType) -> f64 {
+        match (self, target) {
+            (NumericType::Known(from), NumericType::Known(to)) => from.convert_to(value, to),
+            _ => value,
+        }
+    }
+
+    /// Convert a number of this type into the given length and angle units, e.g. a module's
+    /// default units, returning the converted number and its type.
+    pub fn convert_to_units(&self, value: f64, len: UnitLen, angle: UnitAngle) -> (f64, NumericType) {
+        let target = match self {
+            NumericType::Known(UnitType::Length(_)) => UnitType::Length(len),
+            NumericType::Known(UnitType::Area(_)) => UnitType::Area(len),
+            NumericType::Known(UnitType::Angle(_)) => UnitType::Angle(angle),
+            _ => return (value, self.clone()),
+        };
+        let target = NumericType::Known(target);
+        (self.convert_to(value, &target), target)
+    }
+
+    /// The type of the result of a binary operation on numbers of these types. Both operands
+    /// should already be in the same units, see [`NumericType::convert_to_units`].
+    pub fn binary_result(op: &BinaryOperator, left: &NumericType, right: &NumericType) -> NumericType {
+        use NumericType::{Default, Known};
+        match op {
+            BinaryOperator::Add | BinaryOperator::Sub | BinaryOperator::Mod => match (left, right) {
+                (Known(a), Known(b)) if a.same_quantity(b) => left.clone(),
+                (Known(_), Default { .. }) => left.clone(),
+                (Default { .. }, Known(_)) => right.clone(),
+                (Default { .. }, Default { .. }) if left == right => left.clone(),
+                _ => NumericType::Unknown,
+            },
+            BinaryOperator::Mul => match (left, right) {
+                (Known(UnitType::Length(len)), Known(UnitType::Length(_))) => Known(UnitType::Area(*len)),
+                (Known(_), Known(UnitType::Count) | Default { .. }) => left.clone(),
+                (Known(UnitType::Count) | Default { .. }, Known(_)) => right.clone(),
+                _ => NumericType::Unknown,
+            },
+            BinaryOperator::Div => match (left, right) {
+                (Known(a), Known(b)) if a.same_quantity(b) => NumericType::count(),
+                (Known(UnitType::Area(len)), Known(UnitType::Length(_))) => Known(UnitType::Length(*len)),
+                (Known(_), Known(UnitType::Count) | Default { .. }) => left.clone(),
+                _ => NumericType::Unknown,
+            },
+            _ => NumericType::Unknown,
         }
     }
 
@@ -667,6 +725,19 @@ impl NumericType {
     }
 }
 
+impl std::fmt::Display for NumericType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NumericType::Known(UnitType::Count) => write!(f, "number(_)"),
+            NumericType::Known(UnitType::Length(len)) => write!(f, "number({len})"),
+            NumericType::Known(UnitType::Area(len)) => write!(f, "number({len}²)"),
+            NumericType::Known(UnitType::Angle(angle)) => write!(f, "number({angle})"),
+            NumericType::Default { len, .. } => write!(f, "number({len})"),
+            NumericType::Unknown | NumericType::Any => write!(f, "number"),
+        }
+    }
+}
+
 impl From<UnitLen> for NumericType {
     fn from(value: UnitLen) -> Self {
         NumericType::Known(UnitType::Length(value))
@@ -681,13 +752,42 @@ impl From<UnitAngle> for NumericType {
 
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, ts_rs::TS, JsonSchema)]
 #[ts(export)]
-#[serde(tag = "type")]
+// Adjacently tagged, since the units are themselves tagged enums. With `tag = "type"`,
+// `Known(Length(Mm))` serialized as `{"type":"Known","type":"Length","type":"Mm"}`, which can't be
+// deserialized, so values with units couldn't be read back, e.g. from the on-disk module cache.
+#[serde(tag = "quantity", content = "units")]
 pub enum UnitType {
     Count,
     Length(UnitLen),
+    /// The product of two lengths.
+    Area(UnitLen),
     Angle(UnitAngle),
 }
 
+impl UnitType {
+    /// Do both types measure the same kind of quantity, e.g. both are lengths?
+    pub fn same_quantity(&self, other: &UnitType) -> bool {
+        matches!(
+            (self, other),
+            (UnitType::Count, UnitType::Count)
+                | (UnitType::Length(_), UnitType::Length(_))
+                | (UnitType::Area(_), UnitType::Area(_))
+                | (UnitType::Angle(_), UnitType::Angle(_))
+        )
+    }
+
+    /// Convert a value of this type into the units of `target`, which must be the same kind of
+    /// quantity.
+    fn convert_to(&self, value: f64, target: &UnitType) -> f64 {
+        match (self, target) {
+            (UnitType::Length(from), UnitType::Length(to)) => from.convert_to(value, *to),
+            (UnitType::Area(from), UnitType::Area(to)) => from.convert_to(from.convert_to(value, *to), *to),
+            (UnitType::Angle(from), UnitType::Angle(to)) => from.convert_to(value, *to),
+            _ => value,
+        }
+    }
+}
+
 // TODO called UnitLen so as not to clash with UnitLength in settings)
 #[derive(Debug, Default, Clone, Copy, Deserialize, Serialize, PartialEq, ts_rs::TS, JsonSchema, Eq)]
 #[ts(export)]
@@ -702,6 +802,28 @@ pub enum UnitLen {
     Yards,
 }
 
+impl UnitLen {
+    fn in_mm(self) -> f64 {
+        match self {
+            UnitLen::Mm => 1.0,
+            UnitLen::Cm => 10.0,
+            UnitLen::M => 1000.0,
+            UnitLen::Inches => 25.4,
+            UnitLen::Feet => 304.8,
+            UnitLen::Yards => 914.4,
+        }
+    }
+
+    /// Convert a length in these units into `target` units.
+    pub fn convert_to(self, value: f64, target: UnitLen) -> f64 {
+        if self == target {
+            value
+        } else {
+            value * self.in_mm() / target.in_mm()
+        }
+    }
+}
+
 impl std::fmt::Display for UnitLen {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -779,6 +901,17 @@ pub enum UnitAngle {
     Radians,
 }
 
+impl UnitAngle {
+    /// Convert an angle in these units into `target` units.
+    pub fn convert_to(self, value: f64, target: UnitAngle) -> f64 {
+        match (self, target) {
+            (UnitAngle::Degrees, UnitAngle::Radians) => value.to_radians(),
+            (UnitAngle::Radians, UnitAngle::Degrees) => value.to_degrees(),
+            _ => value,
+        }
+    }
+}
+
 impl std::fmt::Display for UnitAngle {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -202,6 +202,50 @@ impl ProgramMemory {
         }
     }
 
+    /// Clear the root environment, keeping all other environments.
+    ///
+    /// Suitable for re-executing the root module while reusing the results of other modules. Since
+    /// modules can't reference the root module, nothing reachable outside it refers to its
+    /// environment.
+    pub fn reset_root_env(&mut self) {
+        assert!(self.call_stack.is_empty());
+        self.environments[EnvironmentRef::root().index()] = Environment::new_root();
+        self.current_env = EnvironmentRef::root();
+    }
+
+    /// Free the environments which can't be reached from `live` or the root environment, i.e.,
+    /// which aren't one of them, the parent of a reachable environment or the environment a function
+    /// in a reachable environment was declared in.
+    ///
+    /// Suitable for dropping the environments of modules which will be executed again. Freed
+    /// environments are emptied rather than removed so that the references to other environments
+    /// stay valid, unless they're at the end.
+    pub fn free_unreachable_envs(&mut self, live: impl IntoIterator<Item = EnvironmentRef>) {
+        assert!(self.call_stack.is_empty());
+
+        let mut reachable = vec![false; self.environments.len()];
+        let mut stack: Vec<usize> = live.into_iter().map(|env| env.index()).collect();
+        stack.push(EnvironmentRef::root().index());
+        while let Some(index) = stack.pop() {
+            if reachable[index] {
+                continue;
+            }
+            reachable[index] = true;
+            stack.extend(self.environments[index].referenced_envs().map(|env| env.index()));
+        }
+
+        for (env, reachable) in self.environments.iter_mut().zip(&reachable) {
+            if !reachable {
+                env.free();
+                self.stats.env_gcs += 1;
+            }
+        }
+        // The root environment is always reachable, so this stops there at the latest.
+        while !reachable[self.environments.len() - 1] {
+            self.environments.pop();
+        }
+    }
+
     /// Push a new (standard KCL) stack frame on to the call stack.
     ///
     /// `parent` is the environment where the function being called is declared (not the caller's
@@ -411,6 +455,31 @@ impl ProgramMemory {
         self.environments[self.current_env.index()].find_all_by(pred)
     }
 
+    /// Iterate over all key/value pairs in the given environment which satisfy the provided
+    /// predicate.
+    pub fn find_all_in_env<'a>(
+        &'a self,
+        env: EnvironmentRef,
+        pred: impl Fn(&KclValue) -> bool + 'a,
+    ) -> impl Iterator<Item = (&'a String, &'a KclValue)> {
+        self.environments[env.index()].find_all_by(pred)
+    }
+
+    /// Create a new root environment containing the given bindings, e.g., the top-level variables
+    /// of a module which were saved by an earlier execution. Functions in the bindings are
+    /// re-attached to the new environment.
+    pub fn restore_root_env(&mut self, bindings: Vec<(String, KclValue)>) -> EnvironmentRef {
+        self.push_new_root_env();
+        let env_ref = self.current_env;
+        for (key, mut value) in bindings {
+            if let KclValue::Function { memory, .. } = &mut value {
+                *memory = env_ref;
+            }
+            self.insert_or_update(key, value);
+        }
+        self.pop_env()
+    }
+
     /// Walk all values accessible from any environment in the call stack.
     ///
     /// This may include duplicate values or different versions of a value known by the same key,
@@ -518,6 +587,11 @@ impl EnvironmentRef {
     fn is_rust_env(&self) -> bool {
         self.0 == usize::MAX
     }
+
+    /// Whether both refer to the same environment, possibly at different points in time.
+    pub fn same_env(&self, other: &EnvironmentRef) -> bool {
+        self.0 == other.0
+    }
 }
 
 /// An index pointing to a snapshot within a specific (unspecified) environment.
@@ -785,6 +859,35 @@ mod env {
                 .filter(move |(_, v)| f(v) && !matches!(v, KclValue::Tombstone { .. }))
         }
 
+        /// The environments this one refers to: its parent and those the functions in it (including
+        /// old versions in snapshots) were declared in.
+        pub(super) fn referenced_envs(&self) -> impl Iterator<Item = EnvironmentRef> + '_ {
+            fn declared_in(value: &KclValue, envs: &mut Vec<EnvironmentRef>) {
+                match value {
+                    KclValue::Function { memory, .. } => envs.push(*memory),
+                    KclValue::Array { value, .. } => value.iter().for_each(|v| declared_in(v, envs)),
+                    KclValue::Object { value, .. } => value.values().for_each(|v| declared_in(v, envs)),
+                    _ => {}
+                }
+            }
+
+            let mut envs = Vec::new();
+            for value in self
+                .bindings
+                .values()
+                .chain(self.snapshots.iter().flat_map(|s| s.data.values()))
+            {
+                declared_in(value, &mut envs);
+            }
+            self.parent.into_iter().chain(envs).filter(|env| !env.is_rust_env())
+        }
+
+        /// Delete everything in the environment, once nothing can refer to it.
+        pub(super) fn free(&mut self) {
+            self.bindings = IndexMap::new();
+            self.snapshots = Vec::new();
+        }
+
         /// Take all bindings from the environment.
         pub(super) fn take_bindings(&mut self) -> impl Iterator<Item = (String, KclValue)> {
             let bindings = std::mem::take(&mut self.bindings);
@@ -1234,4 +1337,49 @@ mod test {
         assert_eq!(mem.environments.len(), 1);
         assert_eq!(mem.current_env, EnvironmentRef(0, SnapshotRef(0)));
     }
+
+    #[test]
+    fn free_unreachable_envs() {
+        let func = |memory| KclValue::Function {
+            func: None,
+            expression: crate::parsing::ast::types::FunctionExpression::dummy(),
+            memory,
+            meta: Vec::new(),
+        };
+        let mem = &mut ProgramMemory::new();
+        mem.add("a".to_owned(), val(1), sr()).unwrap();
+
+        // A module which will be executed again.
+        mem.push_new_root_env();
+        mem.add("b".to_owned(), val(2), sr()).unwrap();
+        let stale = mem.pop_env();
+
+        // A module which is kept, holding a closure from a call to one of its functions.
+        mem.push_new_root_env();
+        mem.add("c".to_owned(), val(3), sr()).unwrap();
+        let sn = mem.snapshot();
+        mem.add("f".to_owned(), func(sn), sr()).unwrap();
+        mem.push_new_env_for_call(sn);
+        mem.add("d".to_owned(), val(4), sr()).unwrap();
+        let closure = mem.snapshot();
+        let call = mem.pop_and_preserve_env();
+        mem.add("g".to_owned(), func(closure), sr()).unwrap();
+        let kept = mem.pop_env();
+
+        // Another module which will be executed again, after the others.
+        mem.push_new_root_env();
+        mem.add("e".to_owned(), val(5), sr()).unwrap();
+        mem.pop_env();
+        assert_eq!(mem.environments.len(), 5);
+
+        mem.free_unreachable_envs([kept]);
+        assert_get(mem, "a", 1);
+        assert_get_from(mem, "c", 3, kept);
+        assert_get_from(mem, "d", 4, closure);
+        assert_get_from(mem, "c", 3, closure);
+        assert!(call.index() < mem.environments.len());
+        // The stale environment in the middle is emptied, and the one at the end is removed.
+        mem.get_from("b", stale, sr()).unwrap_err();
+        assert_eq!(mem.environments.len(), 4);
+    }
 }
@@ -8,7 +8,7 @@ use indexmap::IndexMap;
 use kcmc::{
     each_cmd as mcmd,
     ok_response::{output::TakeSnapshot, OkModelingCmdResponse},
-    websocket::{ModelingSessionData, OkWebSocketResponseData},
+    websocket::{ModelingSessionData, OkWebSocketResponseData, RawFile},
     ImageFormat, ModelingCmd,
 };
 use kittycad_modeling_cmds as kcmc;
@@ -31,9 +31,11 @@ use crate::{
 };
 
 pub use artifact::{Artifact, ArtifactCommand, ArtifactGraph, ArtifactId};
+pub(crate) use artifact::{CodeRef, Thread, ThreadSubType};
 pub use cache::{bust_cache, clear_mem_cache};
 pub use cad_op::Operation;
 pub use exec_ast::FunctionParam;
+pub use export::{ExportOptions, FileExportFormat};
 pub use geometry::*;
 pub(crate) use import::{
     import_foreign, send_to_engine as send_import_to_engine, PreImportedGeometry, ZOO_COORD_SYSTEM,
@@ -41,17 +43,21 @@ pub(crate) use import::{
 pub use kcl_value::{KclObjectFields, KclValue, UnitAngle, UnitLen};
 pub use memory::EnvironmentRef;
 pub use state::{ExecState, IdGenerator, MetaSettings};
+pub use view::CameraView;
 
 pub(crate) mod annotations;
 mod artifact;
 pub(crate) mod cache;
 mod cad_op;
+mod disk_cache;
 mod exec_ast;
+mod export;
 mod geometry;
 mod import;
 pub(crate) mod kcl_value;
 mod memory;
 mod state;
+mod view;
 
 /// Outcome of executing a program.  This is used in TS.
 #[derive(Debug, Clone, Deserialize, Serialize, ts_rs::TS)]
@@ -250,6 +256,10 @@ pub struct ExecutorSettings {
     /// This is the path to the current file being executed.
     /// We use this for preventing cyclic imports.
     pub current_file: Option<PathBuf>,
+    /// Where to store the results of executing imported modules, so they
+    /// can be reused by later runs.  If None is given, results are only
+    /// reused within a single run.
+    pub cache_directory: Option<PathBuf>,
 }
 
 impl Default for ExecutorSettings {
@@ -262,6 +272,7 @@ impl Default for ExecutorSettings {
             replay: None,
             project_directory: None,
             current_file: None,
+            cache_directory: None,
         }
     }
 }
@@ -276,6 +287,7 @@ impl From<crate::settings::types::Configuration> for ExecutorSettings {
             replay: None,
             project_directory: None,
             current_file: None,
+            cache_directory: None,
         }
     }
 }
@@ -290,6 +302,7 @@ impl From<crate::settings::types::project::ProjectConfiguration> for ExecutorSet
             replay: None,
             project_directory: None,
             current_file: None,
+            cache_directory: None,
         }
     }
 }
@@ -304,6 +317,7 @@ impl From<crate::settings::types::ModelingSettings> for ExecutorSettings {
             replay: None,
             project_directory: None,
             current_file: None,
+            cache_directory: None,
         }
     }
 }
@@ -324,6 +338,15 @@ impl ExecutorSettings {
             self.project_directory = Some(current_file.clone());
         }
     }
+
+    /// Cache the results of executing imported modules in the project
+    /// directory, so they can be reused by later runs.
+    pub fn with_disk_cache(&mut self) {
+        self.cache_directory = self
+            .project_directory
+            .as_ref()
+            .map(|dir| dir.join(disk_cache::CACHE_DIR_NAME));
+    }
 }
 
 impl ExecutorContext {
@@ -471,6 +494,7 @@ impl ExecutorContext {
                 replay: None,
                 project_directory: None,
                 current_file: None,
+                cache_directory: None,
             },
             None,
             engine_addr,
@@ -558,7 +582,7 @@ impl ExecutorContext {
 
         let (program, mut exec_state) = if let Some(OldAstState {
             ast: old_ast,
-            exec_state: old_state,
+            exec_state: mut old_state,
             settings: old_settings,
         }) = cache::read_old_ast().await
         {
@@ -571,8 +595,9 @@ impl ExecutorContext {
                 settings: &self.settings,
             };
 
-            // Get the program that actually changed from the old and new information.
-            let (clear_scene, program) = match cache::get_changed_program(old, new).await {
+            // Get the program that actually changed from the old and new information. `None` means
+            // the root module doesn't need to be executed again.
+            let changed = match cache::get_changed_program(old, new).await {
                 CacheResult::ReExecute {
                     clear_scene,
                     reapply_settings,
@@ -585,9 +610,9 @@ impl ExecutorContext {
                             .await
                             .is_err()
                     {
-                        (true, program.ast)
+                        Some((true, program.ast.clone()))
                     } else {
-                        (clear_scene, changed_program)
+                        Some((clear_scene, changed_program))
                     }
                 }
                 CacheResult::NoAction(true) => {
@@ -597,30 +622,48 @@ impl ExecutorContext {
                         .await
                         .is_ok()
                     {
-                        return Ok(old_state.to_wasm_outcome());
+                        None
+                    } else {
+                        Some((true, program.ast.clone()))
                     }
-                    (true, program.ast)
                 }
-                CacheResult::NoAction(false) => return Ok(old_state.to_wasm_outcome()),
+                CacheResult::NoAction(false) => None,
             };
 
-            let exec_state = if clear_scene {
-                // Pop the execution state, since we are starting fresh.
-                let mut exec_state = old_state;
-                exec_state.reset(&self.settings);
+            // Imported modules may have changed even if the root module hasn't.
+            let changed_modules = match &changed {
+                Some((true, _)) => None,
+                _ => cache::get_changed_modules(&self.fs, &mut old_state.global.module_infos).await,
+            };
 
-                // We don't do this in mock mode since there is no engine connection
-                // anyways and from the TS side we override memory and don't want to clear it.
-                self.send_clear_scene(&mut exec_state, Default::default())
-                    .await
-                    .map_err(KclErrorWithOutputs::no_outputs)?;
+            match (changed, changed_modules) {
+                (None, Some(modules)) if modules.is_empty() => return Ok(old_state.to_wasm_outcome()),
+                (Some((false, changed_program)), Some(modules)) if modules.is_empty() => (changed_program, old_state),
+                (_, Some(modules)) => {
+                    // Only re-execute the changed modules and the modules which import them,
+                    // which always includes the root module, so its geometry must be recreated.
+                    let mut exec_state = old_state;
+                    exec_state.reset_changed_modules(&self.settings, modules);
+                    self.send_clear_scene(&mut exec_state, Default::default())
+                        .await
+                        .map_err(KclErrorWithOutputs::no_outputs)?;
 
-                exec_state
-            } else {
-                old_state
-            };
+                    (program.ast, exec_state)
+                }
+                (_, None) => {
+                    // Pop the execution state, since we are starting fresh.
+                    let mut exec_state = old_state;
+                    exec_state.reset(&self.settings);
+
+                    // We don't do this in mock mode since there is no engine connection
+                    // anyways and from the TS side we override memory and don't want to clear it.
+                    self.send_clear_scene(&mut exec_state, Default::default())
+                        .await
+                        .map_err(KclErrorWithOutputs::no_outputs)?;
 
-            (program, exec_state)
+                    (program.ast, exec_state)
+                }
+            }
         } else {
             let mut exec_state = ExecState::new(&self.settings);
             self.send_clear_scene(&mut exec_state, Default::default())
@@ -761,19 +804,17 @@ impl ExecutorContext {
 
     /// Get a snapshot of the current scene.
     pub async fn prepare_snapshot(&self) -> std::result::Result<TakeSnapshot, ExecError> {
-        // Zoom to fit.
-        self.engine
-            .send_modeling_cmd(
-                uuid::Uuid::new_v4(),
-                crate::execution::SourceRange::default(),
-                &ModelingCmd::from(mcmd::ZoomToFit {
-                    object_ids: Default::default(),
-                    animated: false,
-                    padding: 0.1,
-                }),
-            )
-            .await
-            .map_err(KclErrorWithOutputs::no_outputs)?;
+        self.prepare_snapshot_from(&CameraView::Fit).await
+    }
+
+    /// Get a snapshot of the current scene, with the camera pointed the given way.
+    pub async fn prepare_snapshot_from(&self, view: &CameraView) -> std::result::Result<TakeSnapshot, ExecError> {
+        for cmd in view.commands() {
+            self.engine
+                .send_modeling_cmd(uuid::Uuid::new_v4(), crate::execution::SourceRange::default(), &cmd)
+                .await
+                .map_err(KclErrorWithOutputs::no_outputs)?;
+        }
 
         // Send a snapshot request to the engine.
         let resp = self
@@ -799,6 +840,55 @@ impl ExecutorContext {
         Ok(contents)
     }
 
+    /// Export the current scene, after running a program with `exec_state`, to a file in the given
+    /// format. Lengths default to the program's units, which its `@settings` may have changed.
+    pub async fn export(
+        &self,
+        exec_state: &ExecState,
+        format: FileExportFormat,
+        options: ExportOptions,
+    ) -> std::result::Result<Vec<RawFile>, ExecError> {
+        let format = options
+            .output_format(format, exec_state.length_unit().into())
+            .map_err(KclErrorWithOutputs::no_outputs)?;
+
+        let resp = self
+            .engine
+            .send_modeling_cmd(
+                uuid::Uuid::new_v4(),
+                crate::execution::SourceRange::default(),
+                &ModelingCmd::from(mcmd::Export {
+                    entity_ids: Default::default(),
+                    format,
+                }),
+            )
+            .await
+            .map_err(KclErrorWithOutputs::no_outputs)?;
+
+        // The engine sends the files in their own message, unless the export was batched with
+        // other commands.
+        match resp {
+            OkWebSocketResponseData::Export { files } => Ok(files),
+            OkWebSocketResponseData::Modeling {
+                modeling_response: OkModelingCmdResponse::Export(export),
+            } => Ok(export
+                .files
+                .into_iter()
+                .map(|file| RawFile {
+                    name: file.name,
+                    contents: file.contents.into(),
+                })
+                .collect()),
+            _ => Err(
+                KclErrorWithOutputs::no_outputs(KclError::Engine(crate::errors::KclErrorDetails {
+                    message: format!("Instead of an Export response, the engine returned {resp:?}"),
+                    source_ranges: vec![],
+                }))
+                .into(),
+            ),
+        }
+    }
+
     pub async fn close(&self) {
         self.engine.close().await;
     }
@@ -806,13 +896,21 @@ impl ExecutorContext {
 
 #[cfg(test)]
 async fn parse_execute(code: &str) -> Result<(crate::Program, ExecutorContext, ExecState)> {
+    parse_execute_with_settings(code, Default::default()).await
+}
+
+#[cfg(test)]
+async fn parse_execute_with_settings(
+    code: &str,
+    settings: ExecutorSettings,
+) -> Result<(crate::Program, ExecutorContext, ExecState)> {
     let program = crate::Program::parse_no_errs(code)?;
 
     let ctx = ExecutorContext {
         engine: Arc::new(Box::new(crate::engine::conn_mock::EngineConnection::new().await?)),
         fs: Arc::new(crate::fs::FileManager::new()),
         stdlib: Arc::new(crate::std::StdLib::new()),
-        settings: Default::default(),
+        settings,
         context_type: ContextType::Mock,
     };
     let mut exec_state = ExecState::new(&ctx.settings);
@@ -1606,6 +1704,485 @@ let w = f() + f()
         parse_execute(ast).await.unwrap();
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_for_loop() {
+        let ast = r#"offset = 1
+xs = for i in [0..3] {
+  doubled = i * 2
+  doubled + offset
+}
+"#;
+        let (_, _, exec_state) = parse_execute(ast).await.unwrap();
+        let xs: Vec<f64> = mem_get_json(exec_state.memory(), "xs")
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|x| x.as_f64().unwrap())
+            .collect();
+        assert_eq!(xs, vec![1.0, 3.0, 5.0, 7.0]);
+        // Names declared in the body don't leak out of the loop.
+        exec_state.memory().get("doubled", SourceRange::default()).unwrap_err();
+        exec_state.memory().get("i", SourceRange::default()).unwrap_err();
+
+        let iterations: Vec<usize> = exec_state
+            .mod_local
+            .operations
+            .iter()
+            .filter_map(|op| match op {
+                Operation::LoopIteration { index, .. } => Some(*index),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(iterations, vec![0, 1, 2, 3]);
+        let ends = exec_state
+            .mod_local
+            .operations
+            .iter()
+            .filter(|op| matches!(op, Operation::LoopIterationEnd))
+            .count();
+        assert_eq!(ends, 4);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_for_loop_not_array() {
+        let ast = r#"xs = for i in 5 {
+  i
+}
+"#;
+        let result = parse_execute(ast).await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Expected an array to loop over, but found number"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_unit_conversion() {
+        let ast = r#"length = 1inch + 25.4mm
+area = 1cm * 5mm
+ratio = 1m / 50cm
+longer = 1inch > 20mm
+c = cos(180deg)
+"#;
+        let (_, _, exec_state) = parse_execute(ast).await.unwrap();
+        // Results are in the module's default units, mm.
+        let length = mem_get_json(exec_state.memory(), "length");
+        assert_eq!(length.as_f64().unwrap(), 50.8);
+        assert_eq!(length.numeric_type(), UnitLen::Mm.into());
+        let area = mem_get_json(exec_state.memory(), "area");
+        assert_eq!(area.as_f64().unwrap(), 50.0);
+        assert_eq!(area.numeric_type().to_string(), "number(mm²)");
+        let ratio = mem_get_json(exec_state.memory(), "ratio");
+        assert_eq!(ratio.as_f64().unwrap(), 2.0);
+        assert_eq!(ratio.numeric_type(), kcl_value::NumericType::count());
+        assert!(mem_get_json(exec_state.memory(), "longer").as_bool().unwrap());
+        assert_eq!(mem_get_json(exec_state.memory(), "c").as_f64().unwrap(), -1.0);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_unit_conversion_operand_order() {
+        let ast = r#"@settings(defaultLengthUnit = in)
+a = 1cm * 1cm
+sum = 1inch + 25.4mm
+sumSwapped = 25.4mm + 1inch
+volume = a * 5mm
+volumeSwapped = 5mm * a
+length = a / 5mm
+assertEqual(sum, sumSwapped, 0.0000001, "sums differ")
+"#;
+        let (_, _, exec_state) = parse_execute(ast).await.unwrap();
+        let get = |name| mem_get_json(exec_state.memory(), name).as_f64().unwrap();
+        assert_eq!(get("sum"), 2.0);
+        assert_eq!(get("sum"), get("sumSwapped"));
+        assert_eq!(get("volume"), get("volumeSwapped"));
+        assert!((get("volume") - 0.5 / 25.4f64.powi(3) * 1000.0).abs() < 1e-9);
+        let length = mem_get_json(exec_state.memory(), "length");
+        assert!((length.as_f64().unwrap() - 2.0 / 2.54).abs() < 1e-9);
+        assert_eq!(length.numeric_type(), UnitLen::Inches.into());
+    }
+
+    #[test]
+    fn test_numeric_type_json_round_trip() {
+        use kcl_value::{NumericType, UnitType};
+
+        let ty = NumericType::Known(UnitType::Area(UnitLen::Mm));
+        let json = serde_json::to_string(&ty).unwrap();
+        // Each tag has its own key, so none of them are lost when the JSON is parsed.
+        assert_eq!(json, r#"{"type":"Known","quantity":"Area","units":{"type":"Mm"}}"#);
+        assert_eq!(serde_json::from_str::<NumericType>(&json).unwrap(), ty);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_trig_of_length() {
+        // The type check warns about this, but it still runs, with the length taken as radians.
+        let ast = r#"width = 2mm
+c = cos(width)
+"#;
+        let program = crate::Program::parse_no_errs(ast).unwrap();
+        let warnings = program.typecheck();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].severity, crate::errors::Severity::Warning);
+        let (_, _, exec_state) = parse_execute(ast).await.unwrap();
+        assert_eq!(mem_get_json(exec_state.memory(), "c").as_f64().unwrap(), 2f64.cos());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_transform_sketch_on_face() {
+        let ast = r#"cube = startSketchOn('XY')
+  |> startProfileAt([0, 0], %)
+  |> line(end = [10, 0])
+  |> line(end = [0, 10])
+  |> line(end = [-10, 0])
+  |> close()
+  |> extrude(length = 10)
+boss = startSketchOn(cube, 'END')
+  |> circle({ center = [5, 5], radius = 2 }, %)
+moved = translate(boss, translate = [0, 0, 5])
+"#;
+        let result = parse_execute(ast).await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Geometry sketched on a face (`end`) can't be transformed"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_transforms_update_sketch() {
+        let ast = r#"base = startSketchOn('XY')
+  |> startProfileAt([0, 0], %)
+  |> line(end = [10, 0], tag = $edge)
+  |> line(end = [0, 10])
+  |> close()
+moved = translate(base, translate = [0, 0, 5])
+movedEnd = segEnd(edge)
+bigger = scale(base, scale = [2, 2, 1])
+biggerEnd = segEnd(edge)
+turned = rotate(base, axis = [1, 0, 0], angle = 90)
+"#;
+        let (_, _, exec_state) = parse_execute(ast).await.unwrap();
+        let plane = |name: &str| match &mem_get_json(exec_state.memory(), name).as_sketch().unwrap().on {
+            SketchSurface::Plane(plane) => plane.clone(),
+            SketchSurface::Face(_) => panic!("expected a sketch on a plane"),
+        };
+
+        assert_eq!(plane("moved").origin, Point3d::new(0.0, 0.0, 5.0));
+        assert_eq!(plane("moved").value, PlaneType::Custom);
+        let end = mem_get_json(exec_state.memory(), "movedEnd");
+        assert_eq!(end.as_point2d().unwrap(), [10.0, 0.0]);
+        let end = mem_get_json(exec_state.memory(), "biggerEnd");
+        assert_eq!(end.as_point2d().unwrap(), [20.0, 0.0]);
+        let turned = plane("turned");
+        assert!((turned.y_axis.z - 1.0).abs() < 1e-9);
+        assert!((turned.z_axis.y + 1.0).abs() < 1e-9);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_scale_skews_sketch() {
+        let ast = r#"sketch001 = startSketchOn('XY')
+  |> startProfileAt([0, 0], %)
+  |> line(end = [10, 0])
+  |> line(end = [0, 10])
+  |> close()
+  |> scale(scale = [2, 1, 1])
+"#;
+        let result = parse_execute(ast).await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("A sketch must be scaled by the same amount along both axes of its sketch plane"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_measure_mass_properties() {
+        // The mock engine answers 1 cubic centimeter, 1 square millimeter, 1 kilogram and the origin.
+        let ast = r#"cube = startSketchOn('XY')
+  |> startProfileAt([0, 0], %)
+  |> line(end = [10, 0])
+  |> line(end = [0, 10])
+  |> line(end = [-10, 0])
+  |> close()
+  |> extrude(length = 10)
+v = volume(cube)
+a = surfaceArea(cube)
+m = mass(cube, density = 2700)
+c = centerOfMass(cube)
+assertLessThan(v, 1500, "volume is in cubic millimeters")
+"#;
+        let (_, _, exec_state) = parse_execute(ast).await.unwrap();
+        let v = mem_get_json(exec_state.memory(), "v");
+        assert_eq!(v.as_f64().unwrap(), 1000.0);
+        let a = mem_get_json(exec_state.memory(), "a");
+        assert_eq!(a.as_f64().unwrap(), 1.0);
+        assert_eq!(
+            a.numeric_type(),
+            kcl_value::NumericType::Known(kcl_value::UnitType::Area(UnitLen::Mm))
+        );
+        let m = mem_get_json(exec_state.memory(), "m");
+        assert_eq!(m.as_f64().unwrap(), 1.0);
+        let c = mem_get_json(exec_state.memory(), "c");
+        let c: Vec<f64> = c.as_array().unwrap().iter().map(|n| n.as_f64().unwrap()).collect();
+        assert_eq!(c, vec![0.0, 0.0, 0.0]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_measure_in_imported_module() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cube = r#"cube = startSketchOn('XY')
+  |> startProfileAt([0, 0], %)
+  |> line(end = [10, 0])
+  |> line(end = [0, 10])
+  |> line(end = [-10, 0])
+  |> close()
+  |> extrude(length = 10)
+export v = volume(cube)
+assertLessThan(v, 1500, "volume is in cubic millimeters")
+"#;
+        std::fs::write(tmp.path().join("cube.kcl"), cube).unwrap();
+
+        let mut settings = ExecutorSettings::default();
+        settings.with_current_file(tmp.path().join("main.kcl"));
+        let (_, _, exec_state) = parse_execute_with_settings("import v from \"cube.kcl\"\n", settings)
+            .await
+            .unwrap();
+        let v = mem_get_json(exec_state.memory(), "v");
+        assert_eq!(v.as_f64().unwrap(), 1000.0);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_measure_bounding_box() {
+        let ast = r#"disc = startSketchOn('XY')
+  |> circle({ center = [10, 0], radius = 5 }, %)
+  |> extrude(length = 3)
+  |> translate(translate = [0, 0, 2])
+bounds = boundingBox(disc)
+"#;
+        let (_, _, exec_state) = parse_execute(ast).await.unwrap();
+        let bounds = mem_get_json(exec_state.memory(), "bounds");
+        let corner = |name: &str| -> Vec<f64> {
+            let corner = bounds.as_object().unwrap()[name].as_array().unwrap();
+            corner.iter().map(|n| n.as_f64().unwrap()).collect()
+        };
+        assert_eq!(corner("min"), vec![5.0, -5.0, 2.0]);
+        assert_eq!(corner("max"), vec![15.0, 5.0, 5.0]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_extrude_symmetric() {
+        let ast = r#"disc = startSketchOn('XZ')
+  |> circle({ center = [0, 0], radius = 5 }, %)
+  |> extrude(length = 3, symmetric = true)
+bounds = boundingBox(disc)
+"#;
+        let (_, _, exec_state) = parse_execute(ast).await.unwrap();
+        let bounds = mem_get_json(exec_state.memory(), "bounds");
+        let corner = |name: &str| -> Vec<f64> {
+            let corner = bounds.as_object().unwrap()[name].as_array().unwrap();
+            corner.iter().map(|n| n.as_f64().unwrap()).collect()
+        };
+        assert_eq!(corner("min"), vec![-5.0, -1.5, -5.0]);
+        assert_eq!(corner("max"), vec![5.0, 1.5, 5.0]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_mirror_3d() {
+        let ast = r#"block = startSketchOn('XY')
+  |> startProfileAt([5, -5], %)
+  |> line(end = [10, 0])
+  |> line(end = [0, 10], tag = $seg01)
+  |> line(end = [-10, 0])
+  |> close()
+  |> extrude(length = 3)
+mirrored = mirror3d(block, plane = offsetPlane('XY', offset = 10))
+bounds = boundingBox(mirrored)
+"#;
+        let (_, _, exec_state) = parse_execute(ast).await.unwrap();
+        let bounds = mem_get_json(exec_state.memory(), "bounds");
+        let corner = |name: &str| -> Vec<f64> {
+            let corner = bounds.as_object().unwrap()[name].as_array().unwrap();
+            corner.iter().map(|n| n.as_f64().unwrap()).collect()
+        };
+        assert_eq!(corner("min"), vec![5.0, -5.0, 17.0]);
+        assert_eq!(corner("max"), vec![15.0, 5.0, 20.0]);
+
+        let KclValue::Solid { value: block } = mem_get_json(exec_state.memory(), "block") else {
+            panic!("expected a solid");
+        };
+        let KclValue::Solid { value: mirrored } = mem_get_json(exec_state.memory(), "mirrored") else {
+            panic!("expected a solid");
+        };
+        assert_ne!(mirrored.id, block.id);
+        // Nothing refers to the original's faces and edges.
+        assert!(mirrored.sketch.tags.is_empty());
+        assert!(mirrored.value.is_empty());
+        assert_eq!(mirrored.start_cap_id, None);
+        assert_eq!(mirrored.end_cap_id, None);
+        assert_ne!(mirrored.sketch.id, block.sketch.id);
+        assert!(block.sketch.tags.contains_key("seg01"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_mirror_3d_fillet_tagged_edge() {
+        let ast = r#"block = startSketchOn('XY')
+  |> startProfileAt([5, -5], %)
+  |> line(end = [10, 0])
+  |> line(end = [0, 10], tag = $seg01)
+  |> line(end = [-10, 0])
+  |> close()
+  |> extrude(length = 3)
+mirrored = mirror3d(block, plane = 'YZ')
+filleted = fillet({ radius = 1, tags = [getNextAdjacentEdge(mirrored.tags.seg01)] }, mirrored)
+"#;
+        let err = parse_execute(ast).await.unwrap_err().to_string();
+        assert!(err.contains("seg01"), "{err}");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_spline_through() {
+        let ast = r#"pebble = startSketchOn('XY')
+  |> startProfileAt([10, 0], %)
+  |> splineThrough(points = [[0, 10], [-10, 0], [0, -10]], periodic = true, tag = $last)
+  |> extrude(length = 2)
+bounds = boundingBox(pebble)
+"#;
+        let (_, _, exec_state) = parse_execute(ast).await.unwrap();
+        let bounds = mem_get_json(exec_state.memory(), "bounds");
+        let corner = |name: &str| -> Vec<f64> {
+            let corner = bounds.as_object().unwrap()[name].as_array().unwrap();
+            corner
+                .iter()
+                .map(|n| (n.as_f64().unwrap() * 1e6).round() / 1e6)
+                .collect()
+        };
+        assert_eq!(corner("min"), vec![-10.0, -10.0, 0.0]);
+        assert_eq!(corner("max"), vec![10.0, 10.0, 2.0]);
+
+        let KclValue::Solid { value: pebble } = mem_get_json(exec_state.memory(), "pebble") else {
+            panic!("expected a solid");
+        };
+        assert_eq!(pebble.sketch.paths.len(), 4);
+        assert!(pebble
+            .sketch
+            .paths
+            .iter()
+            .all(|path| matches!(path, Path::Bezier { .. })));
+        assert_eq!(pebble.sketch.paths[3].get_base().to, [10.0, 0.0]);
+        assert_eq!(
+            pebble.sketch.tags["last"].info.as_ref().unwrap().id,
+            pebble.sketch.paths[3].get_id()
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_thread() {
+        let ast = r#"bolt = startSketchOn('XY')
+  |> circle({ center = [0, 0], radius = 0.125 }, %, $shank)
+  |> extrude(length = 1)
+  |> thread(face = shank, standard = "1/4-20 UNC", length = 0.75, cosmetic = true)
+"#;
+        let (_, _, exec_state) = parse_execute(&format!("@settings(defaultLengthUnit = in)\n{ast}"))
+            .await
+            .unwrap();
+        let threads: Vec<_> = exec_state
+            .global
+            .artifacts
+            .values()
+            .filter_map(|artifact| match artifact {
+                Artifact::Thread(thread) => Some(thread),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(threads.len(), 1);
+        assert_eq!(threads[0].sub_type, ThreadSubType::Cosmetic);
+        assert_eq!(threads[0].standard, "1/4-20 UNC");
+        assert!(!threads[0].internal);
+        assert!((threads[0].major_diameter - 0.25).abs() < 1e-9);
+        assert!((threads[0].pitch - 0.05).abs() < 1e-9);
+        assert_eq!(threads[0].units, UnitLen::Inches);
+
+        let err = parse_execute(&ast.replace("1/4-20 UNC", "M8"))
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("A M8 thread doesn't fit a diameter of 0.25"), "{err}");
+
+        let err = parse_execute(&ast.replace("cosmetic = true", "cosmetic = false"))
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Modeled threads aren't available yet"), "{err}");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_export() {
+        let ast = r#"cube = startSketchOn('XY')
+  |> startProfileAt([0, 0], %)
+  |> line(end = [10, 0])
+  |> line(end = [0, 10])
+  |> close()
+  |> extrude(length = 10)
+"#;
+        let (_, ctx, exec_state) = parse_execute(ast).await.unwrap();
+        // The mock engine doesn't write any files.
+        let files = ctx
+            .export(&exec_state, FileExportFormat::Stl, Default::default())
+            .await
+            .unwrap();
+        assert!(files.is_empty());
+
+        let options = ExportOptions {
+            coords: Some("directx".to_owned()),
+            ..Default::default()
+        };
+        let err = ctx
+            .export(&exec_state, FileExportFormat::Obj, options)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Unknown coordinate system: directx"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_export_uses_program_units() {
+        let ast = r#"@settings(defaultLengthUnit = in)
+cube = startSketchOn('XY')
+  |> startProfileAt([0, 0], %)
+  |> line(end = [1, 0])
+  |> line(end = [0, 1])
+  |> close()
+  |> extrude(length = 1)
+"#;
+        let (_, ctx, exec_state) = parse_execute(ast).await.unwrap();
+        ctx.export(&exec_state, FileExportFormat::Stl, Default::default())
+            .await
+            .unwrap();
+        let commands = ctx.engine.take_artifact_commands();
+        let format = commands
+            .iter()
+            .find_map(|command| match &command.command {
+                ModelingCmd::Export(export) => Some(&export.format),
+                _ => None,
+            })
+            .unwrap();
+        let kcmc::format::OutputFormat::Stl(options) = format else {
+            panic!("expected an STL export, found {format:?}");
+        };
+        assert_eq!(options.units, kcmc::units::UnitLength::Inches);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_mock_artifact_graph() {
+        let ast = r#"cube = startSketchOn('XY')
+  |> startProfileAt([0, 0], %)
+  |> line(end = [10, 0])
+  |> line(end = [0, 10])
+  |> close()
+  |> extrude(length = 10)
+"#;
+        let (_, _, exec_state) = parse_execute(ast).await.unwrap();
+        // The mock engine's responses are enough to build the plane, the path with its three
+        // segments, and the sweep. Faces need the engine's extrusion face info.
+        assert_eq!(exec_state.global.artifact_graph.len(), 6);
+    }
+
     #[test]
     fn test_serialize_memory_item() {
         let mem = KclValue::Solids {
@@ -11,8 +11,8 @@ use crate::{
         annotations, kcl_value, memory::ProgramMemory, Artifact, ArtifactCommand, ArtifactGraph, ArtifactId,
         ExecOutcome, ExecutorSettings, KclValue, Operation, UnitAngle, UnitLen,
     },
-    modules::{ModuleId, ModuleInfo, ModuleLoader, ModulePath, ModuleRepr},
-    parsing::ast::types::Annotation,
+    modules::{ModuleGraph, ModuleId, ModuleInfo, ModuleLoader, ModulePath, ModuleRepr},
+    parsing::ast::types::{Annotation, Node, Program},
     source_range::SourceRange,
 };
 
@@ -87,6 +87,51 @@ impl ExecState {
         };
     }
 
+    /// Reset the state for re-executing the root module after some of the modules it imports
+    /// have changed. `changed` are the changed modules with their new ASTs.
+    ///
+    /// The results of imported modules which neither changed nor import a changed module are
+    /// kept, so they don't need to be executed again.
+    pub(super) fn reset_changed_modules(
+        &mut self,
+        exec_settings: &ExecutorSettings,
+        changed: Vec<(ModuleId, Node<Program>)>,
+    ) {
+        let graph = ModuleGraph::new(
+            &self.global.module_infos,
+            &self.global.path_to_source_id,
+            &exec_settings.project_directory,
+        );
+        let dirty = graph.dependents(changed.iter().map(|(id, _)| *id));
+
+        let mut memory = std::mem::replace(&mut self.global.memory, ProgramMemory::new());
+        let path_to_source_id = std::mem::take(&mut self.global.path_to_source_id);
+        let mut module_infos = std::mem::take(&mut self.global.module_infos);
+        self.reset(exec_settings);
+
+        // The root module is always re-executed, but the environments of other modules (and the
+        // functions declared in them) are kept.
+        memory.reset_root_env();
+        for (id, new_ast) in changed {
+            module_infos[&id].repr = ModuleRepr::Kcl(new_ast, None);
+        }
+        for id in dirty {
+            if let ModuleRepr::Kcl(_, cache) = &mut module_infos[&id].repr {
+                *cache = None;
+            }
+        }
+        // Free the environments of the modules which will be executed again, and whatever the root
+        // module made, keeping those of the modules whose results are reused.
+        memory.free_unreachable_envs(module_infos.values().filter_map(|info| match &info.repr {
+            ModuleRepr::Kcl(_, Some((env_ref, _))) => Some(*env_ref),
+            _ => None,
+        }));
+
+        self.global.memory = memory;
+        self.global.path_to_source_id = path_to_source_id;
+        self.global.module_infos = module_infos;
+    }
+
     /// Convert to execution outcome when running in WebAssembly.  We want to
     /// reduce the amount of data that crosses the WASM boundary as much as
     /// possible.
@@ -147,12 +192,18 @@ impl ExecState {
         self.global.path_to_source_id.get(path).cloned()
     }
 
-    pub(super) fn add_module(&mut self, id: ModuleId, path: ModulePath, repr: ModuleRepr) {
+    /// Add a module, which was parsed from the given source if it's KCL.
+    pub(super) fn add_module(&mut self, id: ModuleId, path: ModulePath, repr: ModuleRepr, source: Option<&str>) {
         debug_assert!(!self.global.path_to_source_id.contains_key(&path));
 
         self.global.path_to_source_id.insert(path.clone(), id);
 
-        let module_info = ModuleInfo { id, repr, path };
+        let module_info = ModuleInfo {
+            id,
+            repr,
+            path,
+            source_digest: source.map(crate::modules::source_digest),
+        };
         self.global.module_infos.insert(id, module_info);
     }
 
@@ -204,6 +255,7 @@ impl GlobalState {
                 id: root_id,
                 path: ModulePath::Local(root_path.clone()),
                 repr: ModuleRepr::Root,
+                source_digest: None,
             },
         );
         global.path_to_source_id.insert(ModulePath::Local(root_path), root_id);
@@ -293,4 +345,21 @@ impl IdGenerator {
             id
         }
     }
+
+    /// The IDs generated since the generator was at `start`.
+    pub(super) fn ids_since(&self, start: usize) -> Vec<uuid::Uuid> {
+        self.ids[start.min(self.next_id)..self.next_id].to_vec()
+    }
+
+    /// Advance the generator past `ids`, as if they had just been generated. Used when a result
+    /// computed by an earlier execution is reused instead of being computed again.
+    pub(super) fn replay(&mut self, ids: &[uuid::Uuid]) {
+        for id in ids {
+            match self.ids.get_mut(self.next_id) {
+                Some(existing) => *existing = *id,
+                None => self.ids.push(*id),
+            }
+            self.next_id += 1;
+        }
+    }
 }
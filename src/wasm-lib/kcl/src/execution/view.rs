@@ -0,0 +1,104 @@
+//! Camera views to take snapshots of the scene from.
+
+use kcmc::{each_cmd as mcmd, shared::Point3d, ModelingCmd};
+use kittycad_modeling_cmds as kcmc;
+use serde::{Deserialize, Serialize};
+
+/// How much to pad the frame around the scene, as a fraction of its bounding box.
+const PADDING: f32 = 0.1;
+
+/// Where the camera looks at the scene from when taking a snapshot.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum CameraView {
+    /// Keep the camera's direction, and zoom to fit the whole scene.
+    #[default]
+    Fit,
+    /// Look at the whole scene from above its front right corner.
+    Isometric,
+    /// Look at the whole scene along the +Y axis.
+    Front,
+    /// Look at the whole scene along the -Y axis.
+    Back,
+    /// Look at the whole scene along the -Z axis.
+    Top,
+    /// Look at the whole scene along the +Z axis.
+    Bottom,
+    /// Look at the whole scene along the +X axis.
+    Left,
+    /// Look at the whole scene along the -X axis.
+    Right,
+    /// Put the camera at an exact position. The scene isn't zoomed to fit.
+    LookAt {
+        /// Where the camera is.
+        vantage: [f32; 3],
+        /// The point in the center of the snapshot.
+        center: [f32; 3],
+        /// Which way is up in the snapshot.
+        up: [f32; 3],
+    },
+}
+
+impl CameraView {
+    /// The commands which point the engine's camera this way.
+    pub(super) fn commands(&self) -> Vec<ModelingCmd> {
+        let (vantage, up) = match self {
+            CameraView::Fit => return vec![zoom_to_fit()],
+            CameraView::Isometric => return vec![ModelingCmd::from(mcmd::ViewIsometric { padding: PADDING })],
+            CameraView::LookAt { vantage, center, up } => return vec![look_at(*vantage, *center, *up)],
+            CameraView::Front => ([0.0, -1.0, 0.0], [0.0, 0.0, 1.0]),
+            CameraView::Back => ([0.0, 1.0, 0.0], [0.0, 0.0, 1.0]),
+            CameraView::Top => ([0.0, 0.0, 1.0], [0.0, 1.0, 0.0]),
+            CameraView::Bottom => ([0.0, 0.0, -1.0], [0.0, -1.0, 0.0]),
+            CameraView::Left => ([-1.0, 0.0, 0.0], [0.0, 0.0, 1.0]),
+            CameraView::Right => ([1.0, 0.0, 0.0], [0.0, 0.0, 1.0]),
+        };
+        // Turn the camera to look along the axis, then move it back far enough to see everything.
+        vec![look_at(vantage, [0.0; 3], up), zoom_to_fit()]
+    }
+}
+
+fn look_at(vantage: [f32; 3], center: [f32; 3], up: [f32; 3]) -> ModelingCmd {
+    let point = |[x, y, z]: [f32; 3]| Point3d { x, y, z };
+    ModelingCmd::from(mcmd::DefaultCameraLookAt {
+        vantage: point(vantage),
+        center: point(center),
+        up: point(up),
+        sequence: None,
+    })
+}
+
+fn zoom_to_fit() -> ModelingCmd {
+    ModelingCmd::from(mcmd::ZoomToFit {
+        object_ids: Default::default(),
+        animated: false,
+        padding: PADDING,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_views() {
+        let views: Vec<CameraView> = serde_json::from_str(
+            r#"[{"type": "fit"}, {"type": "top"}, {"type": "lookAt", "vantage": [10, 0, 0], "center": [0, 0, 0], "up": [0, 0, 1]}]"#,
+        )
+        .unwrap();
+        assert_eq!(
+            views,
+            vec![
+                CameraView::Fit,
+                CameraView::Top,
+                CameraView::LookAt {
+                    vantage: [10.0, 0.0, 0.0],
+                    center: [0.0; 3],
+                    up: [0.0, 0.0, 1.0],
+                },
+            ]
+        );
+        assert_eq!(views[1].commands().len(), 2);
+        assert_eq!(views[2].commands().len(), 1);
+    }
+}
@@ -51,6 +51,24 @@ impl FileSystem for FileManager {
         })
     }
 
+    async fn write<P: AsRef<std::path::Path> + std::marker::Send + std::marker::Sync>(
+        &self,
+        path: P,
+        contents: Vec<u8>,
+        source_range: SourceRange,
+    ) -> Result<(), KclError> {
+        let to_err = |e: std::io::Error| {
+            KclError::Engine(KclErrorDetails {
+                message: format!("Failed to write file `{}`: {}", path.as_ref().display(), e),
+                source_ranges: vec![source_range],
+            })
+        };
+        if let Some(parent) = path.as_ref().parent() {
+            tokio::fs::create_dir_all(parent).await.map_err(to_err)?;
+        }
+        tokio::fs::write(&path, contents).await.map_err(to_err)
+    }
+
     async fn exists<P: AsRef<std::path::Path> + std::marker::Send + std::marker::Sync>(
         &self,
         path: P,
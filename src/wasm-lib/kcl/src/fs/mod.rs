@@ -33,6 +33,14 @@ pub trait FileSystem: Clone {
         source_range: SourceRange,
     ) -> Result<String, crate::errors::KclError>;
 
+    /// Write a file to the local file system, creating its parent directories if needed.
+    async fn write<P: AsRef<std::path::Path> + std::marker::Send + std::marker::Sync>(
+        &self,
+        path: P,
+        contents: Vec<u8>,
+        source_range: SourceRange,
+    ) -> Result<(), crate::errors::KclError>;
+
     /// Check if a file exists on the local file system.
     async fn exists<P: AsRef<std::path::Path> + std::marker::Send + std::marker::Sync>(
         &self,
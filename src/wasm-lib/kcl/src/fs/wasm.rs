@@ -18,6 +18,9 @@ extern "C" {
     #[wasm_bindgen(method, js_name = readFile, catch)]
     fn read_file(this: &FileSystemManager, path: String) -> Result<js_sys::Promise, js_sys::Error>;
 
+    #[wasm_bindgen(method, js_name = writeFile, catch)]
+    fn write_file(this: &FileSystemManager, path: String, data: Vec<u8>) -> Result<js_sys::Promise, js_sys::Error>;
+
     #[wasm_bindgen(method, js_name = exists, catch)]
     fn exists(this: &FileSystemManager, path: String) -> Result<js_sys::Promise, js_sys::Error>;
 
@@ -95,6 +98,43 @@ impl FileSystem for FileManager {
         Ok(string)
     }
 
+    async fn write<P: AsRef<std::path::Path> + std::marker::Send + std::marker::Sync>(
+        &self,
+        path: P,
+        contents: Vec<u8>,
+        source_range: SourceRange,
+    ) -> Result<(), KclError> {
+        let promise = self
+            .manager
+            .write_file(
+                path.as_ref()
+                    .to_str()
+                    .ok_or_else(|| {
+                        KclError::Engine(KclErrorDetails {
+                            message: "Failed to convert path to string".to_string(),
+                            source_ranges: vec![source_range],
+                        })
+                    })?
+                    .to_string(),
+                contents,
+            )
+            .map_err(|e| {
+                KclError::Engine(KclErrorDetails {
+                    message: e.to_string().into(),
+                    source_ranges: vec![source_range],
+                })
+            })?;
+
+        JsFuture::from(promise).await.map_err(|e| {
+            KclError::Engine(KclErrorDetails {
+                message: format!("Failed to wait for promise from javascript: {:?}", e),
+                source_ranges: vec![source_range],
+            })
+        })?;
+
+        Ok(())
+    }
+
     async fn exists<P: AsRef<std::path::Path> + std::marker::Send + std::marker::Sync>(
         &self,
         path: P,
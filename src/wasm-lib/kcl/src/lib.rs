@@ -82,9 +82,10 @@ mod wasm;
 
 pub use coredump::CoreDump;
 pub use engine::{EngineManager, ExecutionKind};
-pub use errors::{CompilationError, ConnectionError, ExecError, KclError, KclErrorWithOutputs};
+pub use errors::{CompilationError, ConnectionError, ExecError, KclError, KclErrorWithOutputs, Report};
 pub use execution::{
-    bust_cache, clear_mem_cache, ExecOutcome, ExecState, ExecutorContext, ExecutorSettings, MetaSettings, Point2d,
+    bust_cache, clear_mem_cache, CameraView, ExecOutcome, ExecState, ExecutorContext, ExecutorSettings, ExportOptions,
+    FileExportFormat, MetaSettings, Point2d,
 };
 pub use lsp::{
     copilot::Backend as CopilotLspBackend,
@@ -92,7 +93,10 @@ pub use lsp::{
 };
 pub use modules::ModuleId;
 pub use parsing::ast::{modify::modify_ast_for_sketch, types::FormatOptions};
-pub use settings::types::{project::ProjectConfiguration, Configuration, UnitLength};
+pub use settings::types::{
+    project::{LintSettings, ProjectConfiguration, PROJECT_SETTINGS_FILE_NAME},
+    Configuration, UnitLength,
+};
 pub use source_range::SourceRange;
 
 // Rather than make executor public and make lots of it pub(crate), just re-export into a new module.
@@ -156,6 +160,11 @@ impl Program {
         Ok(Program { ast })
     }
 
+    /// Check the types in the program, returning a warning for each mismatch.
+    pub fn typecheck(&self) -> Vec<CompilationError> {
+        parsing::typecheck::check_program(&self.ast)
+    }
+
     pub fn compute_digest(&mut self) -> parsing::ast::digest::Digest {
         self.ast.compute_digest()
     }
@@ -176,6 +185,10 @@ impl Program {
         self.ast.lint_all()
     }
 
+    pub fn lint_with_settings(&self, settings: &LintSettings) -> Result<Vec<lint::Discovered>, anyhow::Error> {
+        self.ast.lint_with_settings(settings)
+    }
+
     pub fn lint<'a>(&'a self, rule: impl lint::Rule<'a>) -> Result<Vec<lint::Discovered>, anyhow::Error> {
         self.ast.lint(rule)
     }
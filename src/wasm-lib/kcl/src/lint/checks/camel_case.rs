@@ -45,10 +45,16 @@ fn lint_lower_camel_case_property(decl: &ObjectProperty) -> Result<Vec<Discovere
     let name = &ident.name;
 
     if !name.is_case(convert_case::Case::Camel) {
-        findings.push(Z0001.at(
-            format!("found '{}'", name),
-            SourceRange::new(ident.start, ident.end, ident.module_id),
-        ));
+        // Unlike variables, renaming a property doesn't need any other code to change.
+        let new_name = name.to_case(convert_case::Case::Camel);
+        findings.push(
+            Z0001
+                .at(
+                    format!("found '{}'", name),
+                    SourceRange::new(ident.start, ident.end, ident.module_id),
+                )
+                .with_suggestion(format!("rename to '{new_name}'"), new_name),
+        );
         return Ok(findings);
     }
 
@@ -168,4 +174,12 @@ const part001 = startSketchOn('XY')
 let circ = {angle_start: 0, angle_end: 360, radius: radius}
 "
     );
+
+    #[test]
+    fn z0001_object_suggestion() {
+        let prog = crate::parsing::top_level_parse("circ = { angle_start = 0 }").unwrap();
+        let findings = prog.lint(lint_object_properties).unwrap();
+        let suggestion = findings[0].suggestion.as_ref().unwrap();
+        assert_eq!(suggestion.insert, "angleStart");
+    }
 }
@@ -1,7 +1,154 @@
 mod camel_case;
 mod offset_plane;
+mod shadowed;
 mod std_lib_args;
+mod unused;
 
 pub use camel_case::{lint_object_properties, lint_variables, Z0001};
 pub use offset_plane::{lint_should_be_offset_plane, Z0003};
+pub use shadowed::{lint_shadowed_std_fns, Z0008};
 pub use std_lib_args::{lint_call_expressions, Z0002};
+pub use unused::{
+    lint_unused_imports, lint_unused_params, lint_unused_tags, lint_unused_variables, Z0004, Z0005, Z0006, Z0007,
+};
+
+use crate::lint::{LintLevel, RegisteredRule};
+
+/// Every lint rule which is checked by `lint_all`. Newer rules are off until a project turns them
+/// on in its settings, so they don't add findings to existing files.
+pub const ALL_RULES: &[RegisteredRule] = &[
+    RegisteredRule {
+        finding: Z0001,
+        default_level: LintLevel::Info,
+        check: lint_variables,
+    },
+    RegisteredRule {
+        finding: Z0001,
+        default_level: LintLevel::Info,
+        check: lint_object_properties,
+    },
+    RegisteredRule {
+        finding: Z0002,
+        default_level: LintLevel::Info,
+        check: lint_call_expressions,
+    },
+    RegisteredRule {
+        finding: Z0003,
+        default_level: LintLevel::Info,
+        check: lint_should_be_offset_plane,
+    },
+    RegisteredRule {
+        finding: Z0004,
+        default_level: LintLevel::Off,
+        check: lint_unused_variables,
+    },
+    RegisteredRule {
+        finding: Z0005,
+        default_level: LintLevel::Off,
+        check: lint_unused_params,
+    },
+    RegisteredRule {
+        finding: Z0006,
+        default_level: LintLevel::Off,
+        check: lint_unused_tags,
+    },
+    RegisteredRule {
+        finding: Z0007,
+        default_level: LintLevel::Off,
+        check: lint_unused_imports,
+    },
+    RegisteredRule {
+        finding: Z0008,
+        default_level: LintLevel::Off,
+        check: lint_shadowed_std_fns,
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use crate::{lint::LintLevel, settings::types::project::LintSettings};
+
+    #[test]
+    fn lint_levels_from_settings() {
+        let prog = crate::parsing::top_level_parse("foo_bar = pow(1, 2, 3)").unwrap();
+
+        let findings = prog.lint_all().unwrap();
+        let codes: Vec<_> = findings.iter().map(|f| (f.finding.code, f.level)).collect();
+        assert_eq!(codes, [("Z0001", LintLevel::Info), ("Z0002", LintLevel::Info)]);
+
+        let settings = LintSettings {
+            rules: [
+                ("Z0001".to_owned(), LintLevel::Off),
+                ("Z0002".to_owned(), LintLevel::Error),
+            ]
+            .into_iter()
+            .collect(),
+        };
+        let findings = prog.lint_with_settings(&settings).unwrap();
+        let codes: Vec<_> = findings.iter().map(|f| (f.finding.code, f.level)).collect();
+        assert_eq!(codes, [("Z0002", LintLevel::Error)]);
+    }
+
+    #[test]
+    fn new_lints_are_off_by_default() {
+        let prog = crate::parsing::top_level_parse(
+            r#"import foo from 'other.kcl'
+x = 1
+fn f(y) {
+  return 1
+}
+fn sqrt(z) {
+  return z
+}
+export s = startSketchOn('XY')
+  |> startProfileAt([0, 0], %)
+  |> line([10, 0], %, $seg01)
+"#,
+        )
+        .unwrap();
+        assert!(prog.lint_all().unwrap().is_empty());
+
+        let codes = ["Z0004", "Z0005", "Z0007", "Z0008"];
+        let settings = LintSettings {
+            rules: codes
+                .iter()
+                .map(|code| (code.to_string(), LintLevel::Warning))
+                .collect(),
+        };
+        let findings = prog.lint_with_settings(&settings).unwrap();
+        let mut found: Vec<_> = findings.iter().map(|f| f.finding.code).collect();
+        found.sort();
+        found.dedup();
+        assert_eq!(found, codes);
+    }
+
+    #[test]
+    fn lint_allow_annotation() {
+        let prog = crate::parsing::top_level_parse(
+            r#"@allow(Z0001)
+foo_bar = 1
+other_thing = 2
+fn f() {
+  @allow(Z0001)
+  fn_thing = 3
+  return fn_thing
+}
+"#,
+        )
+        .unwrap();
+
+        let findings = prog.lint_all().unwrap();
+        let overridden: Vec<_> = findings
+            .iter()
+            .map(|f| (f.description.as_str(), f.overridden))
+            .collect();
+        assert_eq!(
+            overridden,
+            [
+                ("found 'foo_bar'", true),
+                ("found 'other_thing'", false),
+                ("found 'fn_thing'", true)
+            ]
+        );
+    }
+}
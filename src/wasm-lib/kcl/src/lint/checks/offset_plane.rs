@@ -4,7 +4,11 @@ use anyhow::Result;
 
 use crate::{
     lint::rule::{def_finding, Discovered, Finding},
-    parsing::ast::types::{BinaryPart, Expr, LiteralValue, ObjectExpression, UnaryOperator},
+    parsing::{
+        ast::types::{BinaryPart, Expr, LiteralValue, ObjectExpression, UnaryOperator},
+        token::NumericSuffix,
+    },
+    unparser::format_number,
     walk::Node,
     SourceRange,
 };
@@ -146,14 +150,34 @@ pub fn lint_should_be_offset_plane(node: Node) -> Result<Vec<Discovered>> {
         return Ok(vec![]);
     };
 
+    // How far the origin is from the standard plane, in the direction `offsetPlane` moves it.
+    let offset = match plane_name.as_str() {
+        "XY" => origin.2,
+        "-XY" => -origin.2,
+        "XZ" => -origin.1,
+        "-XZ" => origin.1,
+        "YZ" => origin.0,
+        _ => -origin.0,
+    };
+    // Avoid suggesting `-0`.
+    let offset = if offset == 0.0 { 0.0 } else { offset };
+
     let call_source_range = SourceRange::new(call.start, call.end, call.module_id);
-    Ok(vec![Z0003.at(
-        format!(
-            "custom plane in startSketchOn; offsetPlane from {} would work here",
-            plane_name
-        ),
-        call_source_range,
-    )])
+    Ok(vec![Z0003
+        .at(
+            format!(
+                "custom plane in startSketchOn; offsetPlane from {} would work here",
+                plane_name
+            ),
+            call_source_range,
+        )
+        .with_suggestion(
+            format!("use offsetPlane from {plane_name}"),
+            format!(
+                "startSketchOn(offsetPlane(\"{plane_name}\", offset = {}))",
+                format_number(offset, NumericSuffix::None)
+            ),
+        )])
 }
 
 fn get_xyz(point: &ObjectExpression) -> Option<(f64, f64, f64)> {
@@ -236,4 +260,24 @@ startSketchOn({
 })
 "
     );
+
+    #[test]
+    fn z0003_suggestion() {
+        let prog = crate::parsing::top_level_parse(
+            "\
+startSketchOn({
+  plane: {
+    origin: { x: 0, y: -14.3, z: 0 },
+    xAxis: { x: 1, y: 0, z: 0 },
+    yAxis: { x: 0, y: 0, z: 1 },
+    zAxis: { x: 0, y: -1, z: 0 }
+  }
+})
+",
+        )
+        .unwrap();
+        let findings = prog.lint(lint_should_be_offset_plane).unwrap();
+        let suggestion = findings[0].suggestion.as_ref().unwrap();
+        assert_eq!(suggestion.insert, r#"startSketchOn(offsetPlane("XZ", offset = 14.3))"#);
+    }
 }
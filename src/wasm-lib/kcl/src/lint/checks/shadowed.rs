@@ -0,0 +1,90 @@
+use anyhow::Result;
+
+use crate::{
+    lint::rule::{def_finding, Discovered, Finding},
+    parsing::ast::types::{Identifier, ImportSelector, Node as AstNode},
+    walk::Node,
+    SourceRange,
+};
+
+def_finding!(
+    Z0008,
+    "Name shadows a std library function",
+    "\
+This name is the same as a function in the KCL standard library, so the std
+library function can't be used wherever this name is in scope.
+
+For instance, declaring a variable called 'line' means that 'line' can no
+longer be used to draw lines in the rest of the program. Pick another name,
+such as 'guideLine'.
+"
+);
+
+fn lint_name(name: &str, pos: SourceRange) -> Option<Discovered> {
+    if !crate::std::name_in_stdlib(name) {
+        return None;
+    }
+    Some(Z0008.at(format!("'{name}' shadows the std library function '{name}'"), pos))
+}
+
+fn lint_identifier(ident: &AstNode<Identifier>) -> Option<Discovered> {
+    lint_name(&ident.name, ident.into())
+}
+
+pub fn lint_shadowed_std_fns(node: Node) -> Result<Vec<Discovered>> {
+    let findings = match node {
+        Node::VariableDeclaration(decl) => lint_identifier(&decl.declaration.id).into_iter().collect(),
+        Node::FunctionExpression(func) => func
+            .params
+            .iter()
+            .filter_map(|param| lint_identifier(&param.identifier))
+            .collect(),
+        Node::LabelledExpression(expr) => lint_identifier(&expr.label).into_iter().collect(),
+        Node::TagDeclarator(tag) => lint_name(&tag.name, tag.into()).into_iter().collect(),
+        Node::ImportStatement(stmt) => match &stmt.selector {
+            ImportSelector::List { items } => items
+                .iter()
+                .filter_map(|item| lint_identifier(item.alias.as_ref().unwrap_or(&item.name)))
+                .collect(),
+            ImportSelector::None { alias: Some(alias) } => lint_identifier(alias).into_iter().collect(),
+            ImportSelector::None { alias: None } | ImportSelector::Glob(_) => vec![],
+        },
+        _ => vec![],
+    };
+    Ok(findings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{lint_shadowed_std_fns, Z0008};
+    use crate::lint::rule::{test_finding, test_no_finding};
+
+    test_finding!(z0008_variable, lint_shadowed_std_fns, Z0008, "line = 1\n");
+    test_finding!(
+        z0008_function,
+        lint_shadowed_std_fns,
+        Z0008,
+        "\
+fn extrude(x) {
+  return x
+}
+"
+    );
+    test_finding!(
+        z0008_param,
+        lint_shadowed_std_fns,
+        Z0008,
+        "\
+fn f(min, b) {
+  return b
+}
+"
+    );
+    test_finding!(
+        z0008_import,
+        lint_shadowed_std_fns,
+        Z0008,
+        "import foo as circle from 'other.kcl'\n"
+    );
+    test_no_finding!(z0008_ok, lint_shadowed_std_fns, Z0008, "guideLine = 1\n");
+}
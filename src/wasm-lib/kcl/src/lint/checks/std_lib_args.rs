@@ -7,6 +7,7 @@ use crate::{
     lint::rule::{def_finding, Discovered, Finding},
     parsing::ast::types::{CallExpression, NodeRef},
     std::{FunctionKind, StdLib},
+    unparser::ExprContext,
     walk::Node,
     SourceRange,
 };
@@ -46,10 +47,19 @@ fn lint_too_many_args_std_lib_function(
 
     let fn_args_len = f.args(false).len();
     if exp.arguments.len() > fn_args_len {
-        findings.push(Z0002.at(
-            format!("expected {} arguments, found {}", fn_args_len, exp.arguments.len()),
-            SourceRange::new(exp.start, exp.end, exp.module_id),
-        ));
+        let args = exp.arguments[..fn_args_len]
+            .iter()
+            .map(|arg| arg.recast(&Default::default(), 0, ExprContext::Other))
+            .collect::<Vec<_>>()
+            .join(", ");
+        findings.push(
+            Z0002
+                .at(
+                    format!("expected {} arguments, found {}", fn_args_len, exp.arguments.len()),
+                    SourceRange::new(exp.start, exp.end, exp.module_id),
+                )
+                .with_suggestion("remove the extra arguments", format!("{}({args})", exp.callee.name)),
+        );
     }
 
     Ok(findings)
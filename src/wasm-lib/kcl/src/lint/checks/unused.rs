@@ -0,0 +1,429 @@
+use anyhow::Result;
+
+use crate::{
+    lint::rule::{def_finding, Discovered, Finding},
+    parsing::ast::types::{BodyItem, Expr, ImportPath, ItemVisibility, NodeRef, Program},
+    walk::{Node, SymbolKind, SymbolTable},
+    SourceRange,
+};
+
+def_finding!(
+    Z0004,
+    "Unused variable",
+    "\
+This variable or function is declared but never used.
+
+Unused declarations make a part harder to read, and often mean that a sketch or
+solid was left behind by mistake. Either remove the declaration, use it, or
+rename it to start with an underscore (e.g., '_sketch001') to show that it is
+deliberately unused.
+"
+);
+
+def_finding!(
+    Z0005,
+    "Unused function parameter",
+    "\
+This parameter is never used in the body of its function.
+
+If the parameter is needed to match how the function is called (for example, a
+function passed to 'reduce'), rename it to start with an underscore to show
+that it is deliberately unused.
+"
+);
+
+def_finding!(
+    Z0006,
+    "Unused tag",
+    "\
+This tag is declared but never referenced, e.g., by 'fillet', 'chamfer' or
+'segLen'. Tagging a segment or face that is never used is just noise, so the
+tag can be removed.
+
+Tags on values which are exported or returned aren't checked, since they can
+be used via '.tags' by the code which imports or calls them.
+"
+);
+
+def_finding!(
+    Z0007,
+    "Unused import",
+    "\
+Nothing that this statement imports is ever used, so the import can be removed.
+"
+);
+
+/// Find the unused variables, functions, tags and imports declared directly in a program or
+/// function body.
+fn lint_unused_in_program(program: NodeRef<'_, Program>) -> Vec<Discovered> {
+    let table = SymbolTable::new(program);
+    let escaping = escaping_values(program, &table);
+
+    let mut findings = vec![];
+    for (index, symbol) in table.symbols.iter().enumerate() {
+        // Symbols declared in nested functions are checked with the function's body.
+        if !symbol.top_level || symbol.exported || symbol.name.starts_with('_') {
+            continue;
+        }
+        if table.references_to(index).next().is_some() {
+            continue;
+        }
+        let finding = match &symbol.kind {
+            // Solids are part of the model whether or not they're named again.
+            SymbolKind::Variable if declared_value(program, &symbol.name).is_some_and(could_be_solid) => continue,
+            SymbolKind::Variable | SymbolKind::Function | SymbolKind::Label => Z0004
+                .at(format!("'{}' is never used", symbol.name), symbol.declaration)
+                .with_suggestion(format!("rename to '_{}'", symbol.name), format!("_{}", symbol.name)),
+            SymbolKind::Tag if !escaping.iter().any(|value| contains(value, &symbol.declaration)) => {
+                Z0006.at(format!("'{}' is never used", symbol.name), symbol.declaration)
+            }
+            SymbolKind::Import {
+                path: ImportPath::Kcl { .. } | ImportPath::Std { .. },
+                ..
+            } => Z0007.at(format!("'{}' is never used", symbol.name), symbol.declaration),
+            // Importing a foreign file adds its geometry to the scene, even if the name is unused.
+            SymbolKind::Tag | SymbolKind::Import { .. } | SymbolKind::Parameter | SymbolKind::LoopVariable => continue,
+        };
+        findings.push(finding);
+    }
+    findings
+}
+
+/// The expressions whose values can be used outside the program, because they're exported or
+/// returned, or are used to compute a value which is.
+fn escaping_values(program: NodeRef<'_, Program>, table: &SymbolTable) -> Vec<SourceRange> {
+    let mut escaping: Vec<SourceRange> = program
+        .body
+        .iter()
+        .filter_map(|item| match item {
+            BodyItem::VariableDeclaration(decl) if decl.visibility == ItemVisibility::Export => {
+                Some((&decl.declaration.init).into())
+            }
+            BodyItem::ReturnStatement(ret) => Some((&ret.argument).into()),
+            _ => None,
+        })
+        .collect();
+
+    let mut seen = vec![false; table.symbols.len()];
+    let mut i = 0;
+    while i < escaping.len() {
+        let value = escaping[i];
+        i += 1;
+        for reference in &table.references {
+            let Some(index) = reference.symbol else {
+                continue;
+            };
+            if seen[index] || !contains(&value, &reference.range) {
+                continue;
+            }
+            seen[index] = true;
+            if let Some(init) = declared_value(program, &table.symbols[index].name) {
+                escaping.push(init.into());
+            }
+        }
+    }
+    escaping
+}
+
+/// Is `inner` part of `outer`?
+fn contains(outer: &SourceRange, inner: &SourceRange) -> bool {
+    outer.start() <= inner.start() && inner.end() <= outer.end()
+}
+
+/// The value a variable declared directly in the program is initialised with.
+fn declared_value<'a>(program: NodeRef<'a, Program>, name: &str) -> Option<&'a Expr> {
+    program.body.iter().find_map(|item| match item {
+        BodyItem::VariableDeclaration(decl) if decl.declaration.id.name == name => Some(&decl.declaration.init),
+        _ => None,
+    })
+}
+
+/// Could this expression evaluate to geometry that's shown in the model, like a solid or
+/// imported geometry? When we can't tell, assume it could.
+fn could_be_solid(expr: &Expr) -> bool {
+    let callee = match expr {
+        Expr::Literal(_)
+        | Expr::TagDeclarator(_)
+        | Expr::BinaryExpression(_)
+        | Expr::UnaryExpression(_)
+        | Expr::FunctionExpression(_)
+        | Expr::ArrayRangeExpression(_)
+        | Expr::None(_) => return false,
+        Expr::ArrayExpression(array) => return array.elements.iter().any(could_be_solid),
+        Expr::ObjectExpression(object) => return object.properties.iter().any(|p| could_be_solid(&p.value)),
+        Expr::PipeExpression(pipe) => return pipe.body.last().is_some_and(could_be_solid),
+        Expr::LabelledExpression(expr) => return could_be_solid(&expr.expr),
+        Expr::CallExpression(call) => &call.callee.name,
+        Expr::CallExpressionKw(call) => &call.callee.name,
+        Expr::Identifier(_)
+        | Expr::PipeSubstitution(_)
+        | Expr::MemberExpression(_)
+        | Expr::IfExpression(_)
+        | Expr::ForExpression(_) => return true,
+    };
+
+    // Only the std lib tells us what functions return.
+    let Some(return_value) = crate::std::get_stdlib_fn(callee).and_then(|f| f.return_value(false)) else {
+        return true;
+    };
+    ["Solid", "ImportedGeometry", "Helix", "KclValue"]
+        .iter()
+        .any(|ty| return_value.type_.contains(ty))
+}
+
+fn lint_unused(node: Node, finding: &Finding) -> Result<Vec<Discovered>> {
+    let Node::Program(program) = node else {
+        return Ok(vec![]);
+    };
+
+    Ok(lint_unused_in_program(program)
+        .into_iter()
+        .filter(|discovered| &discovered.finding == finding)
+        .collect())
+}
+
+pub fn lint_unused_variables(node: Node) -> Result<Vec<Discovered>> {
+    lint_unused(node, &Z0004)
+}
+
+pub fn lint_unused_tags(node: Node) -> Result<Vec<Discovered>> {
+    lint_unused(node, &Z0006)
+}
+
+pub fn lint_unused_imports(node: Node) -> Result<Vec<Discovered>> {
+    lint_unused(node, &Z0007)
+}
+
+pub fn lint_unused_params(node: Node) -> Result<Vec<Discovered>> {
+    let Node::FunctionExpression(func) = node else {
+        return Ok(vec![]);
+    };
+
+    // Names used in the body which aren't declared in it must come from the parameters (or
+    // from outside the function).
+    let table = SymbolTable::new(&func.body);
+    let mut findings = vec![];
+    for param in &func.params {
+        let name = &param.identifier.name;
+        if name.starts_with('_') {
+            continue;
+        }
+        if table.references.iter().any(|r| r.symbol.is_none() && &r.name == name) {
+            continue;
+        }
+        findings.push(
+            Z0005
+                .at(format!("'{name}' is never used"), (&param.identifier).into())
+                .with_suggestion(format!("rename to '_{name}'"), format!("_{name}")),
+        );
+    }
+    Ok(findings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        lint_unused_imports, lint_unused_params, lint_unused_tags, lint_unused_variables, Z0004, Z0005, Z0006, Z0007,
+    };
+    use crate::lint::rule::{test_finding, test_no_finding};
+
+    test_finding!(
+        z0004_unused_variable,
+        lint_unused_variables,
+        Z0004,
+        "x = 1\ny = 2\nz = x + 1\n"
+    );
+    test_finding!(
+        z0004_unused_in_function,
+        lint_unused_variables,
+        Z0004,
+        "\
+fn f(x) {
+  unused = x * 2
+  return x
+}
+y = f(1)
+"
+    );
+    test_no_finding!(
+        z0004_used_in_function,
+        lint_unused_variables,
+        Z0004,
+        "\
+width = 10
+fn f(x) {
+  return x * width
+}
+export y = f(1)
+"
+    );
+    test_finding!(
+        z0004_unused_sketch,
+        lint_unused_variables,
+        Z0004,
+        "\
+sketch001 = startSketchOn('XY')
+  |> circle({ center: [0, 0], radius: 5 }, %)
+"
+    );
+    test_no_finding!(
+        z0004_solid,
+        lint_unused_variables,
+        Z0004,
+        "\
+extrude001 = startSketchOn('XY')
+  |> circle({ center: [0, 0], radius: 5 }, %)
+  |> extrude(length = 5)
+"
+    );
+    test_no_finding!(
+        z0004_user_fn_result,
+        lint_unused_variables,
+        Z0004,
+        "\
+fn f(x) {
+  return x
+}
+y = f(1)
+"
+    );
+    test_no_finding!(z0004_exported, lint_unused_variables, Z0004, "export x = 1\n");
+    test_no_finding!(z0004_underscore, lint_unused_variables, Z0004, "_x = 1\n");
+
+    test_finding!(
+        z0005_unused_param,
+        lint_unused_params,
+        Z0005,
+        "\
+fn f(x, y) {
+  return x
+}
+"
+    );
+    test_no_finding!(
+        z0005_used_param,
+        lint_unused_params,
+        Z0005,
+        "\
+fn f(x, y) {
+  z = y + 1
+  return x + z
+}
+"
+    );
+    test_no_finding!(
+        z0005_used_in_closure,
+        lint_unused_params,
+        Z0005,
+        "\
+fn f(x) {
+  fn g() {
+    return x
+  }
+  return g()
+}
+"
+    );
+    test_no_finding!(
+        z0005_underscore,
+        lint_unused_params,
+        Z0005,
+        "\
+fn f(_x) {
+  return 1
+}
+"
+    );
+
+    test_finding!(
+        z0006_unused_tag,
+        lint_unused_tags,
+        Z0006,
+        "\
+sketch001 = startSketchOn('XY')
+  |> startProfileAt([0, 0], %)
+  |> line([10, 0], %, $seg01)
+  |> line([0, 10], %, $seg02)
+  |> line([0, segLen(seg01)], %)
+"
+    );
+    test_no_finding!(
+        z0006_used_tags,
+        lint_unused_tags,
+        Z0006,
+        "\
+export sketch001 = startSketchOn('XY')
+  |> startProfileAt([0, 0], %)
+  |> line([10, 0], %, $seg01)
+  |> line([0, 10], %, $seg02)
+  |> line([0, segLen(seg01)], %)
+  |> close()
+export solid001 = extrude(5, sketch001)
+  |> fillet({ radius: 1, tags: [getNextAdjacentEdge(sketch001.tags.seg02)] }, %)
+"
+    );
+    test_no_finding!(
+        z0006_returned_tag,
+        lint_unused_tags,
+        Z0006,
+        "\
+fn square() {
+  return startSketchOn('XY')
+    |> startProfileAt([0, 0], %)
+    |> line([10, 0], %, $seg01)
+}
+sq = square()
+export len = segLen(sq.tags.seg01)
+"
+    );
+    test_no_finding!(
+        z0006_exported_tag,
+        lint_unused_tags,
+        Z0006,
+        "\
+export sketch001 = startSketchOn('XY')
+  |> startProfileAt([0, 0], %)
+  |> line([10, 0], %, $seg01)
+"
+    );
+    test_no_finding!(
+        z0006_tag_used_by_exported,
+        lint_unused_tags,
+        Z0006,
+        "\
+sketch001 = startSketchOn('XY')
+  |> startProfileAt([0, 0], %)
+  |> line([10, 0], %, $seg01)
+  |> line([0, 10], %)
+  |> close()
+export solid001 = extrude(5, sketch001)
+"
+    );
+    test_finding!(
+        z0006_unused_tag_in_function,
+        lint_unused_tags,
+        Z0006,
+        "\
+fn square() {
+  profile = startSketchOn('XY')
+    |> startProfileAt([0, 0], %)
+    |> line([10, 0], %, $seg01)
+  return 1
+}
+"
+    );
+
+    test_finding!(
+        z0007_unused_import,
+        lint_unused_imports,
+        Z0007,
+        "import foo from 'other.kcl'\n"
+    );
+    test_no_finding!(
+        z0007_used_import,
+        lint_unused_imports,
+        Z0007,
+        "import foo from 'other.kcl'\nexport x = foo()\n"
+    );
+    test_no_finding!(z0007_foreign_import, lint_unused_imports, Z0007, "import 'cube.step'\n");
+}
@@ -1,4 +1,4 @@
 pub mod checks;
 mod rule;
 
-pub use rule::{Discovered, Finding, Rule};
+pub use rule::{Discovered, Finding, LintLevel, RegisteredRule, Rule};
@@ -1,9 +1,9 @@
 use anyhow::Result;
 use schemars::JsonSchema;
-use serde::Serialize;
-use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity};
+use serde::{Deserialize, Serialize};
+use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString};
 
-use crate::{lsp::IntoDiagnostic, walk::Node, SourceRange};
+use crate::{errors::Suggestion, lsp::IntoDiagnostic, walk::Node, SourceRange};
 
 /// Check the provided AST for any found rule violations.
 ///
@@ -40,6 +40,25 @@ pub struct Discovered {
 
     /// Is this discovered issue overridden by the programmer?
     pub overridden: bool,
+
+    /// How serious this issue is, according to the project's settings.
+    pub level: LintLevel,
+
+    /// A fix for this issue, which replaces the source at `pos`.
+    pub suggestion: Option<Suggestion>,
+}
+
+impl Discovered {
+    /// Add a fix which replaces the source at `pos` with `insert`.
+    pub fn with_suggestion(self, title: impl ToString, insert: impl ToString) -> Discovered {
+        Discovered {
+            suggestion: Some(Suggestion {
+                title: title.to_string(),
+                insert: insert.to_string(),
+            }),
+            ..self
+        }
+    }
 }
 
 #[cfg(feature = "pyo3")]
@@ -84,22 +103,58 @@ impl IntoDiagnostic for &Discovered {
         Diagnostic {
             range: source_range.to_lsp_range(code),
             severity: Some(self.severity()),
-            code: None,
+            code: Some(NumberOrString::String(self.finding.code.to_owned())),
             // TODO: this is neat we can pass a URL to a help page here for this specific error.
             code_description: None,
             source: Some("lint".to_string()),
             message,
             related_information: None,
             tags: None,
-            data: None,
+            data: self
+                .suggestion
+                .as_ref()
+                .map(|suggestion| serde_json::to_value(suggestion).unwrap()),
         }
     }
 
     fn severity(&self) -> DiagnosticSeverity {
-        DiagnosticSeverity::INFORMATION
+        match self.level {
+            LintLevel::Error => DiagnosticSeverity::ERROR,
+            LintLevel::Warning => DiagnosticSeverity::WARNING,
+            LintLevel::Off | LintLevel::Info => DiagnosticSeverity::INFORMATION,
+            LintLevel::Hint => DiagnosticSeverity::HINT,
+        }
     }
 }
 
+/// How seriously a lint finding should be taken.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema, ts_rs::TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum LintLevel {
+    /// Don't check for this finding at all.
+    Off,
+    /// Only hint at the finding, e.g., in the editor.
+    Hint,
+    /// Report the finding for information.
+    #[default]
+    Info,
+    /// Warn about the finding.
+    Warning,
+    /// Treat the finding as an error.
+    Error,
+}
+
+/// A lint rule which can be enabled, disabled or re-ranked in a project's settings.
+pub struct RegisteredRule {
+    /// The finding this rule can emit.
+    pub finding: Finding,
+    /// The level of the finding, unless the project's settings say otherwise.
+    pub default_level: LintLevel,
+    /// Check the AST at a node for the finding.
+    pub check: for<'a> fn(Node<'a>) -> Result<Vec<Discovered>>,
+}
+
 /// Abstract lint problem type.
 #[derive(Clone, Debug, PartialEq, ts_rs::TS, Serialize, JsonSchema)]
 #[ts(export)]
@@ -127,6 +182,8 @@ impl Finding {
             finding: self.clone(),
             pos,
             overridden: false,
+            level: LintLevel::default(),
+            suggestion: None,
         }
     }
 }
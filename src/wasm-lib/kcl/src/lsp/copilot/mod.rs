@@ -2,6 +2,8 @@
 #![allow(dead_code)]
 
 pub mod cache;
+pub mod prompt;
+pub mod provider;
 pub mod types;
 
 use std::{
@@ -11,6 +13,7 @@ use std::{
 };
 
 use dashmap::DashMap;
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use tower_lsp::{
     jsonrpc::{Error, Result},
@@ -25,13 +28,19 @@ use tower_lsp::{
     LanguageServer,
 };
 
-use crate::lsp::{
-    backend::Backend as _,
-    copilot::{
-        cache::CopilotCache,
-        types::{
-            CopilotAcceptCompletionParams, CopilotCompletionResponse, CopilotCompletionTelemetry, CopilotEditorInfo,
-            CopilotLspCompletionParams, CopilotRejectCompletionParams, DocParams,
+use crate::{
+    docs::StdLibFnData,
+    lsp::{
+        backend::Backend as _,
+        copilot::{
+            cache::CopilotCache,
+            prompt::PromptContext,
+            provider::{CompletionProvider, CompletionRequest},
+            types::{
+                CompletionProviderConfig, CopilotAcceptCompletionParams, CopilotCompletionResponse,
+                CopilotCompletionTelemetry, CopilotEditorInfo, CopilotInitializationOptions,
+                CopilotLspCompletionParams, CopilotRejectCompletionParams, DocParams,
+            },
         },
     },
 };
@@ -58,6 +67,10 @@ pub struct Backend {
     pub code_map: DashMap<String, Vec<u8>>,
     /// The Zoo API client.
     pub zoo_client: kittycad::Client,
+    /// Where completions come from, set by the client's initialization options.
+    pub provider: Arc<RwLock<Arc<dyn CompletionProvider>>>,
+    /// The std library functions, which prompts tell the model about.
+    pub stdlib_fns: IndexMap<String, StdLibFnData>,
     /// The editor info is used to store information about the editor.
     pub editor_info: Arc<RwLock<CopilotEditorInfo>>,
     /// The cache is used to store the results of previous requests.
@@ -156,9 +169,14 @@ impl Backend {
             fs: Arc::new(fs),
             workspace_folders: Default::default(),
             code_map: Default::default(),
+            stdlib_fns: prompt::get_std_fns_from_stdlib(&crate::std::StdLib::new()),
             editor_info: Arc::new(RwLock::new(CopilotEditorInfo::default())),
             cache: Arc::new(CopilotCache::new()),
             telemetry: Default::default(),
+            provider: Arc::new(RwLock::new(provider::from_config(
+                &CompletionProviderConfig::default(),
+                &zoo_client,
+            ))),
             zoo_client,
 
             is_initialized: Default::default(),
@@ -167,40 +185,53 @@ impl Backend {
         }
     }
 
-    /// Get completions from the kittycad api.
-    pub async fn get_completions(&self, language: String, prompt: String, suffix: String) -> Result<Vec<String>> {
-        let body = kittycad::types::KclCodeCompletionRequest {
-            extra: Some(kittycad::types::KclCodeCompletionParams {
-                language: Some(language.to_string()),
-                next_indent: None,
-                trim_by_indentation: true,
-                prompt_tokens: Some(prompt.len() as u32),
-                suffix_tokens: Some(suffix.len() as u32),
-            }),
-            prompt: Some(prompt),
-            suffix: Some(suffix),
-            max_tokens: Some(500),
-            temperature: Some(1.0),
-            top_p: Some(1.0),
-            // We only handle one completion at a time, for now so don't even waste the tokens.
-            n: Some(1),
-            stop: Some(["unset".to_string()].to_vec()),
-            nwo: None,
-            // We haven't implemented streaming yet.
-            stream: false,
-        };
+    /// Use a different completion provider.
+    pub fn set_completion_provider(&self, config: &CompletionProviderConfig) -> Result<()> {
+        let provider = provider::from_config(config, &self.zoo_client);
+        let mut lock = self.provider.write().map_err(|err| Error {
+            code: tower_lsp::jsonrpc::ErrorCode::from(69),
+            data: None,
+            message: Cow::from(format!("Failed lock: {}", err)),
+        })?;
+        *lock = provider;
+        Ok(())
+    }
 
-        let resp = self
-            .zoo_client
-            .ml()
-            .create_kcl_code_completions(&body)
-            .await
+    /// Get completions from the completion provider.
+    pub async fn get_completions(&self, language: String, prompt: String, suffix: String) -> Result<Vec<String>> {
+        let provider = self
+            .provider
+            .read()
             .map_err(|err| Error {
                 code: tower_lsp::jsonrpc::ErrorCode::from(69),
                 data: None,
-                message: Cow::from(format!("Failed to get completions from zoo api: {}", err)),
-            })?;
-        Ok(resp.completions)
+                message: Cow::from(format!("Failed lock: {}", err)),
+            })?
+            .clone();
+        let request = CompletionRequest {
+            language,
+            prompt,
+            suffix,
+        };
+
+        // In wasm this needs to be spawn_local since reqwest doesn't implement Send for wasm.
+        #[cfg(target_arch = "wasm32")]
+        let completions = {
+            let (tx, rx) = tokio::sync::oneshot::channel();
+            wasm_bindgen_futures::spawn_local(async move {
+                let _ = tx.send(provider.complete(&request).await);
+            });
+            rx.await
+                .unwrap_or_else(|_| Err(anyhow::anyhow!("Completion request was dropped")))
+        };
+        #[cfg(not(target_arch = "wasm32"))]
+        let completions = provider.complete(&request).await;
+
+        completions.map_err(|err| Error {
+            code: tower_lsp::jsonrpc::ErrorCode::from(69),
+            data: None,
+            message: Cow::from(err.to_string()),
+        })
     }
 
     pub async fn set_editor_info(&self, params: CopilotEditorInfo) -> Result<Success> {
@@ -245,19 +276,17 @@ impl Backend {
         let doc_params = self.get_doc_params(&params)?;
         let line_before = doc_params.line_before.to_string();
 
-        // Let's not call it yet since it's not our model.
-        // We will need to wrap in spawn_local like we do in kcl/mod.rs for wasm only.
-        #[cfg(test)]
-        let mut completion_list = self
-            .get_completions(doc_params.language, doc_params.prefix, doc_params.suffix)
+        let context = PromptContext::new(&doc_params.prefix, &doc_params.suffix, &self.stdlib_fns);
+        let prompt = context.prompt(&doc_params.prefix);
+        let completion_list = self
+            .get_completions(doc_params.language, prompt, doc_params.suffix.clone())
             .await
             .map_err(|err| Error {
                 code: tower_lsp::jsonrpc::ErrorCode::from(69),
                 data: None,
                 message: Cow::from(format!("Failed to get completions: {}", err)),
             })?;
-        #[cfg(not(test))]
-        let mut completion_list = vec![];
+        let mut completion_list = context.rank_completions(completion_list, &doc_params.prefix, &doc_params.suffix);
 
         // if self.dev_mode
         if false {
@@ -332,7 +361,15 @@ const part001 = cube([0,0], 20)
 
 #[tower_lsp::async_trait]
 impl LanguageServer for Backend {
-    async fn initialize(&self, _: InitializeParams) -> Result<InitializeResult> {
+    async fn initialize(&self, params: InitializeParams) -> Result<InitializeResult> {
+        if let Some(options) = params.initialization_options {
+            let options: CopilotInitializationOptions = serde_json::from_value(options)
+                .map_err(|err| Error::invalid_params(format!("Invalid initialization options: {}", err)))?;
+            if let Some(config) = options.completion_provider {
+                self.set_completion_provider(&config)?;
+            }
+        }
+
         Ok(InitializeResult {
             capabilities: ServerCapabilities {
                 text_document_sync: Some(TextDocumentSyncCapability::Options(TextDocumentSyncOptions {
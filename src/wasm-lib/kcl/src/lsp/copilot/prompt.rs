@@ -0,0 +1,262 @@
+//! Build the prompts completions are asked for with, and check the completions which come back.
+
+use indexmap::{IndexMap, IndexSet};
+
+use crate::{
+    docs::StdLibFnData,
+    parsing::ast::types::{Expr, FormatOptions},
+    Program,
+};
+
+/// How many std library functions which match the word being typed to tell the model about.
+const MAX_MATCHING_STD_FNS: usize = 10;
+
+/// What the model should know about the code at the cursor.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PromptContext {
+    /// Signatures of the functions the user has declared, which can be called at the cursor.
+    pub user_fns: Vec<String>,
+    /// Signatures of the std library functions the code uses, or which match the word being typed.
+    pub std_fns: Vec<String>,
+    /// The tags which have been declared before the cursor.
+    pub tags: Vec<String>,
+    /// The problems the code already has, which completions aren't blamed for.
+    problems: Problems,
+}
+
+/// The errors and warnings in some code, each identified by where it starts and its message.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Problems {
+    errors: Vec<(usize, String)>,
+    warnings: Vec<(usize, String)>,
+}
+
+impl Problems {
+    /// Parse the code, returning its AST if it has one, and its problems.
+    fn parse(code: &str) -> (Option<Program>, Self) {
+        let mut problems = Problems::default();
+        match Program::parse(code) {
+            Ok((program, errs)) => {
+                for err in errs {
+                    let problem = (err.source_range.start(), err.message);
+                    if err.severity.is_err() {
+                        problems.errors.push(problem);
+                    } else {
+                        problems.warnings.push(problem);
+                    }
+                }
+                (program, problems)
+            }
+            // The code can't be lexed.
+            Err(err) => {
+                let start = err
+                    .source_ranges()
+                    .first()
+                    .map(|range| range.start())
+                    .unwrap_or_default();
+                problems.errors.push((start, err.message().to_owned()));
+                (None, problems)
+            }
+        }
+    }
+
+    /// Does this code, which is `before` with `len` bytes inserted at `at`, have errors which
+    /// `before` doesn't?
+    fn adds_errors(&self, before: &Problems, at: usize, len: usize) -> bool {
+        self.errors.iter().any(|err| is_new(err, &before.errors, at, len))
+    }
+
+    /// Does this code, which is `before` with `len` bytes inserted at `at`, have warnings which
+    /// `before` doesn't?
+    fn adds_warnings(&self, before: &Problems, at: usize, len: usize) -> bool {
+        self.warnings
+            .iter()
+            .any(|warning| is_new(warning, &before.warnings, at, len))
+    }
+}
+
+/// Is a problem in some code with `len` bytes inserted at `at` new, compared to the problems the
+/// code had without them?
+fn is_new((start, message): &(usize, String), before: &[(usize, String)], at: usize, len: usize) -> bool {
+    if *start < at {
+        return !before
+            .iter()
+            .any(|problem| problem.0 == *start && problem.1 == *message);
+    }
+    if *start < at + len {
+        return true;
+    }
+    // Parsing stops at the first error, so a problem after the inserted text may have been hidden
+    // by one the code already had between the insertion and the problem.
+    let start = start - len;
+    !before
+        .iter()
+        .any(|(before_start, _)| (at..=start).contains(before_start))
+}
+
+/// The std library functions which completions can use, keyed by name. This is expensive to build,
+/// so should be built once.
+pub fn get_std_fns_from_stdlib(stdlib: &crate::std::StdLib) -> IndexMap<String, StdLibFnData> {
+    let mut fns: IndexMap<_, _> = stdlib
+        .fns
+        .values()
+        .filter_map(|f| f.to_json().ok())
+        .map(|data| (data.name.clone(), data))
+        .collect();
+    fns.sort_keys();
+    fns
+}
+
+impl PromptContext {
+    /// Find what's in scope at the cursor, which is between the prefix and the suffix.
+    pub fn new(prefix: &str, suffix: &str, stdlib_fns: &IndexMap<String, StdLibFnData>) -> Self {
+        let (program, problems) = Problems::parse(&format!("{prefix}{suffix}"));
+        let parsed = match program {
+            Some(program) => Some((program, prefix.len())),
+            // The code is usually being edited, so it may only parse without the line being typed.
+            None => {
+                let head = &prefix[..prefix.rfind('\n').map(|i| i + 1).unwrap_or(0)];
+                Problems::parse(&format!("{head}{suffix}"))
+                    .0
+                    .map(|program| (program, head.len()))
+            }
+        };
+
+        let mut user_fns = IndexSet::new();
+        let mut user_fn_names = IndexSet::new();
+        let mut called = IndexSet::new();
+        let mut tags = IndexSet::new();
+        if let Some((program, cursor)) = &parsed {
+            let cursor = *cursor;
+            let mut nodes = vec![crate::walk::Node::from(&program.ast)];
+            while let Some(node) = nodes.pop() {
+                match node {
+                    crate::walk::Node::VariableDeclaration(decl) if decl.end <= cursor => {
+                        if let Expr::FunctionExpression(func) = &decl.declaration.init {
+                            let options = FormatOptions::default();
+                            let params = func
+                                .params
+                                .iter()
+                                .map(|param| param.recast(&options, 0))
+                                .collect::<Vec<_>>()
+                                .join(", ");
+                            let return_type = match &func.return_type {
+                                Some(rt) => format!(": {}", rt.recast(&options, 0)),
+                                None => String::new(),
+                            };
+                            let name = &decl.declaration.id.name;
+                            user_fns.insert(format!("fn {name}({params}){return_type}"));
+                            user_fn_names.insert(name.clone());
+                        }
+                    }
+                    // Functions and tags declared in another function's body aren't in scope.
+                    crate::walk::Node::FunctionExpression(func) if !(func.start..=func.end).contains(&cursor) => {
+                        continue;
+                    }
+                    crate::walk::Node::TagDeclarator(tag) if tag.end <= cursor => {
+                        tags.insert(tag.name.clone());
+                    }
+                    crate::walk::Node::CallExpression(call) => {
+                        called.insert(call.callee.name.clone());
+                    }
+                    crate::walk::Node::CallExpressionKw(call) => {
+                        called.insert(call.callee.name.clone());
+                    }
+                    _ => {}
+                }
+
+                let mut children = crate::walk::Visitable::children(&node);
+                children.reverse();
+                nodes.extend(children);
+            }
+        }
+
+        let mut std_fns = IndexMap::new();
+        // The functions the code already calls.
+        for name in called.iter().filter(|name| !user_fn_names.contains(*name)) {
+            if let Some(data) = stdlib_fns.get(name) {
+                std_fns.insert(name.as_str(), data);
+            }
+        }
+        // The functions the user might be in the middle of typing the name of.
+        let word = word_before(prefix);
+        if !word.is_empty() {
+            let matching = stdlib_fns
+                .iter()
+                .filter(|(name, data)| name.starts_with(word) && !data.deprecated && !data.unpublished)
+                .take(MAX_MATCHING_STD_FNS)
+                .map(|(name, data)| (name.as_str(), data))
+                .collect::<Vec<_>>();
+            std_fns.extend(matching);
+        }
+
+        Self {
+            user_fns: user_fns.into_iter().collect(),
+            std_fns: std_fns
+                .into_values()
+                .map(|data| format!("{}: {}", data.fn_signature(), data.summary))
+                .collect(),
+            tags: tags.into_iter().collect(),
+            problems,
+        }
+    }
+
+    /// The prompt to complete, which is the prefix with this context in comments above it.
+    pub fn prompt(&self, prefix: &str) -> String {
+        let mut prompt = String::new();
+        if !self.user_fns.is_empty() {
+            prompt.push_str("// Functions in scope:\n");
+            for user_fn in &self.user_fns {
+                prompt.push_str(&format!("// {user_fn}\n"));
+            }
+        }
+        if !self.std_fns.is_empty() {
+            prompt.push_str("// Standard library functions:\n");
+            for std_fn in &self.std_fns {
+                prompt.push_str(&format!("// {std_fn}\n"));
+            }
+        }
+        if !self.tags.is_empty() {
+            prompt.push_str(&format!("// Tags in scope: {}\n", self.tags.join(", ")));
+        }
+        prompt.push_str(prefix);
+        prompt
+    }
+
+    /// Drop the completions which would add errors to the code once inserted at the cursor, and
+    /// put the ones which don't add warnings either first. Errors elsewhere in the code, which were
+    /// there before, don't count against a completion.
+    pub fn rank_completions(&self, completions: Vec<String>, prefix: &str, suffix: &str) -> Vec<String> {
+        let mut clean = Vec::new();
+        let mut with_warnings = Vec::new();
+        let completions: IndexSet<String> = completions.into_iter().collect();
+        for completion in completions {
+            if completion.trim().is_empty() {
+                continue;
+            }
+            let (_, problems) = Problems::parse(&format!("{prefix}{completion}{suffix}"));
+            if problems.adds_errors(&self.problems, prefix.len(), completion.len()) {
+                continue;
+            }
+            if problems.adds_warnings(&self.problems, prefix.len(), completion.len()) {
+                with_warnings.push(completion);
+            } else {
+                clean.push(completion);
+            }
+        }
+        clean.extend(with_warnings);
+        clean
+    }
+}
+
+/// The identifier, or part of one, just before the cursor.
+fn word_before(prefix: &str) -> &str {
+    let start = prefix
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| c.is_alphanumeric() || *c == '_')
+        .last()
+        .map(|(i, _)| i)
+        .unwrap_or(prefix.len());
+    &prefix[start..]
+}
@@ -0,0 +1,178 @@
+//! The models completions can come from.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::lsp::copilot::types::CompletionProviderConfig;
+
+/// The most tokens any completion can be.
+const MAX_TOKENS: u16 = 500;
+/// Where completions stop.
+const STOP: &str = "unset";
+
+/// The code to complete.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompletionRequest {
+    pub language: String,
+    /// The code before the cursor.
+    pub prompt: String,
+    /// The code after the cursor.
+    pub suffix: String,
+}
+
+/// Something which can suggest the code to insert at the cursor.
+// In wasm, requests can't be sent from other threads, so their futures aren't Send.
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+pub trait CompletionProvider: std::fmt::Debug + Send + Sync {
+    async fn complete(&self, request: &CompletionRequest) -> Result<Vec<String>>;
+}
+
+/// Make the provider a client asked for. The KittyCAD API is reached with the given client.
+pub fn from_config(config: &CompletionProviderConfig, zoo_client: &kittycad::Client) -> Arc<dyn CompletionProvider> {
+    match config {
+        CompletionProviderConfig::Zoo => Arc::new(ZooProvider {
+            client: zoo_client.clone(),
+        }),
+        CompletionProviderConfig::Http { url, token } => {
+            let mut client = kittycad::Client::new(token.clone().unwrap_or_default());
+            client.set_base_url(url);
+            Arc::new(ZooProvider { client })
+        }
+        CompletionProviderConfig::OpenAi { url, model, api_key } => Arc::new(OpenAiProvider {
+            client: reqwest::Client::new(),
+            url: url.trim_end_matches('/').to_owned(),
+            model: model.clone(),
+            api_key: api_key.clone(),
+        }),
+        CompletionProviderConfig::Stub { completions } => Arc::new(StubProvider {
+            completions: completions.clone(),
+        }),
+    }
+}
+
+/// Completions from the KittyCAD API, or any server with the same completions endpoint.
+#[derive(Debug)]
+pub struct ZooProvider {
+    client: kittycad::Client,
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+impl CompletionProvider for ZooProvider {
+    async fn complete(&self, request: &CompletionRequest) -> Result<Vec<String>> {
+        let body = kittycad::types::KclCodeCompletionRequest {
+            extra: Some(kittycad::types::KclCodeCompletionParams {
+                language: Some(request.language.clone()),
+                next_indent: None,
+                trim_by_indentation: true,
+                prompt_tokens: Some(request.prompt.len() as u32),
+                suffix_tokens: Some(request.suffix.len() as u32),
+            }),
+            prompt: Some(request.prompt.clone()),
+            suffix: Some(request.suffix.clone()),
+            max_tokens: Some(MAX_TOKENS),
+            temperature: Some(1.0),
+            top_p: Some(1.0),
+            // We only handle one completion at a time, for now so don't even waste the tokens.
+            n: Some(1),
+            stop: Some(vec![STOP.to_owned()]),
+            nwo: None,
+            // We haven't implemented streaming yet.
+            stream: false,
+        };
+
+        let resp = self
+            .client
+            .ml()
+            .create_kcl_code_completions(&body)
+            .await
+            .map_err(|err| anyhow::anyhow!("Failed to get completions from zoo api: {}", err))?;
+        Ok(resp.completions)
+    }
+}
+
+/// Completions from a server with an OpenAI-compatible completions endpoint, such as a model
+/// running locally.
+#[derive(Debug)]
+pub struct OpenAiProvider {
+    client: reqwest::Client,
+    /// The API's base URL, e.g. `http://localhost:8000/v1`.
+    url: String,
+    model: String,
+    api_key: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct OpenAiCompletionRequest<'a> {
+    model: &'a str,
+    prompt: &'a str,
+    suffix: &'a str,
+    max_tokens: u16,
+    temperature: f64,
+    n: u32,
+    stop: [&'a str; 1],
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiCompletionResponse {
+    choices: Vec<OpenAiChoice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiChoice {
+    text: String,
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+impl CompletionProvider for OpenAiProvider {
+    async fn complete(&self, request: &CompletionRequest) -> Result<Vec<String>> {
+        let body = OpenAiCompletionRequest {
+            model: &self.model,
+            prompt: &request.prompt,
+            suffix: &request.suffix,
+            max_tokens: MAX_TOKENS,
+            temperature: 1.0,
+            n: 1,
+            stop: [STOP],
+        };
+        let mut req = self
+            .client
+            .post(format!("{}/completions", self.url))
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(serde_json::to_vec(&body)?);
+        if let Some(api_key) = &self.api_key {
+            req = req.bearer_auth(api_key);
+        }
+
+        let resp = req.send().await?;
+        let status = resp.status();
+        let bytes = resp.bytes().await?;
+        if !status.is_success() {
+            anyhow::bail!(
+                "Failed to get completions from {}: {status} {}",
+                self.url,
+                String::from_utf8_lossy(&bytes)
+            );
+        }
+        let resp: OpenAiCompletionResponse = serde_json::from_slice(&bytes)?;
+        Ok(resp.choices.into_iter().map(|choice| choice.text).collect())
+    }
+}
+
+/// The same completions every time, without asking any model. Useful for tests and working offline.
+#[derive(Debug)]
+pub struct StubProvider {
+    completions: Vec<String>,
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+impl CompletionProvider for StubProvider {
+    async fn complete(&self, _request: &CompletionRequest) -> Result<Vec<String>> {
+        Ok(self.completions.clone())
+    }
+}
@@ -203,3 +203,50 @@ pub struct CopilotCompletionTelemetry {
     pub completion: CopilotCyclingCompletion,
     pub params: CopilotLspCompletionParams,
 }
+
+/// Where completions come from.
+#[derive(Debug, Serialize, Deserialize, Clone, ts_rs::TS, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+#[ts(export)]
+pub enum CompletionProviderConfig {
+    /// The KittyCAD API, with the client the server was started with.
+    Zoo,
+    /// A server with the same completions endpoint as the KittyCAD API, e.g. one running on-prem.
+    #[serde(rename_all = "camelCase")]
+    Http {
+        /// The server's base URL.
+        url: String,
+        /// The token to authenticate with, if the server needs one.
+        token: Option<String>,
+    },
+    /// A server with an OpenAI-compatible completions endpoint, e.g. a model running locally.
+    #[serde(rename_all = "camelCase")]
+    OpenAi {
+        /// The API's base URL, e.g. `http://localhost:8000/v1`.
+        url: String,
+        /// The model to ask for completions.
+        model: String,
+        /// The key to authenticate with, if the server needs one.
+        api_key: Option<String>,
+    },
+    /// The same completions every time, without asking any model.
+    #[serde(rename_all = "camelCase")]
+    Stub { completions: Vec<String> },
+}
+
+impl Default for CompletionProviderConfig {
+    fn default() -> Self {
+        // No completions, so no code is sent anywhere, until a client asks for a model.
+        Self::Stub { completions: vec![] }
+    }
+}
+
+/// The options a client can initialize the server with.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, ts_rs::TS, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct CopilotInitializationOptions {
+    /// Where completions come from.
+    #[serde(default)]
+    pub completion_provider: Option<CompletionProviderConfig>,
+}
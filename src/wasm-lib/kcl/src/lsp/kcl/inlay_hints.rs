@@ -0,0 +1,290 @@
+//! Inlay hints for the `kcl` lsp server.
+
+use std::collections::HashMap;
+
+use indexmap::IndexMap;
+use tower_lsp::lsp_types::{InlayHint, InlayHintKind, InlayHintLabel, ParameterLabel, Range, SignatureHelp};
+
+use crate::{
+    execution::{
+        kcl_value::{NumericType, UnitType},
+        KclValue, MetaSettings,
+    },
+    parsing::ast::types::{
+        BinaryOperator, BinaryPart, Expr, LiteralValue, Node, Program, UnaryOperator, VariableDeclaration,
+    },
+    SourceRange,
+};
+
+/// Everything we know about a file which is used to work out its inlay hints.
+pub(super) struct HintContext<'a> {
+    /// The source code of the file.
+    pub code: &'a str,
+    /// The range of the file the client wants hints for.
+    pub range: Range,
+    /// The top level variables from the last successful execution of the file, if any.
+    pub variables: Option<&'a IndexMap<String, KclValue>>,
+    /// The signatures of the std lib functions, for the names of their parameters.
+    pub stdlib_signatures: &'a HashMap<String, SignatureHelp>,
+}
+
+/// What we know about the names in scope at some point in the program. Function bodies get their
+/// own scope, so names declared in them shadow, rather than replace, names from outside.
+#[derive(Default)]
+struct Scope {
+    /// The parameter names of the functions the user has declared, or `None` if the name is
+    /// bound to something other than a function.
+    fns: HashMap<String, Option<Vec<String>>>,
+    /// The numeric types we've inferred for variables so far.
+    types: HashMap<String, NumericType>,
+}
+
+/// A step of the walk over the program.
+enum Step<'a> {
+    Visit(crate::walk::Node<'a>),
+    /// Leave the scope of the function whose body we've finished walking.
+    ExitScope,
+}
+
+/// Get all the inlay hints for the given program.
+pub(super) fn get_inlay_hints(ast: &Node<Program>, cx: &HintContext<'_>) -> Vec<InlayHint> {
+    let settings = ast.meta_settings().ok().flatten().unwrap_or_default();
+
+    let mut scopes = vec![Scope::default()];
+    let mut hints = vec![];
+    let mut steps = vec![Step::Visit(crate::walk::Node::from(ast))];
+    // Walk the tree in source order, so that types of variables are known before they are used.
+    while let Some(step) = steps.pop() {
+        let node = match step {
+            Step::Visit(node) => node,
+            Step::ExitScope => {
+                scopes.pop();
+                continue;
+            }
+        };
+        match node {
+            crate::walk::Node::VariableDeclaration(decl) => {
+                let params = match &decl.declaration.init {
+                    Expr::FunctionExpression(func) => {
+                        Some(func.params.iter().map(|p| p.identifier.name.clone()).collect())
+                    }
+                    _ => None,
+                };
+                let top_level = scopes.len() == 1;
+                let scope = scopes.last_mut().expect("there is always a scope");
+                scope.fns.insert(decl.declaration.id.name.clone(), params);
+                variable_hints(decl, top_level, &settings, &mut scopes, cx, &mut hints);
+            }
+            crate::walk::Node::FunctionExpression(func) => {
+                // Parameters shadow any outer names, and we know nothing about their values.
+                let mut scope = Scope::default();
+                for param in &func.params {
+                    scope.fns.insert(param.identifier.name.clone(), None);
+                    scope.types.insert(param.identifier.name.clone(), NumericType::Unknown);
+                }
+                scopes.push(scope);
+                steps.push(Step::ExitScope);
+            }
+            crate::walk::Node::CallExpression(call) => {
+                let params = match scopes.iter().rev().find_map(|scope| scope.fns.get(&call.callee.name)) {
+                    Some(params) => params.clone(),
+                    None => std_fn_params(cx, &call.callee.name),
+                };
+                if let Some(params) = params {
+                    for (arg, param) in call.arguments.iter().zip(params) {
+                        if !should_hint_argument(arg, &param) {
+                            continue;
+                        }
+                        let position = SourceRange::from(arg).start_to_lsp_position(cx.code);
+                        push_hint(
+                            &mut hints,
+                            cx,
+                            InlayHint {
+                                position,
+                                label: InlayHintLabel::String(format!("{param}:")),
+                                kind: Some(InlayHintKind::PARAMETER),
+                                text_edits: None,
+                                tooltip: None,
+                                padding_left: Some(false),
+                                padding_right: Some(true),
+                                data: None,
+                            },
+                        );
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        let mut children = crate::walk::Visitable::children(&node);
+        children.reverse();
+        steps.extend(children.into_iter().map(Step::Visit));
+    }
+
+    hints
+}
+
+/// The names of the parameters of a std lib function.
+fn std_fn_params(cx: &HintContext<'_>, name: &str) -> Option<Vec<String>> {
+    let signature = cx.stdlib_signatures.get(name)?.signatures.first()?;
+    signature
+        .parameters
+        .as_ref()?
+        .iter()
+        .map(|param| match &param.label {
+            ParameterLabel::Simple(name) => Some(name.clone()),
+            ParameterLabel::LabelOffsets(_) => None,
+        })
+        .collect()
+}
+
+/// Add the type and value hints for a variable declaration.
+fn variable_hints(
+    decl: &Node<VariableDeclaration>,
+    top_level: bool,
+    settings: &MetaSettings,
+    scopes: &mut [Scope],
+    cx: &HintContext<'_>,
+    hints: &mut Vec<InlayHint>,
+) {
+    let name = &decl.declaration.id.name;
+    let init = &decl.declaration.init;
+
+    // Prefer what we got from actually executing the code, if this is a top level variable.
+    let value = cx
+        .variables
+        .filter(|_| top_level)
+        .and_then(|variables| match variables.get(name) {
+            Some(KclValue::Number { value, ty, .. }) => Some((*value, ty.clone())),
+            _ => None,
+        });
+    let ty = match &value {
+        Some((_, ty)) if *ty != NumericType::Unknown => Some(ty.clone()),
+        _ => infer_numeric_type(init, settings, scopes),
+    };
+
+    // Record the variable even if we don't know its type, so that it shadows outer variables.
+    let ty = ty.unwrap_or(NumericType::Unknown);
+    let scope = scopes.last_mut().expect("there is always a scope");
+    scope.types.insert(name.clone(), ty.clone());
+    if ty == NumericType::Unknown {
+        return;
+    }
+
+    push_hint(
+        hints,
+        cx,
+        InlayHint {
+            position: SourceRange::from(&decl.declaration.id).end_to_lsp_position(cx.code),
+            label: InlayHintLabel::String(format!(": {ty}")),
+            kind: Some(InlayHintKind::TYPE),
+            text_edits: None,
+            tooltip: None,
+            padding_left: Some(false),
+            padding_right: Some(false),
+            data: None,
+        },
+    );
+
+    // There's no point repeating the value of a literal.
+    if matches!(init, Expr::Literal(_)) {
+        return;
+    }
+    let Some((value, ty)) = value else {
+        return;
+    };
+    push_hint(
+        hints,
+        cx,
+        InlayHint {
+            position: SourceRange::from(init).end_to_lsp_position(cx.code),
+            label: InlayHintLabel::String(format!("= {}", format_value(value, &ty))),
+            kind: None,
+            text_edits: None,
+            tooltip: None,
+            padding_left: Some(true),
+            padding_right: Some(false),
+            data: None,
+        },
+    );
+}
+
+/// Only add the hint if it is in the range the client asked for.
+fn push_hint(hints: &mut Vec<InlayHint>, cx: &HintContext<'_>, hint: InlayHint) {
+    if hint.position < cx.range.start || hint.position > cx.range.end {
+        return;
+    }
+    hints.push(hint);
+}
+
+/// Is it worth labelling this argument with the parameter's name?
+fn should_hint_argument(arg: &Expr, param: &str) -> bool {
+    match arg {
+        // Everyone knows what `%` is.
+        Expr::PipeSubstitution(_) => false,
+        // `line(end, %)` says it all already.
+        Expr::Identifier(id) => id.name != param,
+        _ => true,
+    }
+}
+
+/// Work out the numeric type of an expression without executing it.
+fn infer_numeric_type(expr: &Expr, settings: &MetaSettings, scopes: &[Scope]) -> Option<NumericType> {
+    match expr {
+        Expr::Literal(literal) => match &literal.value {
+            LiteralValue::Number { suffix, .. } => Some(NumericType::from_parsed(*suffix, settings)),
+            _ => None,
+        },
+        Expr::Identifier(id) => lookup_type(scopes, &id.name),
+        Expr::BinaryExpression(expr) => {
+            let left = infer_binary_part_type(&expr.left, settings, scopes)?;
+            let right = infer_binary_part_type(&expr.right, settings, scopes)?;
+            match expr.operator {
+                BinaryOperator::Add | BinaryOperator::Sub => Some(left.combine(&right)),
+                // Scaling a number by a plain number doesn't change its units.
+                BinaryOperator::Mul | BinaryOperator::Div => match (&left, &right) {
+                    (NumericType::Known(_), NumericType::Default { .. }) => Some(left),
+                    (NumericType::Default { .. }, NumericType::Known(_)) if expr.operator == BinaryOperator::Mul => {
+                        Some(right)
+                    }
+                    _ => Some(left.combine(&right)),
+                },
+                BinaryOperator::Mod => Some(left),
+                BinaryOperator::Pow => Some(NumericType::Unknown),
+                _ => None,
+            }
+        }
+        Expr::UnaryExpression(expr) if expr.operator == UnaryOperator::Neg => {
+            infer_binary_part_type(&expr.argument, settings, scopes)
+        }
+        _ => None,
+    }
+}
+
+fn infer_binary_part_type(part: &BinaryPart, settings: &MetaSettings, scopes: &[Scope]) -> Option<NumericType> {
+    match part {
+        BinaryPart::Literal(literal) => infer_numeric_type(&Expr::Literal(literal.clone()), settings, scopes),
+        BinaryPart::Identifier(id) => lookup_type(scopes, &id.name),
+        BinaryPart::BinaryExpression(expr) => {
+            infer_numeric_type(&Expr::BinaryExpression(expr.clone()), settings, scopes)
+        }
+        BinaryPart::UnaryExpression(expr) => infer_numeric_type(&Expr::UnaryExpression(expr.clone()), settings, scopes),
+        _ => None,
+    }
+}
+
+/// The type of the innermost variable called `name`, if we know it.
+fn lookup_type(scopes: &[Scope], name: &str) -> Option<NumericType> {
+    scopes.iter().rev().find_map(|scope| scope.types.get(name)).cloned()
+}
+
+/// Format an evaluated number with its units, e.g. `10mm`.
+fn format_value(value: f64, ty: &NumericType) -> String {
+    // Don't show floating point noise.
+    let rounded = (value * 1e6).round() / 1e6;
+    match ty {
+        NumericType::Known(UnitType::Length(len)) => format!("{rounded}{len}"),
+        NumericType::Known(UnitType::Angle(angle)) => format!("{rounded}{angle}"),
+        _ => format!("{rounded}"),
+    }
+}
@@ -11,11 +11,13 @@ use std::{
 use tokio::sync::RwLock;
 
 pub mod custom_notifications;
+mod inlay_hints;
 
 use anyhow::Result;
 #[cfg(feature = "cli")]
 use clap::Parser;
 use dashmap::DashMap;
+use indexmap::IndexMap;
 use sha2::Digest;
 use tower_lsp::{
     jsonrpc::Result as RpcResult,
@@ -28,27 +30,32 @@ use tower_lsp::{
         DidSaveTextDocumentParams, DocumentDiagnosticParams, DocumentDiagnosticReport, DocumentDiagnosticReportResult,
         DocumentFilter, DocumentFormattingParams, DocumentSymbol, DocumentSymbolParams, DocumentSymbolResponse,
         Documentation, FoldingRange, FoldingRangeParams, FoldingRangeProviderCapability, FullDocumentDiagnosticReport,
-        Hover, HoverContents, HoverParams, HoverProviderCapability, InitializeParams, InitializeResult,
-        InitializedParams, InlayHint, InlayHintParams, InsertTextFormat, MarkupContent, MarkupKind, MessageType, OneOf,
-        Position, RelatedFullDocumentDiagnosticReport, RenameFilesParams, RenameParams, SemanticToken,
-        SemanticTokenModifier, SemanticTokenType, SemanticTokens, SemanticTokensFullOptions, SemanticTokensLegend,
-        SemanticTokensOptions, SemanticTokensParams, SemanticTokensRegistrationOptions, SemanticTokensResult,
-        SemanticTokensServerCapabilities, ServerCapabilities, SignatureHelp, SignatureHelpOptions, SignatureHelpParams,
-        StaticRegistrationOptions, TextDocumentItem, TextDocumentRegistrationOptions, TextDocumentSyncCapability,
-        TextDocumentSyncKind, TextDocumentSyncOptions, TextEdit, WorkDoneProgressOptions, WorkspaceEdit,
-        WorkspaceFolder, WorkspaceFoldersServerCapabilities, WorkspaceServerCapabilities,
+        GotoDefinitionParams, GotoDefinitionResponse, Hover, HoverContents, HoverParams, HoverProviderCapability,
+        InitializeParams, InitializeResult, InitializedParams, InlayHint, InlayHintParams, InsertTextFormat, Location,
+        MarkupContent, MarkupKind, MessageType, OneOf, Position, ReferenceParams, RelatedFullDocumentDiagnosticReport,
+        RenameFilesParams, RenameParams, SemanticToken, SemanticTokenModifier, SemanticTokenType, SemanticTokens,
+        SemanticTokensFullOptions, SemanticTokensLegend, SemanticTokensOptions, SemanticTokensParams,
+        SemanticTokensRegistrationOptions, SemanticTokensResult, SemanticTokensServerCapabilities, ServerCapabilities,
+        SignatureHelp, SignatureHelpOptions, SignatureHelpParams, StaticRegistrationOptions, TextDocumentItem,
+        TextDocumentRegistrationOptions, TextDocumentSyncCapability, TextDocumentSyncKind, TextDocumentSyncOptions,
+        TextEdit, WorkDoneProgressOptions, WorkspaceEdit, WorkspaceFolder, WorkspaceFoldersServerCapabilities,
+        WorkspaceServerCapabilities,
     },
     Client, LanguageServer,
 };
 
 use crate::{
     errors::Suggestion,
+    fs::FileSystem,
     lsp::{backend::Backend as _, util::IntoDiagnostic},
+    modules::ModulePath,
     parsing::{
-        ast::types::{Expr, Node, VariableKind},
+        ast::types::{BodyItem, Expr, ImportPath, ImportSelector, Node, VariableKind},
         token::TokenStream,
         PIPE_OPERATOR,
     },
+    settings::types::project::{LintSettings, ProjectConfiguration, PROJECT_SETTINGS_FILE_NAME},
+    walk::{SymbolKind, SymbolTable},
     ModuleId, Program, SourceRange,
 };
 const SEMANTIC_TOKEN_TYPES: [SemanticTokenType; 10] = [
@@ -110,6 +117,8 @@ pub struct Backend {
     pub symbols_map: DashMap<String, Vec<DocumentSymbol>>,
     /// Semantic tokens map.
     pub semantic_tokens_map: DashMap<String, Vec<SemanticToken>>,
+    /// The evaluated top level variables from the last successful execution of each file.
+    pub variables_map: DashMap<String, IndexMap<String, crate::execution::KclValue>>,
     /// The Zoo API client.
     pub zoo_client: kittycad::Client,
     /// If we can send telemetry for this user.
@@ -183,6 +192,7 @@ impl Backend {
             diagnostics_map: Default::default(),
             symbols_map: Default::default(),
             semantic_tokens_map: Default::default(),
+            variables_map: Default::default(),
             is_initialized: Default::default(),
         })
     }
@@ -248,6 +258,7 @@ impl crate::lsp::backend::Backend for Backend {
         self.diagnostics_map.clear();
         self.symbols_map.clear();
         self.semantic_tokens_map.clear();
+        self.variables_map.clear();
     }
 
     fn current_diagnostics_map(&self) -> &DashMap<String, Vec<Diagnostic>> {
@@ -299,7 +310,7 @@ impl crate::lsp::backend::Backend for Backend {
 
         // Lets update the ast.
 
-        let (ast, errs) = match crate::parsing::parse_tokens(tokens.clone()).0 {
+        let (ast, mut errs) = match crate::parsing::parse_tokens(tokens.clone()).0 {
             Ok(result) => result,
             Err(err) => {
                 self.add_to_diagnostics(&params, &[err], true).await;
@@ -307,6 +318,13 @@ impl crate::lsp::backend::Backend for Backend {
                 return;
             }
         };
+        // Only check the types of a program which parsed cleanly, otherwise there'll be spurious
+        // mismatches.
+        if let Some(ast) = &ast {
+            if !errs.iter().any(|e| e.severity.is_err()) {
+                errs.extend(crate::parsing::typecheck::check_program(ast));
+            }
+        }
 
         self.add_to_diagnostics(&params, &errs, true).await;
 
@@ -350,7 +368,13 @@ impl crate::lsp::backend::Backend for Backend {
             // Update our semantic tokens.
             self.update_semantic_tokens(&tokens, &params).await;
 
-            let discovered_findings = ast.lint_all().into_iter().flatten().collect::<Vec<_>>();
+            let lint_settings = self.lint_settings(&params.uri).await;
+            let discovered_findings = ast
+                .lint_with_settings(&lint_settings)
+                .into_iter()
+                .flatten()
+                .filter(|finding| !finding.overridden)
+                .collect::<Vec<_>>();
             self.add_to_diagnostics(&params, &discovered_findings, false).await;
         }
 
@@ -573,16 +597,23 @@ impl Backend {
     }
 
     async fn clear_diagnostics_map(&self, uri: &url::Url, severity: Option<DiagnosticSeverity>) {
+        // If we only want to clear a specific severity, do that.
+        // Lints can have any severity, so they are only cleared along with everything else.
+        match severity {
+            Some(severity) => {
+                self.retain_diagnostics(uri, |x| x.severity != Some(severity) || is_lint(x))
+                    .await
+            }
+            None => self.retain_diagnostics(uri, |_| false).await,
+        }
+    }
+
+    async fn retain_diagnostics(&self, uri: &url::Url, keep: impl Fn(&Diagnostic) -> bool) {
         let Some(mut items) = self.diagnostics_map.get_mut(uri.as_str()) else {
             return;
         };
 
-        // If we only want to clear a specific severity, do that.
-        if let Some(severity) = severity {
-            items.retain(|x| x.severity != Some(severity));
-        } else {
-            items.clear();
-        }
+        items.retain(keep);
 
         if items.is_empty() {
             #[cfg(not(target_arch = "wasm32"))]
@@ -614,22 +645,26 @@ impl Backend {
             return;
         }
 
+        let diagnostics = diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.to_lsp_diagnostic(&params.text))
+            .collect::<Vec<_>>();
+
         if clear_all_before_add {
             self.clear_diagnostics_map(&params.uri, None).await;
-        } else if diagnostics.iter().all(|x| x.severity() == DiagnosticSeverity::ERROR) {
+        } else if diagnostics.iter().all(is_lint) {
+            // If the diagnostic is a lint, we will pass them all to add at once so we need to
+            // clear the old ones.
+            self.retain_diagnostics(&params.uri, |x| !is_lint(x)).await;
+        } else if diagnostics
+            .iter()
+            .all(|x| x.severity == Some(DiagnosticSeverity::ERROR))
+        {
             // If the diagnostic is an error, it will be the only error we get since that halts
             // execution.
             // Clear the diagnostics before we add a new one.
             self.clear_diagnostics_map(&params.uri, Some(DiagnosticSeverity::ERROR))
                 .await;
-        } else if diagnostics
-            .iter()
-            .all(|x| x.severity() == DiagnosticSeverity::INFORMATION)
-        {
-            // If the diagnostic is a lint, we will pass them all to add at once so we need to
-            // clear the old ones.
-            self.clear_diagnostics_map(&params.uri, Some(DiagnosticSeverity::INFORMATION))
-                .await;
         }
 
         let mut items = if let Some(items) = self.diagnostics_map.get(params.uri.as_str()) {
@@ -639,8 +674,7 @@ impl Backend {
             vec![]
         };
 
-        for diagnostic in diagnostics {
-            let d = diagnostic.to_lsp_diagnostic(&params.text);
+        for d in diagnostics {
             // Make sure we don't duplicate diagnostics.
             if !items.iter().any(|x| x == &d) {
                 items.push(d);
@@ -677,7 +711,10 @@ impl Backend {
                 // string.
                 Err(anyhow::anyhow!("failed to execute code"))
             }
-            Ok(_) => Ok(()),
+            Ok(outcome) => {
+                self.variables_map.insert(params.uri.to_string(), outcome.variables);
+                Ok(())
+            }
         }
     }
 
@@ -791,6 +828,147 @@ impl Backend {
         Ok(())
     }
 
+    /// Get the code and parsed AST for a file, preferring what we already have in memory.
+    async fn get_code_and_ast(&self, uri: &url::Url) -> Option<(String, Node<crate::parsing::ast::types::Program>)> {
+        let code = match self.code_map.get(uri.as_str()) {
+            Some(code) => String::from_utf8(code.clone()).ok()?,
+            None => {
+                // The file might not be open, or part of the workspace, so read it from disk.
+                let path = crate::lsp::util::uri_to_path(uri)?;
+                self.fs.read_to_string(&path, SourceRange::default()).await.ok()?
+            }
+        };
+
+        if let Some(ast) = self.ast_map.get(uri.as_str()) {
+            return Some((code, ast.clone()));
+        }
+
+        let ast = crate::parsing::parse_str(&code, ModuleId::default())
+            .parse_errs_as_err()
+            .ok()?;
+        Some((code, ast))
+    }
+
+    /// Resolve the file an import statement in the given file refers to.
+    fn resolve_import(&self, uri: &url::Url, path: &ImportPath) -> Option<url::Url> {
+        if !matches!(path, ImportPath::Kcl { .. }) {
+            // We can't navigate into foreign formats or the std lib.
+            return None;
+        }
+
+        let current = crate::lsp::util::uri_to_path(uri)?;
+        let project_directory = current.parent().map(|p| p.to_path_buf());
+        let ModulePath::Local(path) = ModulePath::from_import_path(path, &project_directory) else {
+            return None;
+        };
+
+        crate::lsp::util::path_to_uri(&path)
+    }
+
+    /// Get the lint settings from the `project.toml` of the project which contains a file.
+    async fn lint_settings(&self, uri: &url::Url) -> LintSettings {
+        let Some(current) = crate::lsp::util::uri_to_path(uri) else {
+            return Default::default();
+        };
+
+        for dir in current.ancestors().skip(1) {
+            let path = dir.join(PROJECT_SETTINGS_FILE_NAME);
+            let open = crate::lsp::util::path_to_uri(&path).and_then(|uri| self.code_map.get(uri.as_str()));
+            let toml = match open {
+                Some(contents) => String::from_utf8_lossy(&contents).into_owned(),
+                None => match self.fs.read_to_string(&path, SourceRange::default()).await {
+                    Ok(contents) => contents,
+                    Err(_) => continue,
+                },
+            };
+            return ProjectConfiguration::backwards_compatible_toml_parse(&toml)
+                .map(|config| config.settings.lint)
+                .unwrap_or_default();
+        }
+
+        Default::default()
+    }
+
+    /// Find where a name is defined in another module.
+    async fn find_exported_definition(
+        &self,
+        uri: &url::Url,
+        path: &ImportPath,
+        name: Option<&str>,
+    ) -> Option<Location> {
+        let target = self.resolve_import(uri, path)?;
+        let (code, ast) = self.get_code_and_ast(&target).await?;
+
+        // Importing the module itself goes to the top of the file.
+        let range = match name {
+            Some(name) => {
+                let table = SymbolTable::new(&ast);
+                let symbol = table.exported(name)?;
+                table.symbols[symbol].declaration.to_lsp_range(&code)
+            }
+            None => SourceRange::default().to_lsp_range(&code),
+        };
+
+        Some(Location { uri: target, range })
+    }
+
+    /// Find all the locations a name exported from the given module is used, in every other file
+    /// we know about.
+    async fn find_importing_references(&self, origin: &url::Url, name: &str, locations: &mut Vec<Location>) {
+        let uris = self
+            .code_map
+            .iter()
+            .map(|entry| entry.key().clone())
+            .filter(|key| key.ends_with(".kcl") && key != origin.as_str())
+            .collect::<Vec<_>>();
+
+        for uri in uris {
+            let Ok(uri) = url::Url::parse(&uri) else {
+                continue;
+            };
+            let Some((code, ast)) = self.get_code_and_ast(&uri).await else {
+                continue;
+            };
+
+            let table = SymbolTable::new(&ast);
+            for (index, symbol) in table.symbols.iter().enumerate() {
+                let SymbolKind::Import {
+                    path,
+                    original_name: Some(original_name),
+                } = &symbol.kind
+                else {
+                    continue;
+                };
+                if original_name != name || self.resolve_import(&uri, path).as_ref() != Some(origin) {
+                    continue;
+                }
+
+                locations.push(Location {
+                    uri: uri.clone(),
+                    range: symbol.declaration.to_lsp_range(&code),
+                });
+                locations.extend(table.references_to(index).map(|r| Location {
+                    uri: uri.clone(),
+                    range: r.range.to_lsp_range(&code),
+                }));
+            }
+
+            // Names brought in with `import *` don't have a declaration in this file.
+            if glob_imports(&ast).any(|path| self.resolve_import(&uri, path).as_ref() == Some(origin)) {
+                locations.extend(
+                    table
+                        .references
+                        .iter()
+                        .filter(|r| r.symbol.is_none() && r.name == name)
+                        .map(|r| Location {
+                            uri: uri.clone(),
+                            range: r.range.to_lsp_range(&code),
+                        }),
+                );
+            }
+        }
+    }
+
     pub async fn update_units(
         &self,
         params: custom_notifications::UpdateUnitsParams,
@@ -877,9 +1055,11 @@ impl LanguageServer for Backend {
                     ..Default::default()
                 })),
                 document_formatting_provider: Some(OneOf::Left(true)),
+                definition_provider: Some(OneOf::Left(true)),
                 folding_range_provider: Some(FoldingRangeProviderCapability::Simple(true)),
                 hover_provider: Some(HoverProviderCapability::Simple(true)),
                 inlay_hint_provider: Some(OneOf::Left(true)),
+                references_provider: Some(OneOf::Left(true)),
                 rename_provider: Some(OneOf::Left(true)),
                 semantic_tokens_provider: Some(SemanticTokensServerCapabilities::SemanticTokensRegistrationOptions(
                     SemanticTokensRegistrationOptions {
@@ -1245,10 +1425,32 @@ impl LanguageServer for Backend {
         }
     }
 
-    async fn inlay_hint(&self, _params: InlayHintParams) -> RpcResult<Option<Vec<InlayHint>>> {
-        // TODO: do this
+    async fn inlay_hint(&self, params: InlayHintParams) -> RpcResult<Option<Vec<InlayHint>>> {
+        let filename = params.text_document.uri.to_string();
+
+        let Some(current_code) = self.code_map.get(&filename) else {
+            return Ok(None);
+        };
+        let Ok(current_code) = std::str::from_utf8(&current_code) else {
+            return Ok(None);
+        };
+
+        let Some(ast) = self.ast_map.get(&filename) else {
+            return Ok(None);
+        };
+
+        let variables = self.variables_map.get(&filename);
+        let hints = inlay_hints::get_inlay_hints(
+            &ast,
+            &inlay_hints::HintContext {
+                code: current_code,
+                range: params.range,
+                variables: variables.as_deref(),
+                stdlib_signatures: &self.stdlib_signatures,
+            },
+        );
 
-        Ok(None)
+        Ok(Some(hints))
     }
 
     async fn semantic_tokens_full(&self, params: SemanticTokensParams) -> RpcResult<Option<SemanticTokensResult>> {
@@ -1347,6 +1549,112 @@ impl LanguageServer for Backend {
         }))
     }
 
+    async fn goto_definition(&self, params: GotoDefinitionParams) -> RpcResult<Option<GotoDefinitionResponse>> {
+        let uri = params.text_document_position_params.text_document.uri;
+
+        let Some((current_code, ast)) = self.get_code_and_ast(&uri).await else {
+            return Ok(None);
+        };
+
+        let pos = position_to_char_index(params.text_document_position_params.position, &current_code);
+        let table = SymbolTable::new(&ast);
+
+        let Some(symbol) = table.symbol_at(pos) else {
+            // This might be a name brought in by a glob import.
+            let Some(reference) = table.reference_at(pos) else {
+                return Ok(None);
+            };
+            for path in glob_imports(&ast) {
+                if let Some(location) = self.find_exported_definition(&uri, path, Some(&reference.name)).await {
+                    return Ok(Some(GotoDefinitionResponse::Scalar(location)));
+                }
+            }
+            return Ok(None);
+        };
+
+        let symbol = &table.symbols[symbol];
+        // Imported items jump to their definition in the other module, unless we're already on the
+        // import itself.
+        if let SymbolKind::Import { path, original_name } = &symbol.kind {
+            if let Some(location) = self
+                .find_exported_definition(&uri, path, original_name.as_deref())
+                .await
+            {
+                return Ok(Some(GotoDefinitionResponse::Scalar(location)));
+            }
+        }
+
+        Ok(Some(GotoDefinitionResponse::Scalar(Location {
+            uri,
+            range: symbol.declaration.to_lsp_range(&current_code),
+        })))
+    }
+
+    async fn references(&self, params: ReferenceParams) -> RpcResult<Option<Vec<Location>>> {
+        let uri = params.text_document_position.text_document.uri;
+        let include_declaration = params.context.include_declaration;
+
+        let Some((current_code, ast)) = self.get_code_and_ast(&uri).await else {
+            return Ok(None);
+        };
+
+        let pos = position_to_char_index(params.text_document_position.position, &current_code);
+        let table = SymbolTable::new(&ast);
+        let Some(index) = table.symbol_at(pos) else {
+            return Ok(None);
+        };
+        let symbol = &table.symbols[index];
+
+        // Work out which module actually defines the name, so we can find uses of it in every
+        // file which imports it.
+        let origin = match &symbol.kind {
+            SymbolKind::Import {
+                path,
+                original_name: Some(original_name),
+            } => self
+                .find_exported_definition(&uri, path, Some(original_name))
+                .await
+                .map(|location| (location.uri, original_name.clone())),
+            _ if symbol.exported => Some((uri.clone(), symbol.name.clone())),
+            _ => None,
+        };
+
+        let mut locations = vec![];
+        let Some((origin, name)) = origin else {
+            // Only used in this file.
+            if include_declaration {
+                locations.push(Location {
+                    uri: uri.clone(),
+                    range: symbol.declaration.to_lsp_range(&current_code),
+                });
+            }
+            locations.extend(table.references_to(index).map(|r| Location {
+                uri: uri.clone(),
+                range: r.range.to_lsp_range(&current_code),
+            }));
+            return Ok(Some(locations));
+        };
+
+        if let Some((origin_code, origin_ast)) = self.get_code_and_ast(&origin).await {
+            let origin_table = SymbolTable::new(&origin_ast);
+            if let Some(index) = origin_table.exported(&name) {
+                if include_declaration {
+                    locations.push(Location {
+                        uri: origin.clone(),
+                        range: origin_table.symbols[index].declaration.to_lsp_range(&origin_code),
+                    });
+                }
+                locations.extend(origin_table.references_to(index).map(|r| Location {
+                    uri: origin.clone(),
+                    range: r.range.to_lsp_range(&origin_code),
+                }));
+            }
+        }
+        self.find_importing_references(&origin, &name, &mut locations).await;
+
+        Ok(Some(locations))
+    }
+
     async fn folding_range(&self, params: FoldingRangeParams) -> RpcResult<Option<Vec<FoldingRange>>> {
         let filename = params.text_document.uri.to_string();
 
@@ -1401,6 +1709,11 @@ impl LanguageServer for Backend {
     }
 }
 
+/// Is this diagnostic from the linter, rather than from parsing or executing the code?
+fn is_lint(diagnostic: &Diagnostic) -> bool {
+    diagnostic.source.as_deref() == Some("lint")
+}
+
 /// Get completions from our stdlib.
 pub fn get_completions_from_stdlib(stdlib: &crate::std::StdLib) -> Result<HashMap<String, CompletionItem>> {
     let mut completions = HashMap::new();
@@ -1430,6 +1743,14 @@ pub fn get_signatures_from_stdlib(stdlib: &crate::std::StdLib) -> Result<HashMap
     Ok(signatures)
 }
 
+/// Get the paths of all the modules imported with `import *` in a program.
+fn glob_imports(ast: &crate::parsing::ast::types::Program) -> impl Iterator<Item = &ImportPath> {
+    ast.body.iter().filter_map(|item| match item {
+        BodyItem::ImportStatement(stmt) if matches!(stmt.selector, ImportSelector::Glob(_)) => Some(&stmt.path),
+        _ => None,
+    })
+}
+
 /// Convert a position to a character index from the start of the file.
 fn position_to_char_index(position: Position, code: &str) -> usize {
     // Get the character position from the start of the file.
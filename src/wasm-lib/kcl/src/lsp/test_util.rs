@@ -32,6 +32,7 @@ pub async fn kcl_lsp_server(execute: bool) -> Result<crate::lsp::kcl::Backend> {
         diagnostics_map: Default::default(),
         symbols_map: Default::default(),
         semantic_tokens_map: Default::default(),
+        variables_map: Default::default(),
         zoo_client,
         can_send_telemetry: true,
         executor_ctx: Arc::new(tokio::sync::RwLock::new(executor_ctx)),
@@ -55,8 +56,18 @@ pub async fn kcl_lsp_server(execute: bool) -> Result<crate::lsp::kcl::Backend> {
 
 // Create a fake copilot lsp server for testing.
 pub async fn copilot_lsp_server() -> Result<crate::lsp::copilot::Backend> {
-    // We don't actually need to authenticate to the backend for this test.
-    let zoo_client = kittycad::Client::new_from_env();
+    copilot_lsp_server_with_provider(None).await
+}
+
+// Create a fake copilot lsp server for testing, initialized with the given completion provider.
+pub async fn copilot_lsp_server_with_provider(
+    completion_provider: Option<crate::lsp::copilot::types::CompletionProviderConfig>,
+) -> Result<crate::lsp::copilot::Backend> {
+    // Only the Zoo provider uses the client, so the others don't need a token.
+    let zoo_client = match completion_provider {
+        Some(crate::lsp::copilot::types::CompletionProviderConfig::Zoo) => kittycad::Client::new_from_env(),
+        _ => kittycad::Client::new(""),
+    };
 
     // Create the backend.
     let (service, _) = tower_lsp::LspService::new(|client| crate::lsp::copilot::Backend {
@@ -64,6 +75,11 @@ pub async fn copilot_lsp_server() -> Result<crate::lsp::copilot::Backend> {
         fs: Arc::new(crate::fs::FileManager::new()),
         workspace_folders: Default::default(),
         code_map: Default::default(),
+        stdlib_fns: crate::lsp::copilot::prompt::get_std_fns_from_stdlib(&crate::std::StdLib::new()),
+        provider: Arc::new(RwLock::new(crate::lsp::copilot::provider::from_config(
+            &Default::default(),
+            &zoo_client,
+        ))),
         zoo_client,
         editor_info: Arc::new(RwLock::new(crate::lsp::copilot::types::CopilotEditorInfo::default())),
         cache: Arc::new(crate::lsp::copilot::cache::CopilotCache::new()),
@@ -75,7 +91,16 @@ pub async fn copilot_lsp_server() -> Result<crate::lsp::copilot::Backend> {
     let server = service.inner();
 
     server
-        .initialize(tower_lsp::lsp_types::InitializeParams::default())
+        .initialize(tower_lsp::lsp_types::InitializeParams {
+            initialization_options: completion_provider
+                .map(|completion_provider| {
+                    serde_json::to_value(crate::lsp::copilot::types::CopilotInitializationOptions {
+                        completion_provider: Some(completion_provider),
+                    })
+                })
+                .transpose()?,
+            ..Default::default()
+        })
         .await?;
 
     server.initialized(tower_lsp::lsp_types::InitializedParams {}).await;
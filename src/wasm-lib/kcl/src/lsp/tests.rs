@@ -7,7 +7,7 @@ use tower_lsp::{
 };
 
 use crate::{
-    lsp::test_util::{copilot_lsp_server, kcl_lsp_server},
+    lsp::test_util::{copilot_lsp_server, copilot_lsp_server_with_provider, kcl_lsp_server},
     parsing::ast::types::{Node, Program},
 };
 
@@ -60,7 +60,7 @@ async fn test_updating_kcl_lsp_files() {
         }
     );
 
-    assert_eq!(server.code_map.len(), 10);
+    assert_eq!(server.code_map.len(), 13);
 
     // Run open file.
     server
@@ -75,7 +75,7 @@ async fn test_updating_kcl_lsp_files() {
         .await;
 
     // Check the code map.
-    assert_eq!(server.code_map.len(), 11);
+    assert_eq!(server.code_map.len(), 14);
     assert_eq!(
         server.code_map.get("file:///test.kcl").unwrap().clone(),
         "test".as_bytes()
@@ -91,7 +91,7 @@ async fn test_updating_kcl_lsp_files() {
         .await;
 
     // Check the code map.
-    assert_eq!(server.code_map.len(), 11);
+    assert_eq!(server.code_map.len(), 14);
     assert_eq!(
         server.code_map.get("file:///test.kcl").unwrap().clone(),
         "test".as_bytes()
@@ -110,7 +110,7 @@ async fn test_updating_kcl_lsp_files() {
         .await;
 
     // Check the code map.
-    assert_eq!(server.code_map.len(), 12);
+    assert_eq!(server.code_map.len(), 15);
     assert_eq!(
         server.code_map.get("file:///test.kcl").unwrap().clone(),
         "test".as_bytes()
@@ -136,7 +136,7 @@ async fn test_updating_kcl_lsp_files() {
         .await;
 
     // Check the code map.
-    assert_eq!(server.code_map.len(), 12);
+    assert_eq!(server.code_map.len(), 15);
     assert_eq!(
         server.code_map.get("file:///test.kcl").unwrap().clone(),
         "test".as_bytes()
@@ -157,7 +157,7 @@ async fn test_updating_kcl_lsp_files() {
         .await;
 
     // Check the code map.
-    assert_eq!(server.code_map.len(), 12);
+    assert_eq!(server.code_map.len(), 15);
     assert_eq!(
         server.code_map.get("file:///test.kcl").unwrap().clone(),
         "test".as_bytes()
@@ -177,7 +177,7 @@ async fn test_updating_kcl_lsp_files() {
         .await;
 
     // Check the code map.
-    assert_eq!(server.code_map.len(), 13);
+    assert_eq!(server.code_map.len(), 16);
     assert_eq!(
         server.code_map.get("file:///test.kcl").unwrap().clone(),
         "test".as_bytes()
@@ -198,7 +198,7 @@ async fn test_updating_kcl_lsp_files() {
         .await;
 
     // Check the code map.
-    assert_eq!(server.code_map.len(), 12);
+    assert_eq!(server.code_map.len(), 15);
     assert_eq!(
         server.code_map.get("file:///test.kcl").unwrap().clone(),
         "test".as_bytes()
@@ -232,7 +232,7 @@ async fn test_updating_kcl_lsp_files() {
     );
 
     // Check the code map.
-    assert_eq!(server.code_map.len(), 12);
+    assert_eq!(server.code_map.len(), 15);
     assert_eq!(
         server.code_map.get("file:///test.kcl").unwrap().clone(),
         "test".as_bytes()
@@ -268,7 +268,7 @@ async fn test_updating_kcl_lsp_files() {
             name: "my-project2".to_string()
         }
     );
-    assert_eq!(server.code_map.len(), 10);
+    assert_eq!(server.code_map.len(), 13);
     // Just make sure that one of the current files read from disk is accurate.
     assert_eq!(
         server
@@ -313,7 +313,7 @@ async fn test_updating_copilot_lsp_files() {
         }
     );
 
-    assert_eq!(server.code_map.len(), 10);
+    assert_eq!(server.code_map.len(), 13);
 
     // Run open file.
     server
@@ -328,7 +328,7 @@ async fn test_updating_copilot_lsp_files() {
         .await;
 
     // Check the code map.
-    assert_eq!(server.code_map.len(), 11);
+    assert_eq!(server.code_map.len(), 14);
     assert_eq!(
         server.code_map.get("file:///test.kcl").unwrap().clone(),
         "test".as_bytes()
@@ -344,7 +344,7 @@ async fn test_updating_copilot_lsp_files() {
         .await;
 
     // Check the code map.
-    assert_eq!(server.code_map.len(), 11);
+    assert_eq!(server.code_map.len(), 14);
     assert_eq!(
         server.code_map.get("file:///test.kcl").unwrap().clone(),
         "test".as_bytes()
@@ -363,7 +363,7 @@ async fn test_updating_copilot_lsp_files() {
         .await;
 
     // Check the code map.
-    assert_eq!(server.code_map.len(), 12);
+    assert_eq!(server.code_map.len(), 15);
     assert_eq!(
         server.code_map.get("file:///test.kcl").unwrap().clone(),
         "test".as_bytes()
@@ -389,7 +389,7 @@ async fn test_updating_copilot_lsp_files() {
         .await;
 
     // Check the code map.
-    assert_eq!(server.code_map.len(), 12);
+    assert_eq!(server.code_map.len(), 15);
     assert_eq!(
         server.code_map.get("file:///test.kcl").unwrap().clone(),
         "test".as_bytes()
@@ -410,7 +410,7 @@ async fn test_updating_copilot_lsp_files() {
         .await;
 
     // Check the code map.
-    assert_eq!(server.code_map.len(), 12);
+    assert_eq!(server.code_map.len(), 15);
     assert_eq!(
         server.code_map.get("file:///test.kcl").unwrap().clone(),
         "test".as_bytes()
@@ -430,7 +430,7 @@ async fn test_updating_copilot_lsp_files() {
         .await;
 
     // Check the code map.
-    assert_eq!(server.code_map.len(), 13);
+    assert_eq!(server.code_map.len(), 16);
     assert_eq!(
         server.code_map.get("file:///test.kcl").unwrap().clone(),
         "test".as_bytes()
@@ -451,7 +451,7 @@ async fn test_updating_copilot_lsp_files() {
         .await;
 
     // Check the code map.
-    assert_eq!(server.code_map.len(), 12);
+    assert_eq!(server.code_map.len(), 15);
     assert_eq!(
         server.code_map.get("file:///test.kcl").unwrap().clone(),
         "test".as_bytes()
@@ -485,7 +485,7 @@ async fn test_updating_copilot_lsp_files() {
     );
 
     // Check the code map.
-    assert_eq!(server.code_map.len(), 12);
+    assert_eq!(server.code_map.len(), 15);
     assert_eq!(
         server.code_map.get("file:///test.kcl").unwrap().clone(),
         "test".as_bytes()
@@ -516,7 +516,7 @@ async fn test_updating_copilot_lsp_files() {
     );
 
     // Check the code map.
-    assert_eq!(server.code_map.len(), 12);
+    assert_eq!(server.code_map.len(), 15);
     assert_eq!(
         server.code_map.get("file:///test.kcl").unwrap().clone(),
         "test".as_bytes()
@@ -552,7 +552,7 @@ async fn test_updating_copilot_lsp_files() {
             name: "my-project2".to_string()
         }
     );
-    assert_eq!(server.code_map.len(), 10);
+    assert_eq!(server.code_map.len(), 13);
 }
 
 #[tokio::test(flavor = "multi_thread")]
@@ -588,7 +588,7 @@ async fn test_kcl_lsp_create_zip() {
         }
     );
 
-    assert_eq!(server.code_map.len(), 10);
+    assert_eq!(server.code_map.len(), 13);
 
     // Run open file.
     server
@@ -603,7 +603,7 @@ async fn test_kcl_lsp_create_zip() {
         .await;
 
     // Check the code map.
-    assert_eq!(server.code_map.len(), 11);
+    assert_eq!(server.code_map.len(), 14);
     assert_eq!(
         server.code_map.get("file:///test.kcl").unwrap().clone(),
         "test".as_bytes()
@@ -627,7 +627,7 @@ async fn test_kcl_lsp_create_zip() {
         files.insert(file.name().to_string(), file.size());
     }
 
-    assert_eq!(files.len(), 11);
+    assert_eq!(files.len(), 14);
     let util_path = format!("{}/util.rs", string_path).replace("file://", "");
     assert!(files.contains_key(&util_path));
     assert_eq!(files.get("/test.kcl"), Some(&4));
@@ -1717,6 +1717,460 @@ async fn test_kcl_lsp_rename() {
     );
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_kcl_lsp_goto_definition() {
+    let server = kcl_lsp_server(false).await.unwrap();
+
+    // Send open file.
+    server
+        .did_open(tower_lsp::lsp_types::DidOpenTextDocumentParams {
+            text_document: tower_lsp::lsp_types::TextDocumentItem {
+                uri: "file:///test.kcl".try_into().unwrap(),
+                language_id: "kcl".to_string(),
+                version: 1,
+                text: r#"width = 10
+part001 = startSketchOn('XY')
+  |> startProfileAt([0, 0], %)
+  |> line(end = [width, 0], tag = $seg01)
+  |> line(end = [0, segLen(seg01)])"#
+                    .to_string(),
+            },
+        })
+        .await;
+
+    let goto = |line, character| {
+        server.goto_definition(tower_lsp::lsp_types::GotoDefinitionParams {
+            text_document_position_params: tower_lsp::lsp_types::TextDocumentPositionParams {
+                text_document: tower_lsp::lsp_types::TextDocumentIdentifier {
+                    uri: "file:///test.kcl".try_into().unwrap(),
+                },
+                position: tower_lsp::lsp_types::Position { line, character },
+            },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+    };
+
+    // Go to the definition of `width`.
+    let definition = goto(3, 18).await.unwrap().unwrap();
+    assert_eq!(
+        definition,
+        tower_lsp::lsp_types::GotoDefinitionResponse::Scalar(tower_lsp::lsp_types::Location {
+            uri: "file:///test.kcl".try_into().unwrap(),
+            range: tower_lsp::lsp_types::Range {
+                start: tower_lsp::lsp_types::Position { line: 0, character: 0 },
+                end: tower_lsp::lsp_types::Position { line: 0, character: 5 }
+            },
+        })
+    );
+
+    // Go to the definition of the tag `seg01`.
+    let definition = goto(4, 28).await.unwrap().unwrap();
+    assert_eq!(
+        definition,
+        tower_lsp::lsp_types::GotoDefinitionResponse::Scalar(tower_lsp::lsp_types::Location {
+            uri: "file:///test.kcl".try_into().unwrap(),
+            range: tower_lsp::lsp_types::Range {
+                start: tower_lsp::lsp_types::Position { line: 3, character: 34 },
+                end: tower_lsp::lsp_types::Position { line: 3, character: 40 }
+            },
+        })
+    );
+
+    // Std lib functions have no definition in the file.
+    assert_eq!(goto(4, 7).await.unwrap(), None);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_kcl_lsp_goto_definition_across_modules() {
+    let server = kcl_lsp_server(false).await.unwrap();
+
+    // Send open files.
+    server
+        .did_open(tower_lsp::lsp_types::DidOpenTextDocumentParams {
+            text_document: tower_lsp::lsp_types::TextDocumentItem {
+                uri: "file:///project/bolt.kcl".try_into().unwrap(),
+                language_id: "kcl".to_string(),
+                version: 1,
+                text: r#"export fn bolt(diameter) {
+  return diameter * 2
+}"#
+                .to_string(),
+            },
+        })
+        .await;
+    server
+        .did_open(tower_lsp::lsp_types::DidOpenTextDocumentParams {
+            text_document: tower_lsp::lsp_types::TextDocumentItem {
+                uri: "file:///project/main.kcl".try_into().unwrap(),
+                language_id: "kcl".to_string(),
+                version: 1,
+                text: r#"import bolt as b from "bolt.kcl"
+x = b(5)"#
+                    .to_string(),
+            },
+        })
+        .await;
+
+    // Send go to definition request on the use of `b`.
+    let definition = server
+        .goto_definition(tower_lsp::lsp_types::GotoDefinitionParams {
+            text_document_position_params: tower_lsp::lsp_types::TextDocumentPositionParams {
+                text_document: tower_lsp::lsp_types::TextDocumentIdentifier {
+                    uri: "file:///project/main.kcl".try_into().unwrap(),
+                },
+                position: tower_lsp::lsp_types::Position { line: 1, character: 4 },
+            },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(
+        definition,
+        tower_lsp::lsp_types::GotoDefinitionResponse::Scalar(tower_lsp::lsp_types::Location {
+            uri: "file:///project/bolt.kcl".try_into().unwrap(),
+            range: tower_lsp::lsp_types::Range {
+                start: tower_lsp::lsp_types::Position { line: 0, character: 10 },
+                end: tower_lsp::lsp_types::Position { line: 0, character: 14 }
+            },
+        })
+    );
+
+    // Find all the references to `bolt`, from its definition.
+    let references = server
+        .references(tower_lsp::lsp_types::ReferenceParams {
+            text_document_position: tower_lsp::lsp_types::TextDocumentPositionParams {
+                text_document: tower_lsp::lsp_types::TextDocumentIdentifier {
+                    uri: "file:///project/bolt.kcl".try_into().unwrap(),
+                },
+                position: tower_lsp::lsp_types::Position { line: 0, character: 11 },
+            },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+            context: tower_lsp::lsp_types::ReferenceContext {
+                include_declaration: true,
+            },
+        })
+        .await
+        .unwrap()
+        .unwrap();
+
+    let main: tower_lsp::lsp_types::Url = "file:///project/main.kcl".try_into().unwrap();
+    assert_eq!(
+        references
+            .iter()
+            .map(|l| (l.uri.path(), l.range.start.line, l.range.start.character))
+            .collect::<Vec<_>>(),
+        vec![
+            ("/project/bolt.kcl", 0, 10),
+            ("/project/main.kcl", 0, 15),
+            ("/project/main.kcl", 1, 4),
+        ]
+    );
+    assert_eq!(references[2].uri, main);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_kcl_lsp_goto_definition_across_modules_escaped_path() {
+    let server = kcl_lsp_server(false).await.unwrap();
+
+    // The imported file is only on disk, in a directory whose name has to be escaped in a URI.
+    let tmp = tempfile::Builder::new().prefix("my project ").tempdir().unwrap();
+    let dir = tmp.path();
+    std::fs::write(
+        dir.join("bolt.kcl"),
+        "export fn bolt(diameter) {\n  return diameter * 2\n}",
+    )
+    .unwrap();
+    let bolt = tower_lsp::lsp_types::Url::from_file_path(dir.join("bolt.kcl")).unwrap();
+    let main = tower_lsp::lsp_types::Url::from_file_path(dir.join("main.kcl")).unwrap();
+    assert!(main.as_str().contains("%20"));
+
+    // Send open file.
+    server
+        .did_open(tower_lsp::lsp_types::DidOpenTextDocumentParams {
+            text_document: tower_lsp::lsp_types::TextDocumentItem {
+                uri: main.clone(),
+                language_id: "kcl".to_string(),
+                version: 1,
+                text: "import bolt from \"bolt.kcl\"\nx = bolt(5)".to_string(),
+            },
+        })
+        .await;
+
+    // Send go to definition request on the use of `bolt`.
+    let definition = server
+        .goto_definition(tower_lsp::lsp_types::GotoDefinitionParams {
+            text_document_position_params: tower_lsp::lsp_types::TextDocumentPositionParams {
+                text_document: tower_lsp::lsp_types::TextDocumentIdentifier { uri: main },
+                position: tower_lsp::lsp_types::Position { line: 1, character: 5 },
+            },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(
+        definition,
+        tower_lsp::lsp_types::GotoDefinitionResponse::Scalar(tower_lsp::lsp_types::Location {
+            uri: bolt,
+            range: tower_lsp::lsp_types::Range {
+                start: tower_lsp::lsp_types::Position { line: 0, character: 10 },
+                end: tower_lsp::lsp_types::Position { line: 0, character: 14 }
+            },
+        })
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_kcl_lsp_references() {
+    let server = kcl_lsp_server(false).await.unwrap();
+
+    // Send open file.
+    server
+        .did_open(tower_lsp::lsp_types::DidOpenTextDocumentParams {
+            text_document: tower_lsp::lsp_types::TextDocumentItem {
+                uri: "file:///test.kcl".try_into().unwrap(),
+                language_id: "kcl".to_string(),
+                version: 1,
+                text: r#"thing = 1
+fn double(thing) {
+  return thing * 2
+}
+other = double(thing) + thing"#
+                    .to_string(),
+            },
+        })
+        .await;
+
+    let references = |include_declaration| {
+        server.references(tower_lsp::lsp_types::ReferenceParams {
+            text_document_position: tower_lsp::lsp_types::TextDocumentPositionParams {
+                text_document: tower_lsp::lsp_types::TextDocumentIdentifier {
+                    uri: "file:///test.kcl".try_into().unwrap(),
+                },
+                position: tower_lsp::lsp_types::Position { line: 0, character: 2 },
+            },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+            context: tower_lsp::lsp_types::ReferenceContext { include_declaration },
+        })
+    };
+
+    // The parameter of the same name isn't a reference to the outer `thing`.
+    let found = references(true).await.unwrap().unwrap();
+    assert_eq!(
+        found
+            .iter()
+            .map(|l| (l.range.start.line, l.range.start.character))
+            .collect::<Vec<_>>(),
+        vec![(0, 0), (4, 15), (4, 24)]
+    );
+
+    let found = references(false).await.unwrap().unwrap();
+    assert_eq!(found.len(), 2);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_kcl_lsp_inlay_hints_parameter_names() {
+    let server = kcl_lsp_server(false).await.unwrap();
+
+    // Send open file.
+    server
+        .did_open(tower_lsp::lsp_types::DidOpenTextDocumentParams {
+            text_document: tower_lsp::lsp_types::TextDocumentItem {
+                uri: "file:///test.kcl".try_into().unwrap(),
+                language_id: "kcl".to_string(),
+                version: 1,
+                text: r#"fn box(width, height) {
+  return width * height
+}
+height = 2
+area = box(1, height)
+other = pow(2, 3)"#
+                    .to_string(),
+            },
+        })
+        .await;
+
+    // Send inlay hints request.
+    let hints = server
+        .inlay_hint(tower_lsp::lsp_types::InlayHintParams {
+            text_document: tower_lsp::lsp_types::TextDocumentIdentifier {
+                uri: "file:///test.kcl".try_into().unwrap(),
+            },
+            range: tower_lsp::lsp_types::Range {
+                start: tower_lsp::lsp_types::Position { line: 4, character: 0 },
+                end: tower_lsp::lsp_types::Position { line: 6, character: 0 },
+            },
+            work_done_progress_params: Default::default(),
+        })
+        .await
+        .unwrap()
+        .unwrap();
+
+    // `height` is already called `height`, so it doesn't get a hint.
+    let parameters = hints
+        .iter()
+        .filter(|hint| hint.kind == Some(tower_lsp::lsp_types::InlayHintKind::PARAMETER))
+        .map(|hint| match &hint.label {
+            tower_lsp::lsp_types::InlayHintLabel::String(label) => {
+                (hint.position.line, hint.position.character, label.clone())
+            }
+            _ => panic!("expected a string label"),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        parameters,
+        vec![
+            (4, 11, "width:".to_string()),
+            (5, 12, "num:".to_string()),
+            (5, 15, "pow:".to_string()),
+        ]
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_kcl_lsp_inlay_hints_types() {
+    let server = kcl_lsp_server(false).await.unwrap();
+
+    // Send open file.
+    server
+        .did_open(tower_lsp::lsp_types::DidOpenTextDocumentParams {
+            text_document: tower_lsp::lsp_types::TextDocumentItem {
+                uri: "file:///test.kcl".try_into().unwrap(),
+                language_id: "kcl".to_string(),
+                version: 1,
+                text: r#"width = 10mm
+half = width / 2
+angle = -45deg
+plain = 3
+mixed = width + angle
+name = "bolt""#
+                    .to_string(),
+            },
+        })
+        .await;
+
+    // Send inlay hints request.
+    let hints = server
+        .inlay_hint(tower_lsp::lsp_types::InlayHintParams {
+            text_document: tower_lsp::lsp_types::TextDocumentIdentifier {
+                uri: "file:///test.kcl".try_into().unwrap(),
+            },
+            range: tower_lsp::lsp_types::Range {
+                start: tower_lsp::lsp_types::Position { line: 0, character: 0 },
+                end: tower_lsp::lsp_types::Position { line: 6, character: 0 },
+            },
+            work_done_progress_params: Default::default(),
+        })
+        .await
+        .unwrap()
+        .unwrap();
+
+    // Without executing we can't know any values, and mixing units loses track of the type.
+    let types = hints
+        .iter()
+        .map(|hint| {
+            assert_eq!(hint.kind, Some(tower_lsp::lsp_types::InlayHintKind::TYPE));
+            match &hint.label {
+                tower_lsp::lsp_types::InlayHintLabel::String(label) => {
+                    (hint.position.line, hint.position.character, label.clone())
+                }
+                _ => panic!("expected a string label"),
+            }
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        types,
+        vec![
+            (0, 5, ": number(mm)".to_string()),
+            (1, 4, ": number(mm)".to_string()),
+            (2, 5, ": number(deg)".to_string()),
+            (3, 5, ": number(mm)".to_string()),
+        ]
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_kcl_lsp_inlay_hints_shadowed_names() {
+    let server = kcl_lsp_server(false).await.unwrap();
+
+    // Send open file.
+    server
+        .did_open(tower_lsp::lsp_types::DidOpenTextDocumentParams {
+            text_document: tower_lsp::lsp_types::TextDocumentItem {
+                uri: "file:///test.kcl".try_into().unwrap(),
+                language_id: "kcl".to_string(),
+                version: 1,
+                text: r#"size = 10mm
+fn scale(x) {
+  size = x * 2
+  half = size / 2
+  return half
+}
+total = size"#
+                    .to_string(),
+            },
+        })
+        .await;
+
+    // Pretend the file was executed, since we can't without an engine.
+    let number = |value| crate::execution::KclValue::Number {
+        value,
+        ty: crate::execution::kcl_value::NumericType::Known(crate::execution::kcl_value::UnitType::Length(
+            crate::execution::UnitLen::Mm,
+        )),
+        meta: vec![],
+    };
+    server.variables_map.insert(
+        "file:///test.kcl".to_string(),
+        [("size".to_string(), number(10.0)), ("total".to_string(), number(10.0))]
+            .into_iter()
+            .collect(),
+    );
+
+    // Send inlay hints request.
+    let hints = server
+        .inlay_hint(tower_lsp::lsp_types::InlayHintParams {
+            text_document: tower_lsp::lsp_types::TextDocumentIdentifier {
+                uri: "file:///test.kcl".try_into().unwrap(),
+            },
+            range: tower_lsp::lsp_types::Range {
+                start: tower_lsp::lsp_types::Position { line: 0, character: 0 },
+                end: tower_lsp::lsp_types::Position { line: 7, character: 0 },
+            },
+            work_done_progress_params: Default::default(),
+        })
+        .await
+        .unwrap()
+        .unwrap();
+
+    // The `size` in `scale` isn't the top level one, and nothing is known about `x`.
+    let labels = hints
+        .iter()
+        .map(|hint| match &hint.label {
+            tower_lsp::lsp_types::InlayHintLabel::String(label) => {
+                (hint.position.line, hint.position.character, label.clone())
+            }
+            _ => panic!("expected a string label"),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        labels,
+        vec![
+            (0, 4, ": number(mm)".to_string()),
+            (6, 5, ": number(mm)".to_string()),
+            (6, 12, "= 10mm".to_string()),
+        ]
+    );
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_kcl_lsp_diagnostic_no_errors() {
     let server = kcl_lsp_server(false).await.unwrap();
@@ -2001,6 +2455,268 @@ async fn test_copilot_lsp_completions() {
         .await;
 }
 
+fn copilot_completion_params() -> crate::lsp::copilot::types::CopilotLspCompletionParams {
+    crate::lsp::copilot::types::CopilotLspCompletionParams {
+        doc: crate::lsp::copilot::types::CopilotDocParams {
+            indent_size: 4,
+            insert_spaces: true,
+            language_id: "kcl".to_string(),
+            path: "file:///test.copilot".to_string(),
+            position: crate::lsp::copilot::types::CopilotPosition { line: 2, character: 2 },
+            relative_path: "test.copilot".to_string(),
+            source: r#"bracket = startSketchOn('XY')
+  |> startProfileAt([0, 0], %)
+  
+"#
+            .to_string(),
+            tab_size: 4,
+            uri: "file:///test.copilot".into(),
+        },
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_copilot_lsp_completions_stub_provider() {
+    let server = copilot_lsp_server_with_provider(Some(crate::lsp::copilot::types::CompletionProviderConfig::Stub {
+        completions: vec!["|> line(end = [10, 0])".to_string()],
+    }))
+    .await
+    .unwrap();
+
+    let completions = server
+        .get_completions_cycling(copilot_completion_params())
+        .await
+        .unwrap();
+    assert_eq!(completions.completions.len(), 1);
+    assert_eq!(completions.completions[0].text, "  |> line(end = [10, 0])");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_copilot_lsp_completions_no_provider() {
+    // Without initialization options, there are no completions, so no code is sent anywhere.
+    let server = copilot_lsp_server_with_provider(None).await.unwrap();
+
+    let completions = server
+        .get_completions_cycling(copilot_completion_params())
+        .await
+        .unwrap();
+    assert!(completions.completions.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_copilot_lsp_completions_openai_provider() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    // A server which answers one completion request the way an OpenAI-compatible one would.
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/v1/", listener.local_addr().unwrap());
+    let model = tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut request = Vec::new();
+        let mut buf = [0; 4096];
+        // Read until the whole body has arrived.
+        let body = loop {
+            let n = socket.read(&mut buf).await.unwrap();
+            request.extend_from_slice(&buf[..n]);
+            let text = String::from_utf8_lossy(&request).to_string();
+            if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                let length: usize = head
+                    .lines()
+                    .find_map(|line| {
+                        line.to_lowercase()
+                            .strip_prefix("content-length: ")
+                            .map(|l| l.to_owned())
+                    })
+                    .unwrap()
+                    .parse()
+                    .unwrap();
+                if body.len() >= length {
+                    break (head.to_owned(), body.to_owned());
+                }
+            }
+        };
+        let response = r#"{"choices":[{"text":"|> line(end = [0, 10])","index":0,"finish_reason":"stop"}]}"#;
+        socket
+            .write_all(
+                format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{response}",
+                    response.len()
+                )
+                .as_bytes(),
+            )
+            .await
+            .unwrap();
+        body
+    });
+
+    let server = copilot_lsp_server_with_provider(Some(crate::lsp::copilot::types::CompletionProviderConfig::OpenAi {
+        url,
+        model: "kcl-coder".to_string(),
+        api_key: Some("secret".to_string()),
+    }))
+    .await
+    .unwrap();
+
+    let completions = server
+        .get_completions_cycling(copilot_completion_params())
+        .await
+        .unwrap();
+    assert_eq!(completions.completions.len(), 1);
+    assert_eq!(completions.completions[0].text, "  |> line(end = [0, 10])");
+
+    let (head, body) = model.await.unwrap();
+    assert!(head.starts_with("POST /v1/completions "), "{head}");
+    assert!(head.to_lowercase().contains("authorization: bearer secret"), "{head}");
+    let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(body["model"], "kcl-coder");
+    let prompt = body["prompt"].as_str().unwrap();
+    assert!(
+        prompt.starts_with("// Standard library functions:\n// startSketchOn("),
+        "{prompt}"
+    );
+    assert!(prompt.ends_with("startProfileAt([0, 0], %)\n  "), "{prompt}");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_copilot_lsp_completions_which_dont_parse_are_dropped() {
+    let server = copilot_lsp_server_with_provider(Some(crate::lsp::copilot::types::CompletionProviderConfig::Stub {
+        completions: vec![
+            "|> line(end = [10, 0]".to_string(),
+            "|> line(end = [10, 0])".to_string(),
+            "|> line(end = [10, 0])".to_string(),
+            "  ".to_string(),
+        ],
+    }))
+    .await
+    .unwrap();
+
+    let completions = server
+        .get_completions_cycling(copilot_completion_params())
+        .await
+        .unwrap();
+    assert_eq!(completions.completions.len(), 1);
+    assert_eq!(completions.completions[0].text, "  |> line(end = [10, 0])");
+}
+
+#[test]
+fn test_copilot_prompt_context() {
+    let prefix = r#"fn square(@profile, size) {
+  inner = startSketchOn('XY')
+    |> startProfileAt([0, 0], %)
+    |> line(end = [size, 0], tag = $innerEdge)
+  return profile
+}
+
+base = startSketchOn('XY')
+  |> startProfileAt([0, 0], %)
+  |> line(end = [10, 0], tag = $bottom)
+  |> "#;
+    let suffix = r#"
+  |> close()
+
+fn later() {
+  return 1
+}
+"#;
+    let context = crate::lsp::copilot::prompt::PromptContext::new(prefix, suffix, &stdlib_fns());
+
+    assert_eq!(context.user_fns, vec!["fn square(@profile, size)".to_string()]);
+    // Tags in other functions' bodies aren't in scope.
+    assert_eq!(context.tags, vec!["bottom".to_string()]);
+    assert!(
+        context.std_fns.iter().any(|f| f.starts_with("startProfileAt(")),
+        "{:?}",
+        context.std_fns
+    );
+    assert!(
+        context.std_fns.iter().any(|f| f.starts_with("close(")),
+        "{:?}",
+        context.std_fns
+    );
+    assert!(!context.std_fns.iter().any(|f| f.starts_with("extrude(")));
+
+    let prompt = context.prompt(prefix);
+    assert!(
+        prompt.starts_with("// Functions in scope:\n// fn square(@profile, size)\n// Standard library functions:\n")
+    );
+    assert!(prompt.ends_with(&format!("// Tags in scope: bottom\n{prefix}")));
+}
+
+#[test]
+fn test_copilot_prompt_context_inside_function() {
+    let prefix = r#"fn square(size) {
+  sg = startSketchOn('XY')
+    |> startProfileAt([0, 0], %)
+    |> line(end = [size, 0], tag = $inner)
+    |> "#;
+    let suffix = "\n  return sg\n}\n";
+    let context = crate::lsp::copilot::prompt::PromptContext::new(prefix, suffix, &stdlib_fns());
+
+    assert_eq!(context.tags, vec!["inner".to_string()]);
+    // The function isn't declared until after the cursor.
+    assert!(context.user_fns.is_empty());
+}
+
+#[test]
+fn test_copilot_prompt_context_matches_word_being_typed() {
+    let context = crate::lsp::copilot::prompt::PromptContext::new("x = ext", "", &stdlib_fns());
+    assert!(
+        context.std_fns.iter().any(|f| f.starts_with("extrude(")),
+        "{:?}",
+        context.std_fns
+    );
+
+    // Without any code there's nothing to say.
+    let context = crate::lsp::copilot::prompt::PromptContext::new("", "", &stdlib_fns());
+    assert_eq!(context, Default::default());
+    assert_eq!(context.prompt(""), "");
+}
+
+#[test]
+fn test_copilot_rank_completions() {
+    let prefix = "x = ";
+    let context = crate::lsp::copilot::prompt::PromptContext::new(prefix, "\n", &stdlib_fns());
+    let completions = context.rank_completions(
+        vec![
+            "[1, 2".to_string(),
+            "1 + 2".to_string(),
+            "sqrt(4)".to_string(),
+            "1 + 2".to_string(),
+        ],
+        prefix,
+        "\n",
+    );
+    assert_eq!(completions, vec!["1 + 2".to_string(), "sqrt(4)".to_string()]);
+}
+
+#[test]
+fn test_copilot_rank_completions_with_errors_elsewhere() {
+    let prefix = "x = ";
+    let suffix = "\ny = [1, 2\n";
+    let context = crate::lsp::copilot::prompt::PromptContext::new(prefix, suffix, &stdlib_fns());
+    let completions = context.rank_completions(vec!["[1, 2".to_string(), "1 + 2".to_string()], prefix, suffix);
+    // The file doesn't parse either way, but only the first completion makes it worse.
+    assert_eq!(completions, vec!["1 + 2".to_string()]);
+}
+
+fn stdlib_fns() -> indexmap::IndexMap<String, crate::docs::StdLibFnData> {
+    crate::lsp::copilot::prompt::get_std_fns_from_stdlib(&crate::std::StdLib::new())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_copilot_lsp_invalid_initialization_options() {
+    let server = copilot_lsp_server_with_provider(None).await.unwrap();
+
+    let err = server
+        .initialize(tower_lsp::lsp_types::InitializeParams {
+            initialization_options: Some(serde_json::json!({ "completionProvider": { "type": "nope" } })),
+            ..Default::default()
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(err.code, tower_lsp::jsonrpc::ErrorCode::InvalidParams);
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_copilot_on_save() {
     let server = copilot_lsp_server().await.unwrap();
@@ -1,5 +1,7 @@
 //! Utility functions for working with ropes and positions.
 
+use std::path::{Path, PathBuf};
+
 use ropey::Rope;
 use tower_lsp::lsp_types::{Diagnostic, Position};
 
@@ -32,6 +34,37 @@ pub fn get_line_before(pos: Position, rope: &Rope) -> Option<String> {
     Some(rope.slice(line_start..offset).to_string())
 }
 
+/// The path of the file a `file://` URI refers to.
+pub fn uri_to_path(uri: &url::Url) -> Option<PathBuf> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        uri.to_file_path().ok()
+    }
+    // `Url::to_file_path` doesn't exist on wasm, where the paths are always unix-style.
+    #[cfg(target_arch = "wasm32")]
+    {
+        if uri.scheme() != "file" {
+            return None;
+        }
+        Some(PathBuf::from(urlencoding::decode(uri.path()).ok()?.into_owned()))
+    }
+}
+
+/// The `file://` URI of a file, given its absolute path.
+pub fn path_to_uri(path: &Path) -> Option<url::Url> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        url::Url::from_file_path(path).ok()
+    }
+    #[cfg(target_arch = "wasm32")]
+    {
+        if !path.has_root() {
+            return None;
+        }
+        url::Url::parse(&format!("file://{}", path.display())).ok()
+    }
+}
+
 /// Convert an object into a [lsp_types::Diagnostic] given the
 /// [TextDocumentItem]'s `.text` field.
 pub trait IntoDiagnostic {
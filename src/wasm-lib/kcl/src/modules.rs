@@ -1,14 +1,19 @@
 use std::{fmt, path::PathBuf};
 
 use anyhow::Result;
+use indexmap::{IndexMap, IndexSet};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest as _, Sha256};
 
 use crate::{
     errors::{KclError, KclErrorDetails},
     execution::{EnvironmentRef, PreImportedGeometry},
     fs::{FileManager, FileSystem},
-    parsing::ast::types::{ImportPath, Node, Program},
+    parsing::ast::{
+        digest::Digest,
+        types::{BodyItem, ImportPath, Node, Program},
+    },
     source_range::SourceRange,
 };
 
@@ -77,6 +82,11 @@ pub(crate) fn read_std(_mod_name: &str) -> Option<&'static str> {
     None
 }
 
+/// A digest of a module's source code.
+pub(crate) fn source_digest(source: &str) -> Digest {
+    Sha256::digest(source.as_bytes()).into()
+}
+
 /// Info about a module.
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct ModuleInfo {
@@ -85,6 +95,10 @@ pub struct ModuleInfo {
     /// Absolute path of the module's source file.
     pub(crate) path: ModulePath,
     pub(crate) repr: ModuleRepr,
+    /// A digest of the source the module was parsed from, so that it's only parsed again if the
+    /// source changes. `None` for modules which aren't parsed from KCL source.
+    #[serde(default)]
+    pub(crate) source_digest: Option<Digest>,
 }
 
 impl ModuleInfo {
@@ -110,6 +124,55 @@ pub enum ModuleRepr {
     Dummy,
 }
 
+/// Which modules import which, for finding the modules affected when one changes.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ModuleGraph {
+    /// Map from a module to the modules which import it.
+    importers: IndexMap<ModuleId, IndexSet<ModuleId>>,
+}
+
+impl ModuleGraph {
+    /// Build the graph from the imports of the loaded KCL modules. The root module is left out,
+    /// since nothing can import it.
+    pub(crate) fn new(
+        module_infos: &IndexMap<ModuleId, ModuleInfo>,
+        path_to_source_id: &IndexMap<ModulePath, ModuleId>,
+        project_directory: &Option<PathBuf>,
+    ) -> Self {
+        let mut graph = ModuleGraph::default();
+        let programs = module_infos.values().filter_map(|info| match &info.repr {
+            ModuleRepr::Kcl(program, _) => Some((info.id, program)),
+            ModuleRepr::Root | ModuleRepr::Foreign(_) | ModuleRepr::Dummy => None,
+        });
+        for (importer, program) in programs {
+            for item in &program.body {
+                let BodyItem::ImportStatement(import) = item else {
+                    continue;
+                };
+                let path = ModulePath::from_import_path(&import.path, project_directory);
+                if let Some(id) = path_to_source_id.get(&path) {
+                    graph.importers.entry(*id).or_default().insert(importer);
+                }
+            }
+        }
+        graph
+    }
+
+    /// The given modules and every module which imports them, directly or indirectly.
+    pub(crate) fn dependents(&self, modules: impl IntoIterator<Item = ModuleId>) -> IndexSet<ModuleId> {
+        let mut result = IndexSet::new();
+        let mut stack: Vec<_> = modules.into_iter().collect();
+        while let Some(id) = stack.pop() {
+            if result.insert(id) {
+                if let Some(importers) = self.importers.get(&id) {
+                    stack.extend(importers.iter().copied());
+                }
+            }
+        }
+        result
+    }
+}
+
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize, Hash)]
 pub enum ModulePath {
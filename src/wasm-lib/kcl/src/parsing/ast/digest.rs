@@ -3,10 +3,10 @@ use sha2::{Digest as DigestTrait, Sha256};
 use super::types::{DefaultParamVal, ItemVisibility, LabelledExpression, LiteralValue, VariableKind};
 use crate::parsing::ast::types::{
     Annotation, ArrayExpression, ArrayRangeExpression, BinaryExpression, BinaryPart, BodyItem, CallExpression,
-    CallExpressionKw, ElseIf, Expr, ExpressionStatement, FnArgType, FunctionExpression, Identifier, IfExpression,
-    ImportItem, ImportSelector, ImportStatement, KclNone, Literal, LiteralIdentifier, MemberExpression, MemberObject,
-    ObjectExpression, ObjectProperty, Parameter, PipeExpression, PipeSubstitution, Program, ReturnStatement,
-    TagDeclarator, UnaryExpression, VariableDeclaration, VariableDeclarator,
+    CallExpressionKw, ElseIf, Expr, ExpressionStatement, FnArgType, ForExpression, FunctionExpression, Identifier,
+    IfExpression, ImportItem, ImportSelector, ImportStatement, KclNone, Literal, LiteralIdentifier, MemberExpression,
+    MemberObject, ObjectExpression, ObjectProperty, Parameter, PipeExpression, PipeSubstitution, Program,
+    ReturnStatement, TagDeclarator, TypeDeclaration, UnaryExpression, VariableDeclaration, VariableDeclarator,
 };
 
 /// Position-independent digest of the AST node.
@@ -113,6 +113,7 @@ impl BodyItem {
             BodyItem::ImportStatement(s) => s.compute_digest(),
             BodyItem::ExpressionStatement(es) => es.compute_digest(),
             BodyItem::VariableDeclaration(vs) => vs.compute_digest(),
+            BodyItem::TypeDeclaration(ts) => ts.compute_digest(),
             BodyItem::ReturnStatement(rs) => rs.compute_digest(),
         });
 
@@ -141,6 +142,7 @@ impl Expr {
             Expr::MemberExpression(me) => me.compute_digest(),
             Expr::UnaryExpression(ue) => ue.compute_digest(),
             Expr::IfExpression(e) => e.compute_digest(),
+            Expr::ForExpression(e) => e.compute_digest(),
             Expr::LabelledExpression(e) => e.compute_digest(),
             Expr::None(_) => {
                 let mut hasher = Sha256::new();
@@ -203,6 +205,10 @@ impl FnArgType {
                     hasher.update(prop.compute_digest());
                 }
             }
+            FnArgType::Named { name } => {
+                hasher.update(b"FnArgType::Named");
+                hasher.update(name.compute_digest());
+            }
         }
 
         hasher.finalize().into()
@@ -229,6 +235,16 @@ impl Parameter {
     });
 }
 
+impl TypeDeclaration {
+    compute_digest!(|slf, hasher| {
+        hasher.update(slf.name.compute_digest());
+        hasher.update(slf.fields.len().to_ne_bytes());
+        for field in slf.fields.iter_mut() {
+            hasher.update(field.compute_digest());
+        }
+    });
+}
+
 impl KclNone {
     compute_digest!(|slf, hasher| {
         hasher.update(b"KclNone");
@@ -461,6 +477,14 @@ impl ElseIf {
     });
 }
 
+impl ForExpression {
+    compute_digest!(|slf, hasher| {
+        hasher.update(slf.var.compute_digest());
+        hasher.update(slf.iterable.compute_digest());
+        hasher.update(slf.body.compute_digest());
+    });
+}
+
 #[cfg(test)]
 mod test {
     #[tokio::test(flavor = "multi_thread")]
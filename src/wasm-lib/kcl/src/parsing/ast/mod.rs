@@ -13,6 +13,7 @@ impl BodyItem {
             BodyItem::ImportStatement(stmt) => stmt.module_id,
             BodyItem::ExpressionStatement(expression_statement) => expression_statement.module_id,
             BodyItem::VariableDeclaration(variable_declaration) => variable_declaration.module_id,
+            BodyItem::TypeDeclaration(type_declaration) => type_declaration.module_id,
             BodyItem::ReturnStatement(return_statement) => return_statement.module_id,
         }
     }
@@ -36,6 +37,7 @@ impl Expr {
             Expr::MemberExpression(member_expression) => member_expression.module_id,
             Expr::UnaryExpression(unary_expression) => unary_expression.module_id,
             Expr::IfExpression(expr) => expr.module_id,
+            Expr::ForExpression(expr) => expr.module_id,
             Expr::LabelledExpression(expr) => expr.expr.module_id(),
             Expr::None(none) => none.module_id,
         }
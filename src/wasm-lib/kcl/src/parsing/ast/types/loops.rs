@@ -0,0 +1,66 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::{BoxNode, ConstraintLevel, Digest, Expr, Hover, Identifier, Node};
+use crate::SourceRange;
+
+// Like the blocks of an `if` expression, the body of a loop must end in an expression.
+type ForBlock = crate::parsing::ast::types::Program;
+
+/// A `for` loop, e.g. `for i in [0..5] { i * 2 }`.
+///
+/// The body is run once for each item of the array, and the loop evaluates to an array of the
+/// results of each run.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, ts_rs::TS, JsonSchema)]
+#[ts(export)]
+#[serde(tag = "type")]
+pub struct ForExpression {
+    /// The name each item is bound to in the body.
+    pub var: Node<Identifier>,
+    /// The array to loop over.
+    pub iterable: Box<Expr>,
+    pub body: BoxNode<ForBlock>,
+
+    pub digest: Option<Digest>,
+}
+
+// Source code metadata
+
+impl Node<ForExpression> {
+    fn source_ranges(&self) -> Vec<SourceRange> {
+        vec![SourceRange::from(self)]
+    }
+}
+
+// IDE support and refactors
+
+impl Node<ForExpression> {
+    /// Get the constraint level.
+    pub fn get_constraint_level(&self) -> ConstraintLevel {
+        ConstraintLevel::Full {
+            source_ranges: self.source_ranges(),
+        }
+    }
+}
+
+impl ForExpression {
+    pub fn get_hover_value_for_position(&self, pos: usize, code: &str) -> Option<Hover> {
+        self.iterable
+            .get_hover_value_for_position(pos, code)
+            .or_else(|| self.body.get_hover_value_for_position(pos, code))
+    }
+
+    /// Rename all identifiers that have the old name to the new given name.
+    pub fn rename_identifiers(&mut self, old_name: &str, new_name: &str) {
+        self.iterable.rename_identifiers(old_name, new_name);
+        // The loop variable shadows the old name in the body.
+        if self.var.name == old_name {
+            return;
+        }
+        self.body.rename_identifiers(old_name, new_name);
+    }
+
+    pub fn replace_value(&mut self, source_range: SourceRange, new_value: Expr) {
+        self.iterable.replace_value(source_range, new_value);
+    }
+}
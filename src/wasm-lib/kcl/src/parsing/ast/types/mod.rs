@@ -20,6 +20,7 @@ use tower_lsp::lsp_types::{
 pub use crate::parsing::ast::types::{
     condition::{ElseIf, IfExpression},
     literal_value::LiteralValue,
+    loops::ForExpression,
     none::KclNone,
 };
 use crate::{
@@ -28,12 +29,14 @@ use crate::{
     execution::{annotations, KclValue, Metadata, TagIdentifier},
     parsing::{ast::digest::Digest, PIPE_OPERATOR},
     pretty::NumericSuffix,
+    settings::types::project::LintSettings,
     source_range::SourceRange,
     ModuleId,
 };
 
 mod condition;
 mod literal_value;
+mod loops;
 mod none;
 
 pub enum Definition<'a> {
@@ -249,20 +252,51 @@ impl Node<Program> {
     }
 
     pub fn lint_all(&self) -> Result<Vec<crate::lint::Discovered>> {
-        let rules = vec![
-            crate::lint::checks::lint_variables,
-            crate::lint::checks::lint_object_properties,
-            crate::lint::checks::lint_call_expressions,
-            crate::lint::checks::lint_should_be_offset_plane,
-        ];
+        self.lint_with_settings(&Default::default())
+    }
+
+    /// Check the Program with every lint rule, at the levels set in the project's settings.
+    /// Findings suppressed by an `@allow` annotation are marked as overridden.
+    pub fn lint_with_settings(&self, settings: &LintSettings) -> Result<Vec<crate::lint::Discovered>> {
+        let allowed = self.allowed_lints()?;
 
         let mut findings = vec![];
-        for rule in rules {
-            findings.append(&mut self.lint(rule)?);
+        for rule in crate::lint::checks::ALL_RULES {
+            let level = settings.level(rule.finding.code).unwrap_or(rule.default_level);
+            if level == crate::lint::LintLevel::Off {
+                continue;
+            }
+            for mut finding in self.lint(rule.check)? {
+                finding.level = level;
+                finding.overridden = allowed
+                    .iter()
+                    .any(|(range, codes)| range.contains(finding.pos.start()) && codes.contains(&finding.finding.code));
+                findings.push(finding);
+            }
         }
         Ok(findings)
     }
 
+    /// Find the statements with `@allow` annotations, and the lints they allow.
+    fn allowed_lints<'a>(&'a self) -> Result<Vec<(SourceRange, Vec<&'a str>)>> {
+        let allowed = RefCell::new(vec![]);
+        crate::walk::walk(self, |node: crate::walk::Node<'a>| {
+            let (attrs, range) = match node {
+                crate::walk::Node::ImportStatement(n) => (&n.outer_attrs, SourceRange::from(n)),
+                crate::walk::Node::ExpressionStatement(n) => (&n.outer_attrs, SourceRange::from(n)),
+                crate::walk::Node::VariableDeclaration(n) => (&n.outer_attrs, SourceRange::from(n)),
+                crate::walk::Node::ReturnStatement(n) => (&n.outer_attrs, SourceRange::from(n)),
+                _ => return Ok::<bool, anyhow::Error>(true),
+            };
+            let codes = annotations::allowed_lints(attrs);
+            if !codes.is_empty() {
+                allowed.borrow_mut().push((range, codes));
+            }
+            Ok(true)
+        })?;
+        Ok(allowed.into_inner())
+    }
+
     /// Get the annotations for the meta settings from the kcl file.
     pub fn meta_settings(&self) -> Result<Option<crate::execution::MetaSettings>, KclError> {
         for annotation in &self.inner_attrs {
@@ -358,7 +392,7 @@ impl Program {
 
         // Recurse over the item.
         match item {
-            BodyItem::ImportStatement(_) => None,
+            BodyItem::ImportStatement(_) | BodyItem::TypeDeclaration(_) => None,
             BodyItem::ExpressionStatement(expression_statement) => Some(&expression_statement.expression),
             BodyItem::VariableDeclaration(variable_declaration) => variable_declaration.get_expr_for_position(pos),
             BodyItem::ReturnStatement(return_statement) => Some(&return_statement.argument),
@@ -375,7 +409,7 @@ impl Program {
 
         // Recurse over the item.
         let expr = match item {
-            BodyItem::ImportStatement(_) => None,
+            BodyItem::ImportStatement(_) | BodyItem::TypeDeclaration(_) => None,
             BodyItem::ExpressionStatement(expression_statement) => Some(&expression_statement.expression),
             BodyItem::VariableDeclaration(variable_declaration) => variable_declaration.get_expr_for_position(pos),
             BodyItem::ReturnStatement(return_statement) => Some(&return_statement.argument),
@@ -399,7 +433,7 @@ impl Program {
         // We only care about the top level things in the program.
         for item in &self.body {
             match item {
-                BodyItem::ImportStatement(_) => continue,
+                BodyItem::ImportStatement(_) | BodyItem::TypeDeclaration(_) => continue,
                 BodyItem::ExpressionStatement(expression_statement) => {
                     if let Some(folding_range) = expression_statement.expression.get_lsp_folding_range() {
                         ranges.push(folding_range)
@@ -429,7 +463,7 @@ impl Program {
                         break;
                     }
                 }
-                BodyItem::ExpressionStatement(_expression_statement) => {
+                BodyItem::ExpressionStatement(_) | BodyItem::TypeDeclaration(_) => {
                     continue;
                 }
                 BodyItem::VariableDeclaration(ref mut variable_declaration) => {
@@ -456,6 +490,7 @@ impl Program {
             // Recurse over the item.
             let mut value = match item {
                 BodyItem::ImportStatement(_) => None, // TODO
+                BodyItem::TypeDeclaration(_) => None,
                 BodyItem::ExpressionStatement(ref mut expression_statement) => {
                     Some(&mut expression_statement.expression)
                 }
@@ -490,6 +525,7 @@ impl Program {
                 BodyItem::ImportStatement(ref mut stmt) => {
                     stmt.rename_identifiers(old_name, new_name);
                 }
+                BodyItem::TypeDeclaration(_) => {}
                 BodyItem::ExpressionStatement(ref mut expression_statement) => {
                     expression_statement.expression.rename_identifiers(old_name, new_name);
                 }
@@ -507,7 +543,7 @@ impl Program {
     pub fn replace_variable(&mut self, name: &str, declarator: Node<VariableDeclarator>) {
         for item in &mut self.body {
             match item {
-                BodyItem::ImportStatement(_) => {
+                BodyItem::ImportStatement(_) | BodyItem::TypeDeclaration(_) => {
                     continue;
                 }
                 BodyItem::ExpressionStatement(_expression_statement) => {
@@ -529,6 +565,7 @@ impl Program {
         for item in &mut self.body {
             match item {
                 BodyItem::ImportStatement(_) => {} // TODO
+                BodyItem::TypeDeclaration(_) => {}
                 BodyItem::ExpressionStatement(ref mut expression_statement) => expression_statement
                     .expression
                     .replace_value(source_range, new_value.clone()),
@@ -551,7 +588,7 @@ impl Program {
                         return Some(Definition::Import(stmt));
                     }
                 }
-                BodyItem::ExpressionStatement(_expression_statement) => {
+                BodyItem::ExpressionStatement(_) | BodyItem::TypeDeclaration(_) => {
                     continue;
                 }
                 BodyItem::VariableDeclaration(variable_declaration) => {
@@ -592,6 +629,7 @@ pub enum BodyItem {
     ImportStatement(BoxNode<ImportStatement>),
     ExpressionStatement(Node<ExpressionStatement>),
     VariableDeclaration(BoxNode<VariableDeclaration>),
+    TypeDeclaration(BoxNode<TypeDeclaration>),
     ReturnStatement(Node<ReturnStatement>),
 }
 
@@ -601,6 +639,7 @@ impl BodyItem {
             BodyItem::ImportStatement(stmt) => stmt.start,
             BodyItem::ExpressionStatement(expression_statement) => expression_statement.start,
             BodyItem::VariableDeclaration(variable_declaration) => variable_declaration.start,
+            BodyItem::TypeDeclaration(type_declaration) => type_declaration.start,
             BodyItem::ReturnStatement(return_statement) => return_statement.start,
         }
     }
@@ -610,6 +649,7 @@ impl BodyItem {
             BodyItem::ImportStatement(stmt) => stmt.end,
             BodyItem::ExpressionStatement(expression_statement) => expression_statement.end,
             BodyItem::VariableDeclaration(variable_declaration) => variable_declaration.end,
+            BodyItem::TypeDeclaration(type_declaration) => type_declaration.end,
             BodyItem::ReturnStatement(return_statement) => return_statement.end,
         }
     }
@@ -619,6 +659,7 @@ impl BodyItem {
             BodyItem::ImportStatement(node) => node.outer_attrs = attr,
             BodyItem::ExpressionStatement(node) => node.outer_attrs = attr,
             BodyItem::VariableDeclaration(node) => node.outer_attrs = attr,
+            BodyItem::TypeDeclaration(node) => node.outer_attrs = attr,
             BodyItem::ReturnStatement(node) => node.outer_attrs = attr,
         }
     }
@@ -628,6 +669,7 @@ impl BodyItem {
             BodyItem::ImportStatement(node) => &node.outer_attrs,
             BodyItem::ExpressionStatement(node) => &node.outer_attrs,
             BodyItem::VariableDeclaration(node) => &node.outer_attrs,
+            BodyItem::TypeDeclaration(node) => &node.outer_attrs,
             BodyItem::ReturnStatement(node) => &node.outer_attrs,
         }
     }
@@ -637,6 +679,7 @@ impl BodyItem {
             BodyItem::ImportStatement(node) => &mut node.outer_attrs,
             BodyItem::ExpressionStatement(node) => &mut node.outer_attrs,
             BodyItem::VariableDeclaration(node) => &mut node.outer_attrs,
+            BodyItem::TypeDeclaration(node) => &mut node.outer_attrs,
             BodyItem::ReturnStatement(node) => &mut node.outer_attrs,
         }
     }
@@ -675,6 +718,7 @@ pub enum Expr {
     MemberExpression(BoxNode<MemberExpression>),
     UnaryExpression(BoxNode<UnaryExpression>),
     IfExpression(BoxNode<IfExpression>),
+    ForExpression(BoxNode<ForExpression>),
     LabelledExpression(BoxNode<LabelledExpression>),
     None(Node<KclNone>),
 }
@@ -718,6 +762,7 @@ impl Expr {
             Expr::UnaryExpression(_unary_exp) => None,
             Expr::PipeSubstitution(_pipe_substitution) => None,
             Expr::IfExpression(_) => None,
+            Expr::ForExpression(_) => None,
             Expr::LabelledExpression(expr) => expr.expr.get_non_code_meta(),
             Expr::None(_none) => None,
         }
@@ -744,6 +789,7 @@ impl Expr {
             Expr::PipeExpression(ref mut pipe_exp) => pipe_exp.replace_value(source_range, new_value),
             Expr::UnaryExpression(ref mut unary_exp) => unary_exp.replace_value(source_range, new_value),
             Expr::IfExpression(_) => {}
+            Expr::ForExpression(ref mut expr) => expr.replace_value(source_range, new_value),
             Expr::PipeSubstitution(_) => {}
             Expr::LabelledExpression(expr) => expr.expr.replace_value(source_range, new_value),
             Expr::None(_) => {}
@@ -767,6 +813,7 @@ impl Expr {
             Expr::MemberExpression(member_expression) => member_expression.start,
             Expr::UnaryExpression(unary_expression) => unary_expression.start,
             Expr::IfExpression(expr) => expr.start,
+            Expr::ForExpression(expr) => expr.start,
             Expr::LabelledExpression(expr) => expr.start,
             Expr::None(none) => none.start,
         }
@@ -789,6 +836,7 @@ impl Expr {
             Expr::MemberExpression(member_expression) => member_expression.end,
             Expr::UnaryExpression(unary_expression) => unary_expression.end,
             Expr::IfExpression(expr) => expr.end,
+            Expr::ForExpression(expr) => expr.end,
             Expr::LabelledExpression(expr) => expr.end,
             Expr::None(none) => none.end,
         }
@@ -811,6 +859,7 @@ impl Expr {
             Expr::MemberExpression(member_expression) => member_expression.get_hover_value_for_position(pos, code),
             Expr::UnaryExpression(unary_expression) => unary_expression.get_hover_value_for_position(pos, code),
             Expr::IfExpression(expr) => expr.get_hover_value_for_position(pos, code),
+            Expr::ForExpression(expr) => expr.get_hover_value_for_position(pos, code),
             // TODO: LSP hover information for values/types. https://github.com/KittyCAD/modeling-app/issues/1126
             Expr::None(_) => None,
             Expr::Literal(_) => None,
@@ -847,6 +896,7 @@ impl Expr {
             }
             Expr::UnaryExpression(ref mut unary_expression) => unary_expression.rename_identifiers(old_name, new_name),
             Expr::IfExpression(ref mut expr) => expr.rename_identifiers(old_name, new_name),
+            Expr::ForExpression(ref mut expr) => expr.rename_identifiers(old_name, new_name),
             Expr::LabelledExpression(expr) => expr.expr.rename_identifiers(old_name, new_name),
             Expr::None(_) => {}
         }
@@ -873,6 +923,7 @@ impl Expr {
             Expr::MemberExpression(member_expression) => member_expression.get_constraint_level(),
             Expr::UnaryExpression(unary_expression) => unary_expression.get_constraint_level(),
             Expr::IfExpression(expr) => expr.get_constraint_level(),
+            Expr::ForExpression(expr) => expr.get_constraint_level(),
             Expr::LabelledExpression(expr) => expr.expr.get_constraint_level(),
             Expr::None(none) => none.get_constraint_level(),
         }
@@ -947,6 +998,7 @@ impl Expr {
             Expr::MemberExpression(_) => "property of an object/array",
             Expr::UnaryExpression(_) => "expression",
             Expr::IfExpression(_) => "if expression",
+            Expr::ForExpression(_) => "for loop",
             Expr::LabelledExpression(_) => "labelled expression",
             Expr::None(_) => "none",
         }
@@ -1290,7 +1342,8 @@ pub struct Annotation {
 
 impl Annotation {
     pub fn is_inner(&self) -> bool {
-        self.name.is_some()
+        // `@allow` applies to the item it's attached to.
+        self.name.is_some() && self.name() != Some(annotations::ALLOW)
     }
 
     pub fn name(&self) -> Option<&str> {
@@ -3059,6 +3112,31 @@ pub enum FnArgType {
     Object {
         properties: Vec<Parameter>,
     },
+    /// A record type declared with `type`, referred to by name.
+    Named {
+        name: BoxNode<Identifier>,
+    },
+}
+
+/// A record type declaration, e.g., `type Bolt = { diameter: number(mm), length: number(mm) }`.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, ts_rs::TS, JsonSchema)]
+#[ts(export)]
+#[serde(tag = "type")]
+pub struct TypeDeclaration {
+    pub name: Node<Identifier>,
+    /// The fields of the record. Fields declared with `?` don't need to be given.
+    pub fields: Vec<Parameter>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub digest: Option<Digest>,
+}
+
+impl TypeDeclaration {
+    /// Get the field with the given name.
+    pub fn field(&self, name: &str) -> Option<&Parameter> {
+        self.fields.iter().find(|field| field.identifier.name == name)
+    }
 }
 
 /// Default value for a parameter of a KCL function.
@@ -12,6 +12,7 @@ pub(crate) mod ast;
 mod math;
 pub(crate) mod parser;
 pub(crate) mod token;
+pub(crate) mod typecheck;
 
 pub const PIPE_SUBSTITUTION_OPERATOR: &str = "%";
 pub const PIPE_OPERATOR: &str = "|>";
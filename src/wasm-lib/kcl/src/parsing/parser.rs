@@ -19,15 +19,17 @@ use super::{
 use crate::{
     docs::StdLibFn,
     errors::{CompilationError, Severity, Tag},
+    execution::annotations,
     parsing::{
         ast::types::{
             Annotation, ArrayExpression, ArrayRangeExpression, BinaryExpression, BinaryOperator, BinaryPart, BodyItem,
             BoxNode, CallExpression, CallExpressionKw, CommentStyle, DefaultParamVal, ElseIf, Expr,
-            ExpressionStatement, FnArgPrimitive, FnArgType, FunctionExpression, Identifier, IfExpression, ImportItem,
-            ImportSelector, ImportStatement, ItemVisibility, LabeledArg, Literal, LiteralIdentifier, LiteralValue,
-            MemberExpression, MemberObject, Node, NodeList, NonCodeMeta, NonCodeNode, NonCodeValue, ObjectExpression,
-            ObjectProperty, Parameter, PipeExpression, PipeSubstitution, Program, ReturnStatement, Shebang,
-            TagDeclarator, UnaryExpression, UnaryOperator, VariableDeclaration, VariableDeclarator, VariableKind,
+            ExpressionStatement, FnArgPrimitive, FnArgType, ForExpression, FunctionExpression, Identifier,
+            IfExpression, ImportItem, ImportSelector, ImportStatement, ItemVisibility, LabeledArg, Literal,
+            LiteralIdentifier, LiteralValue, MemberExpression, MemberObject, Node, NodeList, NonCodeMeta, NonCodeNode,
+            NonCodeValue, ObjectExpression, ObjectProperty, Parameter, PipeExpression, PipeSubstitution, Program,
+            ReturnStatement, Shebang, TagDeclarator, TypeDeclaration, UnaryExpression, UnaryOperator,
+            VariableDeclaration, VariableDeclarator, VariableKind,
         },
         math::BinaryExpressionToken,
         token::{Token, TokenSlice, TokenType},
@@ -294,29 +296,51 @@ fn annotation(i: &mut TokenSlice) -> PResult<Node<Annotation>> {
         ignore_whitespace(i);
         let properties: Vec<_> = separated(
             0..,
-            separated_pair(
-                terminated(identifier, opt(whitespace)),
-                terminated(one_of((TokenType::Operator, "=")), opt(whitespace)),
-                expression,
-            )
-            .map(|(key, value)| Node {
-                start: key.start,
-                end: value.end(),
-                module_id: key.module_id,
-                inner: ObjectProperty {
-                    key,
-                    value,
-                    digest: None,
-                },
-                outer_attrs: Vec::new(),
-            }),
+            alt((
+                separated_pair(
+                    terminated(identifier, opt(whitespace)),
+                    terminated(one_of((TokenType::Operator, "=")), opt(whitespace)),
+                    expression,
+                )
+                .map(|(key, value)| {
+                    let property = Node {
+                        start: key.start,
+                        end: value.end(),
+                        module_id: key.module_id,
+                        inner: ObjectProperty {
+                            key,
+                            value,
+                            digest: None,
+                        },
+                        outer_attrs: Vec::new(),
+                    };
+                    (property, false)
+                }),
+                // A bare name, e.g., `@allow(Z0001)`.
+                object_property_same_key_and_val.map(|property| (property, true)),
+            )),
             comma_sep,
         )
         .parse_next(i)?;
         ignore_trailing_comma(i);
         ignore_whitespace(i);
         end = close_paren(i)?.end;
-        Some(properties)
+
+        // Only `@allow` takes bare names, everything else needs a value.
+        let allow = name.as_ref().is_some_and(|n| n.name == annotations::ALLOW);
+        if let Some((property, _)) = properties.iter().find(|(_, bare)| *bare && !allow) {
+            return Err(ErrMode::Cut(
+                CompilationError::fatal(
+                    SourceRange::from(property),
+                    format!(
+                        "Expected a value for `{}`, e.g., `{} = ...`",
+                        property.key.name, property.key.name
+                    ),
+                )
+                .into(),
+            ));
+        }
+        Some(properties.into_iter().map(|(property, _)| property).collect())
     } else {
         None
     };
@@ -497,7 +521,7 @@ pub(crate) fn unsigned_number_literal(i: &mut TokenSlice) -> PResult<Node<Litera
                 if token.numeric_suffix().is_some() {
                     ParseContext::warn(CompilationError::err(
                         (&token).into(),
-                        "Unit of Measure suffixes are experimental and currently do nothing.",
+                        "Unit of Measure suffixes are experimental and most std library functions ignore them.",
                     ));
                 }
 
@@ -578,6 +602,7 @@ fn operand(i: &mut TokenSlice) -> PResult<BinaryPart> {
                 | Expr::ArrayExpression(_)
                 | Expr::ArrayRangeExpression(_)
                 | Expr::ObjectExpression(_)
+                | Expr::ForExpression(_)
                 | Expr::LabelledExpression(..) => return Err(CompilationError::fatal(source_range, TODO_783)),
                 Expr::None(_) => {
                     return Err(CompilationError::fatal(
@@ -1091,6 +1116,63 @@ fn if_expr(i: &mut TokenSlice) -> PResult<BoxNode<IfExpression>> {
     ))
 }
 
+fn for_expr(i: &mut TokenSlice) -> PResult<BoxNode<ForExpression>> {
+    let for_ = any
+        .try_map(|token: Token| {
+            if matches!(token.token_type, TokenType::Keyword) && token.value == "for" {
+                Ok(token)
+            } else {
+                Err(CompilationError::fatal(
+                    token.as_source_range(),
+                    format!("{} is not 'for'", token.value.as_str()),
+                ))
+            }
+        })
+        .context(expected("the 'for' keyword"))
+        .parse_next(i)?;
+    let _ = whitespace(i)?;
+    let var = binding_name.parse_next(i).map_err(|e| e.cut())?;
+    let _ = whitespace(i)?;
+    // `in` is only a keyword here, so it can still be used as a name elsewhere.
+    let _ = any
+        .try_map(|token: Token| {
+            if matches!(token.token_type, TokenType::Word) && token.value == "in" {
+                Ok(token)
+            } else {
+                Err(CompilationError::fatal(
+                    token.as_source_range(),
+                    format!("{} is not 'in'", token.value.as_str()),
+                ))
+            }
+        })
+        .context(expected("the 'in' keyword"))
+        .parse_next(i)
+        .map_err(|e| e.cut())?;
+    let _ = whitespace(i)?;
+    let iterable = expression(i).map(Box::new)?;
+    let _ = whitespace(i)?;
+    let _ = open_brace(i)?;
+    ignore_whitespace(i);
+    let body = program
+        .verify(|block| block.ends_with_expr())
+        .parse_next(i)
+        .map_err(|e| e.cut())
+        .map(Box::new)?;
+    ignore_whitespace(i);
+    let end = close_brace(i)?.end;
+    Ok(Node::boxed(
+        ForExpression {
+            var,
+            iterable,
+            body,
+            digest: Default::default(),
+        },
+        for_.start,
+        end,
+        for_.module_id,
+    ))
+}
+
 fn function_expr(i: &mut TokenSlice) -> PResult<Expr> {
     let fn_tok = opt(fun).parse_next(i)?;
     ignore_whitespace(i);
@@ -1332,6 +1414,8 @@ fn body_items_within_function(i: &mut TokenSlice) -> PResult<WithinFunction> {
             (declaration.map(BodyItem::VariableDeclaration), opt(noncode_just_after_code)).map(WithinFunction::BodyItem),
         token if token.value == "import" && matches!(token.token_type, TokenType::Keyword) =>
             (import_stmt.map(BodyItem::ImportStatement), opt(noncode_just_after_code)).map(WithinFunction::BodyItem),
+        token if token.value == "type" && matches!(token.token_type, TokenType::Keyword) =>
+            (type_decl.map(BodyItem::TypeDeclaration), opt(noncode_just_after_code)).map(WithinFunction::BodyItem),
         Token { ref value, .. } if value == "return" =>
             (return_stmt.map(BodyItem::ReturnStatement), opt(noncode_just_after_code)).map(WithinFunction::BodyItem),
         token if !token.is_code_token() => {
@@ -1546,6 +1630,76 @@ fn glob(i: &mut TokenSlice) -> PResult<Token> {
         .parse_next(i)
 }
 
+/// Parse a record type declaration, e.g. `type Bolt = { diameter: number(mm), length: number(mm) }`.
+fn type_decl(i: &mut TokenSlice) -> PResult<BoxNode<TypeDeclaration>> {
+    let type_token = any
+        .try_map(|token: Token| {
+            if matches!(token.token_type, TokenType::Keyword) && token.value == "type" {
+                Ok(token)
+            } else {
+                Err(CompilationError::fatal(
+                    token.as_source_range(),
+                    format!("{} is not the 'type' keyword", token.value.as_str()),
+                ))
+            }
+        })
+        .context(expected("the 'type' keyword"))
+        .parse_next(i)?;
+    require_whitespace(i)?;
+    let name = binding_name
+        .context(expected("the name of the type, e.g. `type Bolt = { ... }`"))
+        .parse_next(i)
+        .map_err(|e| e.cut())?;
+    ignore_whitespace(i);
+    equals(i).map_err(|e| e.cut())?;
+    ignore_whitespace(i);
+    let (fields, end) = record_fields
+        .context(expected(
+            "the fields of the record, e.g. `{ diameter: number, length: number }`",
+        ))
+        .parse_next(i)
+        .map_err(|e| e.cut())?;
+    Ok(Node::boxed(
+        TypeDeclaration {
+            name,
+            fields,
+            digest: None,
+        },
+        type_token.start,
+        end,
+        type_token.module_id,
+    ))
+}
+
+/// The fields of a record type, e.g. `{ diameter: number, length?: number }`.
+/// Returns the fields and the end of the closing brace.
+fn record_fields(i: &mut TokenSlice) -> PResult<(Vec<Parameter>, usize)> {
+    fn record_field(i: &mut TokenSlice) -> PResult<Parameter> {
+        let identifier = binding_name.parse_next(i)?;
+        let optional = opt(question_mark).parse_next(i)?.is_some();
+        ignore_whitespace(i);
+        colon
+            .context(expected("a `:` followed by the type of the field"))
+            .parse_next(i)?;
+        ignore_whitespace(i);
+        let type_ = argument_type.parse_next(i)?;
+        Ok(Parameter {
+            identifier,
+            type_: Some(type_),
+            default_value: optional.then(DefaultParamVal::none),
+            labeled: true,
+            digest: None,
+        })
+    }
+
+    open_brace(i)?;
+    ignore_whitespace(i);
+    let fields = repeat(0.., terminated(record_field, property_separator)).parse_next(i)?;
+    ignore_whitespace(i);
+    let end = close_brace(i)?.end;
+    Ok((fields, end))
+}
+
 fn import_stmt(i: &mut TokenSlice) -> PResult<BoxNode<ImportStatement>> {
     let (visibility, visibility_token) = opt(terminated(item_visibility, whitespace))
         .parse_next(i)?
@@ -1909,6 +2063,7 @@ fn expr_allowed_in_pipe_expr(i: &mut TokenSlice) -> PResult<Expr> {
         pipe_sub.map(Box::new).map(Expr::PipeSubstitution),
         function_expr,
         if_expr.map(Expr::IfExpression),
+        for_expr.map(Expr::ForExpression),
         unnecessarily_bracketed,
     ))
     .context(expected("a KCL expression (but not a pipe expression)"))
@@ -2549,6 +2704,12 @@ fn argument_type(i: &mut TokenSlice) -> PResult<FnArgType> {
                         CompilationError::fatal(token.as_source_range(), format!("Invalid type: {}", token.value))
                     })
             }),
+        // Types named by a word, either a built-in type like `Solid` or a record type declared with `type`.
+        identifier.map(|name| {
+            Ok(FnArgPrimitive::from_str(&name.name, None)
+                .map(FnArgType::Primitive)
+                .unwrap_or(FnArgType::Named { name: Box::new(name) }))
+        }),
     ))
     .parse_next(i)?
     .map_err(|e: CompilationError| ErrMode::Backtrack(ContextError::from(e)))?;
@@ -3903,6 +4064,38 @@ height = [obj["a"] -1, 0]"#;
         crate::parsing::top_level_parse("@settings(units = mm)").unwrap();
     }
 
+    #[test]
+    fn test_annotation_allow() {
+        let program = crate::parsing::top_level_parse(
+            r#"@settings(defaultLengthUnit = mm)
+
+@allow(Z0001, Z0003)
+foo_bar = 1"#,
+        )
+        .unwrap();
+        assert_eq!(program.inner_attrs.len(), 1);
+        let attrs = program.body[0].get_attrs();
+        assert_eq!(attrs.len(), 1);
+        assert_eq!(attrs[0].name(), Some("allow"));
+        let names: Vec<_> = attrs[0]
+            .properties
+            .iter()
+            .flatten()
+            .map(|prop| prop.key.name.as_str())
+            .collect();
+        assert_eq!(names, ["Z0001", "Z0003"]);
+    }
+
+    #[test]
+    fn test_annotation_bare_name_only_for_allow() {
+        assert_err(
+            "@settings(defaultLengthUnit)\nx = 1",
+            "Expected a value for `defaultLengthUnit`",
+            [10, 27],
+        );
+        assert_err("@allow(Z0001, x)\n@foo(x)\ny = 1", "Expected a value for `x`", [22, 23]);
+    }
+
     #[test]
     fn test_anon_fn_no_fn() {
         assert_err_contains("foo(42, (x) { return x + 1 })", "Anonymous function requires `fn`");
@@ -4321,6 +4514,77 @@ export fn cos(num: number(rad)): number(_) {}"#;
         let _res = if_expr(&mut tokens.as_slice()).unwrap();
     }
 
+    #[test]
+    fn basic_for() {
+        let some_program_string = "for i in [0..3] {
+            x = i * 2
+            x + 1
+        }";
+        let module_id = ModuleId::default();
+        let tokens = crate::parsing::token::lex(some_program_string, module_id).unwrap();
+        let res = for_expr(&mut tokens.as_slice()).unwrap();
+        assert_eq!(res.var.name, "i");
+        assert!(matches!(*res.iterable, Expr::ArrayRangeExpression(_)));
+        assert_eq!(res.body.body.len(), 2);
+    }
+
+    #[test]
+    fn for_in_declaration() {
+        let some_program_string = r#"holes = for i in [1, 2, 3] {
+  i * 10
+}
+"#;
+        let program = crate::parsing::top_level_parse(some_program_string).unwrap();
+        let BodyItem::VariableDeclaration(decl) = &program.body[0] else {
+            panic!("expected a variable declaration");
+        };
+        assert!(matches!(decl.declaration.init, Expr::ForExpression(_)));
+    }
+
+    #[test]
+    fn for_needs_in() {
+        assert_err("x = for i [0..3] { i }", "[ is not 'in'", [10, 11]);
+    }
+
+    #[test]
+    fn for_body_must_end_with_expr() {
+        assert_err_contains("x = for i in [0..3] { y = i }", "Unexpected token: y");
+    }
+
+    #[test]
+    fn type_declaration() {
+        let program = crate::parsing::top_level_parse(
+            "type Bolt = { diameter: number, length?: number }\nfn area(bolt: Bolt): number { return 1 }",
+        )
+        .unwrap();
+        let BodyItem::TypeDeclaration(decl) = &program.body[0] else {
+            panic!("expected a type declaration, found {:?}", program.body[0]);
+        };
+        assert_eq!(decl.name.name, "Bolt");
+        let fields: Vec<_> = decl
+            .fields
+            .iter()
+            .map(|f| (f.identifier.name.as_str(), f.optional()))
+            .collect();
+        assert_eq!(fields, vec![("diameter", false), ("length", true)]);
+    }
+
+    #[test]
+    fn type_declaration_needs_fields() {
+        assert_err_contains("type Bolt = number", "Unexpected token: number");
+    }
+
+    #[test]
+    fn type_mismatches_are_not_parse_errors() {
+        let program = crate::parsing::top_level_parse("fn double(x: number) { return x * 2 }\nd = double('two')")
+            .parse_errs_as_err()
+            .unwrap();
+        let warnings = crate::parsing::typecheck::check_program(&program);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].message, "Expected a number, found a string");
+        assert_eq!(warnings[0].severity, Severity::Warning);
+    }
+
     #[test]
     fn test_keyword_ok_in_fn_args_return() {
         let some_program_string = r#"fn thing(param) {
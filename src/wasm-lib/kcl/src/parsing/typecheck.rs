@@ -0,0 +1,1035 @@
+//! Check the types in a program after it's parsed, before anything is executed.
+//!
+//! This is a separate pass from parsing, and mismatches are reported as warnings, so that programs
+//! which ran before types were checked still parse and run.
+//!
+//! Most KCL values aren't annotated with types, so the check is conservative: a mismatch is only
+//! reported when both the expected type and the type of a value are known, and they don't match.
+//! The units of numbers are checked the same way, so adding a length to an angle is reported, but
+//! adding a length to a number without units is not.
+
+use std::collections::HashMap;
+
+use crate::{
+    errors::CompilationError,
+    parsing::{
+        ast::types::{
+            BinaryExpression, BinaryOperator, BinaryPart, BodyItem, CallExpression, CallExpressionKw, DefaultParamVal,
+            Expr, FnArgPrimitive, FnArgType, FunctionExpression, IfExpression, LiteralIdentifier, LiteralValue,
+            MemberExpression, MemberObject, Node, ObjectProperty, Parameter, Program, TypeDeclaration, UnaryExpression,
+            UnaryOperator,
+        },
+        token::NumericSuffix,
+    },
+    source_range::SourceRange,
+};
+
+/// Trigonometric functions in the standard library, which take an angle.
+const TRIG_FUNCTIONS: [&str; 3] = ["cos", "sin", "tan"];
+
+/// How far to follow variables and nested types before giving up, so cycles can't hang the check.
+const MAX_DEPTH: usize = 32;
+
+/// Check the types in a program, returning a warning for each mismatch.
+pub(crate) fn check_program(program: &Node<Program>) -> Vec<CompilationError> {
+    let mut checker = Checker::default();
+    checker.check_block(program, Vec::new(), None);
+    checker.warnings
+}
+
+/// What a name is bound to, as far as the checker can tell.
+#[derive(Debug, Clone, Copy)]
+enum Binding<'a> {
+    /// A variable or function, bound to the value of an expression.
+    Value(&'a Expr),
+    /// A parameter declared with a type.
+    Typed(&'a FnArgType),
+    /// Anything else, e.g. an untyped parameter or a loop variable.
+    Unknown,
+}
+
+#[derive(Debug, Default)]
+struct Scope<'a> {
+    bindings: HashMap<&'a str, Binding<'a>>,
+    types: HashMap<&'a str, &'a Node<TypeDeclaration>>,
+}
+
+/// The type of a value, as far as the checker can tell.
+#[derive(Debug, Clone)]
+enum Ty<'a> {
+    Unknown,
+    /// Any primitive other than a number.
+    Primitive(FnArgPrimitive),
+    Number(Quantity),
+    Function,
+    /// An array, with its elements if they're written out.
+    Array(&'a [Expr]),
+    /// An array whose elements are all of the same primitive type.
+    ArrayOf(FnArgPrimitive),
+    /// An object literal.
+    Object(&'a [Node<ObjectProperty>]),
+    /// An object whose fields are declared by a type, and the name of the type if it has one.
+    Record {
+        name: Option<&'a str>,
+        fields: &'a [Parameter],
+    },
+}
+
+/// What a number measures, as far as the checker can tell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Quantity {
+    Unknown,
+    /// A number without units, which takes on the units of whatever it's used with.
+    Unspecified,
+    Count,
+    Length,
+    Area,
+    Angle,
+}
+
+impl Quantity {
+    fn from_suffix(suffix: NumericSuffix) -> Self {
+        match suffix {
+            NumericSuffix::None => Quantity::Unspecified,
+            NumericSuffix::Count => Quantity::Count,
+            NumericSuffix::Mm
+            | NumericSuffix::Cm
+            | NumericSuffix::M
+            | NumericSuffix::Inch
+            | NumericSuffix::Ft
+            | NumericSuffix::Yd => Quantity::Length,
+            NumericSuffix::Deg | NumericSuffix::Rad => Quantity::Angle,
+        }
+    }
+
+    /// Describe a physical quantity, or `None` if this isn't one.
+    fn physical(self) -> Option<&'static str> {
+        match self {
+            Quantity::Length => Some("a length"),
+            Quantity::Area => Some("an area"),
+            Quantity::Angle => Some("an angle"),
+            Quantity::Unknown | Quantity::Unspecified | Quantity::Count => None,
+        }
+    }
+
+    /// The quantity measured by the result of an arithmetic operation.
+    fn combine(op: &BinaryOperator, left: Quantity, right: Quantity) -> Quantity {
+        use Quantity::*;
+        match op {
+            BinaryOperator::Add | BinaryOperator::Sub | BinaryOperator::Mod => match (left, right) {
+                (_, Unspecified) => left,
+                (Unspecified, _) => right,
+                _ if left == right => left,
+                _ => Unknown,
+            },
+            BinaryOperator::Mul => match (left, right) {
+                (Length, Length) => Area,
+                (_, Unspecified | Count) => left,
+                (Unspecified | Count, _) => right,
+                _ => Unknown,
+            },
+            BinaryOperator::Div => match (left, right) {
+                (Length, Length) | (Area, Area) | (Angle, Angle) => Count,
+                (Area, Length) => Length,
+                (_, Unspecified | Count) => left,
+                _ => Unknown,
+            },
+            BinaryOperator::Pow => match (left, right) {
+                (Unspecified, Unspecified) => Unspecified,
+                _ => Unknown,
+            },
+            _ => Unknown,
+        }
+    }
+
+    /// Explain why two quantities can't be used together, if they can't.
+    fn conflict(op: &BinaryOperator, left: Quantity, right: Quantity) -> Option<String> {
+        let verb = match op {
+            BinaryOperator::Add => "add",
+            BinaryOperator::Sub => "subtract",
+            BinaryOperator::Mod => "take the remainder of",
+            BinaryOperator::Eq
+            | BinaryOperator::Neq
+            | BinaryOperator::Gt
+            | BinaryOperator::Gte
+            | BinaryOperator::Lt
+            | BinaryOperator::Lte => "compare",
+            // Multiplying or dividing quantities is fine, it just makes a different quantity.
+            BinaryOperator::Mul | BinaryOperator::Div | BinaryOperator::Pow => return None,
+            BinaryOperator::And | BinaryOperator::Or => return None,
+        };
+        match (left.physical(), right.physical()) {
+            (Some(l), Some(r)) if left != right => Some(format!("Cannot {verb} {l} and {r}")),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Checker<'a> {
+    scopes: Vec<Scope<'a>>,
+    warnings: Vec<CompilationError>,
+}
+
+impl<'a> Checker<'a> {
+    /// Check a program or block, with the given names bound in its scope. If the block is a
+    /// function body, `return_type` is the function's declared return type.
+    fn check_block(
+        &mut self,
+        program: &'a Node<Program>,
+        bindings: Vec<(&'a str, Binding<'a>)>,
+        return_type: Option<&'a FnArgType>,
+    ) {
+        self.scopes.push(Scope {
+            bindings: bindings.into_iter().collect(),
+            types: HashMap::new(),
+        });
+
+        // Types can be used anywhere in the block they're declared in.
+        let declarations: Vec<_> = program
+            .body
+            .iter()
+            .filter_map(|item| match item {
+                BodyItem::TypeDeclaration(decl) => Some(decl.as_ref()),
+                _ => None,
+            })
+            .collect();
+        for decl in &declarations {
+            let types = &mut self.scopes.last_mut().unwrap().types;
+            if types.insert(&decl.name.name, decl).is_some() {
+                self.warnings.push(CompilationError::warn(
+                    (&decl.name).into(),
+                    format!("The type `{}` is declared more than once", decl.name.name),
+                ));
+            }
+        }
+        for decl in declarations {
+            for field in &decl.fields {
+                self.check_param(field);
+            }
+        }
+
+        for item in &program.body {
+            match item {
+                BodyItem::ImportStatement(_) | BodyItem::TypeDeclaration(_) => {}
+                BodyItem::ExpressionStatement(stmt) => self.visit_expr(&stmt.expression),
+                BodyItem::VariableDeclaration(decl) => {
+                    let declarator = &decl.declaration;
+                    // Functions can refer to themselves, other variables can not.
+                    if let Expr::FunctionExpression(_) = declarator.init {
+                        self.bind(&declarator.id.name, Binding::Value(&declarator.init));
+                        self.visit_expr(&declarator.init);
+                    } else {
+                        self.visit_expr(&declarator.init);
+                        self.bind(&declarator.id.name, Binding::Value(&declarator.init));
+                    }
+                }
+                BodyItem::ReturnStatement(stmt) => {
+                    self.visit_expr(&stmt.argument);
+                    if let Some(return_type) = return_type {
+                        self.expect(return_type, &stmt.argument);
+                    }
+                }
+            }
+        }
+
+        self.scopes.pop();
+    }
+
+    fn bind(&mut self, name: &'a str, binding: Binding<'a>) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.bindings.insert(name, binding);
+        }
+    }
+
+    fn lookup(&self, name: &str) -> Option<Binding<'a>> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.bindings.get(name).copied())
+    }
+
+    fn lookup_type(&self, name: &str) -> Option<&'a Node<TypeDeclaration>> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.types.get(name).copied())
+    }
+
+    /// Find the user-defined function a name refers to.
+    fn lookup_function(&self, name: &str) -> Option<&'a Node<FunctionExpression>> {
+        match self.lookup(name)? {
+            Binding::Value(Expr::FunctionExpression(func)) => Some(func),
+            _ => None,
+        }
+    }
+
+    /// Check that an expression has the expected type.
+    fn expect(&mut self, expected: &FnArgType, expr: &'a Expr) {
+        let mut warnings = Vec::new();
+        self.check(expected, self.type_of(expr, 0), expr.into(), 0, &mut warnings);
+        self.warnings.extend(warnings);
+    }
+
+    /// Check a parameter or record field: its type must exist, and its default value must fit it.
+    fn check_param(&mut self, param: &'a Parameter) {
+        let Some(type_) = &param.type_ else {
+            return;
+        };
+        self.check_type_exists(type_);
+        if let Some(DefaultParamVal::Literal(literal)) = &param.default_value {
+            let mut warnings = Vec::new();
+            self.check(type_, literal_type(&literal.value), literal.into(), 0, &mut warnings);
+            self.warnings.extend(warnings);
+        }
+    }
+
+    fn check_type_exists(&mut self, type_: &'a FnArgType) {
+        match type_ {
+            FnArgType::Primitive(_) | FnArgType::Array(_) => {}
+            FnArgType::Object { properties } => {
+                for property in properties {
+                    self.check_param(property);
+                }
+            }
+            FnArgType::Named { name } => {
+                if self.lookup_type(&name.name).is_none() {
+                    self.warnings.push(CompilationError::warn(
+                        name.into(),
+                        format!("Unknown type `{}`", name.name),
+                    ));
+                }
+            }
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        match expr {
+            Expr::Literal(_)
+            | Expr::Identifier(_)
+            | Expr::MemberExpression(_)
+            | Expr::TagDeclarator(_)
+            | Expr::PipeSubstitution(_)
+            | Expr::None(_) => {}
+            Expr::BinaryExpression(expr) => self.visit_binary(expr),
+            Expr::FunctionExpression(func) => self.visit_function(func),
+            Expr::CallExpression(call) => self.visit_call(call),
+            Expr::CallExpressionKw(call) => self.visit_call_kw(call),
+            Expr::PipeExpression(pipe) => pipe.body.iter().for_each(|expr| self.visit_expr(expr)),
+            Expr::ArrayExpression(array) => array.elements.iter().for_each(|expr| self.visit_expr(expr)),
+            Expr::ArrayRangeExpression(range) => {
+                self.visit_expr(&range.start_element);
+                self.visit_expr(&range.end_element);
+            }
+            Expr::ObjectExpression(object) => object.properties.iter().for_each(|p| self.visit_expr(&p.value)),
+            Expr::UnaryExpression(expr) => self.visit_binary_part(&expr.argument),
+            Expr::IfExpression(expr) => self.visit_if(expr),
+            Expr::ForExpression(expr) => {
+                self.visit_expr(&expr.iterable);
+                self.check_block(&expr.body, vec![(&expr.var.name, Binding::Unknown)], None);
+            }
+            Expr::LabelledExpression(expr) => {
+                self.visit_expr(&expr.expr);
+                self.bind(&expr.label.name, Binding::Value(&expr.expr));
+            }
+        }
+    }
+
+    fn visit_binary(&mut self, expr: &'a Node<BinaryExpression>) {
+        self.visit_binary_part(&expr.left);
+        self.visit_binary_part(&expr.right);
+        if let (Ty::Number(left), Ty::Number(right)) =
+            (self.type_of_part(&expr.left, 0), self.type_of_part(&expr.right, 0))
+        {
+            if let Some(message) = Quantity::conflict(&expr.operator, left, right) {
+                self.warnings.push(CompilationError::warn(expr.into(), message));
+            }
+        }
+    }
+
+    fn visit_binary_part(&mut self, part: &'a BinaryPart) {
+        match part {
+            BinaryPart::Literal(_) | BinaryPart::Identifier(_) | BinaryPart::MemberExpression(_) => {}
+            BinaryPart::BinaryExpression(expr) => self.visit_binary(expr),
+            BinaryPart::CallExpression(call) => self.visit_call(call),
+            BinaryPart::CallExpressionKw(call) => self.visit_call_kw(call),
+            BinaryPart::UnaryExpression(expr) => self.visit_binary_part(&expr.argument),
+            BinaryPart::IfExpression(expr) => self.visit_if(expr),
+        }
+    }
+
+    fn visit_if(&mut self, expr: &'a IfExpression) {
+        self.visit_expr(&expr.cond);
+        self.check_block(&expr.then_val, Vec::new(), None);
+        for else_if in &expr.else_ifs {
+            self.visit_expr(&else_if.cond);
+            self.check_block(&else_if.then_val, Vec::new(), None);
+        }
+        self.check_block(&expr.final_else, Vec::new(), None);
+    }
+
+    fn visit_function(&mut self, func: &'a FunctionExpression) {
+        for param in &func.params {
+            self.check_param(param);
+        }
+        if let Some(return_type) = &func.return_type {
+            self.check_type_exists(return_type);
+        }
+        let params = func
+            .params
+            .iter()
+            .map(|param| {
+                let binding = match &param.type_ {
+                    Some(type_) => Binding::Typed(type_),
+                    None => Binding::Unknown,
+                };
+                (param.identifier.name.as_str(), binding)
+            })
+            .collect();
+        self.check_block(&func.body, params, func.return_type.as_ref());
+    }
+
+    fn visit_call(&mut self, call: &'a CallExpression) {
+        for arg in &call.arguments {
+            self.visit_expr(arg);
+        }
+        if let Some(arg) = call.arguments.first() {
+            self.check_trig_arg(&call.callee.name, arg);
+        }
+        let Some(func) = self.lookup_function(&call.callee.name) else {
+            return;
+        };
+        // The executor checks the number of arguments.
+        for (param, arg) in func.params.iter().zip(&call.arguments) {
+            if let Some(type_) = &param.type_ {
+                self.expect(type_, arg);
+            }
+        }
+    }
+
+    fn visit_call_kw(&mut self, call: &'a CallExpressionKw) {
+        if let Some(arg) = &call.unlabeled {
+            self.visit_expr(arg);
+        }
+        for arg in &call.arguments {
+            self.visit_expr(&arg.arg);
+        }
+        if let Some(arg) = &call.unlabeled {
+            self.check_trig_arg(&call.callee.name, arg);
+        }
+        let Some(func) = self.lookup_function(&call.callee.name) else {
+            return;
+        };
+        if let Some(arg) = &call.unlabeled {
+            if let Some(type_) = func
+                .params
+                .first()
+                .filter(|p| !p.labeled)
+                .and_then(|p| p.type_.as_ref())
+            {
+                self.expect(type_, arg);
+            }
+        }
+        for arg in &call.arguments {
+            let param = func
+                .params
+                .iter()
+                .find(|p| p.labeled && p.identifier.name == arg.label.name);
+            if let Some(type_) = param.and_then(|p| p.type_.as_ref()) {
+                self.expect(type_, &arg.arg);
+            }
+        }
+    }
+
+    /// Trigonometric functions take angles, or numbers without units which are in radians.
+    fn check_trig_arg(&mut self, callee: &str, arg: &'a Expr) {
+        if !TRIG_FUNCTIONS.contains(&callee) || self.lookup(callee).is_some() {
+            return;
+        }
+        if let Ty::Number(quantity @ (Quantity::Length | Quantity::Area)) = self.type_of(arg, 0) {
+            self.warnings.push(CompilationError::warn(
+                arg.into(),
+                format!(
+                    "`{callee}` expects an angle, found {}",
+                    quantity.physical().unwrap_or("a number")
+                ),
+            ));
+        }
+    }
+
+    /// The type of a value declared with the given type.
+    fn declared(&self, type_: &'a FnArgType) -> Ty<'a> {
+        match type_ {
+            // A number declared without units could be anything.
+            FnArgType::Primitive(FnArgPrimitive::Number(NumericSuffix::None)) => Ty::Number(Quantity::Unknown),
+            FnArgType::Primitive(FnArgPrimitive::Number(suffix)) => Ty::Number(Quantity::from_suffix(*suffix)),
+            FnArgType::Primitive(primitive) => Ty::Primitive(primitive.clone()),
+            FnArgType::Array(primitive) => Ty::ArrayOf(primitive.clone()),
+            FnArgType::Object { properties } => Ty::Record {
+                name: None,
+                fields: properties,
+            },
+            FnArgType::Named { name } => match self.lookup_type(&name.name) {
+                Some(decl) => Ty::Record {
+                    name: Some(&decl.name.name),
+                    fields: &decl.fields,
+                },
+                None => Ty::Unknown,
+            },
+        }
+    }
+
+    fn type_of(&self, expr: &'a Expr, depth: usize) -> Ty<'a> {
+        if depth > MAX_DEPTH {
+            return Ty::Unknown;
+        }
+        match expr {
+            Expr::Literal(literal) => literal_type(&literal.value),
+            Expr::TagDeclarator(_) => Ty::Primitive(FnArgPrimitive::Tag),
+            Expr::Identifier(id) => self.type_of_name(&id.name, depth),
+            Expr::BinaryExpression(expr) => self.type_of_binary(expr, depth),
+            Expr::FunctionExpression(_) => Ty::Function,
+            Expr::CallExpression(call) => self.return_type_of(&call.callee.name),
+            Expr::CallExpressionKw(call) => self.return_type_of(&call.callee.name),
+            Expr::ArrayExpression(array) => Ty::Array(&array.elements),
+            Expr::ArrayRangeExpression(_) => Ty::ArrayOf(FnArgPrimitive::Number(NumericSuffix::None)),
+            Expr::ObjectExpression(object) => Ty::Object(&object.properties),
+            Expr::MemberExpression(member) => self.type_of_member(member, depth),
+            Expr::UnaryExpression(expr) => self.type_of_unary(expr, depth),
+            Expr::LabelledExpression(expr) => self.type_of(&expr.expr, depth + 1),
+            // We don't know what's in the array, but it's definitely an array.
+            Expr::ForExpression(_) => Ty::Array(&[]),
+            Expr::PipeExpression(_) | Expr::PipeSubstitution(_) | Expr::IfExpression(_) | Expr::None(_) => Ty::Unknown,
+        }
+    }
+
+    fn type_of_part(&self, part: &'a BinaryPart, depth: usize) -> Ty<'a> {
+        if depth > MAX_DEPTH {
+            return Ty::Unknown;
+        }
+        match part {
+            BinaryPart::Literal(literal) => literal_type(&literal.value),
+            BinaryPart::Identifier(id) => self.type_of_name(&id.name, depth),
+            BinaryPart::BinaryExpression(expr) => self.type_of_binary(expr, depth),
+            BinaryPart::CallExpression(call) => self.return_type_of(&call.callee.name),
+            BinaryPart::CallExpressionKw(call) => self.return_type_of(&call.callee.name),
+            BinaryPart::UnaryExpression(expr) => self.type_of_unary(expr, depth),
+            BinaryPart::MemberExpression(member) => self.type_of_member(member, depth),
+            BinaryPart::IfExpression(_) => Ty::Unknown,
+        }
+    }
+
+    fn type_of_name(&self, name: &str, depth: usize) -> Ty<'a> {
+        match self.lookup(name) {
+            Some(Binding::Value(expr)) => self.type_of(expr, depth + 1),
+            Some(Binding::Typed(type_)) => self.declared(type_),
+            Some(Binding::Unknown) | None => Ty::Unknown,
+        }
+    }
+
+    fn type_of_binary(&self, expr: &'a BinaryExpression, depth: usize) -> Ty<'a> {
+        match expr.operator {
+            BinaryOperator::Add
+            | BinaryOperator::Sub
+            | BinaryOperator::Mul
+            | BinaryOperator::Div
+            | BinaryOperator::Mod
+            | BinaryOperator::Pow => {
+                match (
+                    self.type_of_part(&expr.left, depth + 1),
+                    self.type_of_part(&expr.right, depth + 1),
+                ) {
+                    (Ty::Number(left), Ty::Number(right)) => Ty::Number(Quantity::combine(&expr.operator, left, right)),
+                    _ => Ty::Unknown,
+                }
+            }
+            BinaryOperator::Eq
+            | BinaryOperator::Neq
+            | BinaryOperator::Gt
+            | BinaryOperator::Gte
+            | BinaryOperator::Lt
+            | BinaryOperator::Lte
+            | BinaryOperator::And
+            | BinaryOperator::Or => Ty::Primitive(FnArgPrimitive::Boolean),
+        }
+    }
+
+    fn type_of_unary(&self, expr: &'a UnaryExpression, depth: usize) -> Ty<'a> {
+        match expr.operator {
+            UnaryOperator::Neg => {
+                let ty = self.type_of_part(&expr.argument, depth + 1);
+                if let Ty::Number(_) = ty {
+                    ty
+                } else {
+                    Ty::Unknown
+                }
+            }
+            UnaryOperator::Not => Ty::Primitive(FnArgPrimitive::Boolean),
+        }
+    }
+
+    fn type_of_member(&self, member: &'a MemberExpression, depth: usize) -> Ty<'a> {
+        let LiteralIdentifier::Identifier(property) = &member.property else {
+            return Ty::Unknown;
+        };
+        if member.computed || depth > MAX_DEPTH {
+            return Ty::Unknown;
+        }
+        let object = match &member.object {
+            MemberObject::Identifier(id) => self.type_of_name(&id.name, depth),
+            MemberObject::MemberExpression(object) => self.type_of_member(object, depth + 1),
+        };
+        match object {
+            Ty::Object(properties) => properties
+                .iter()
+                .find(|p| p.key.name == property.name)
+                .map(|p| self.type_of(&p.value, depth + 1))
+                .unwrap_or(Ty::Unknown),
+            Ty::Record { fields, .. } => fields
+                .iter()
+                .find(|f| f.identifier.name == property.name)
+                .and_then(|f| f.type_.as_ref())
+                .map(|type_| self.declared(type_))
+                .unwrap_or(Ty::Unknown),
+            _ => Ty::Unknown,
+        }
+    }
+
+    /// The declared return type of a user-defined function.
+    fn return_type_of(&self, callee: &str) -> Ty<'a> {
+        self.lookup_function(callee)
+            .and_then(|func| func.return_type.as_ref())
+            .map(|type_| self.declared(type_))
+            .unwrap_or(Ty::Unknown)
+    }
+
+    /// Check a value of type `actual` against the `expected` type, adding any mismatches to `warnings`.
+    fn check(
+        &self,
+        expected: &FnArgType,
+        actual: Ty<'a>,
+        range: SourceRange,
+        depth: usize,
+        warnings: &mut Vec<CompilationError>,
+    ) {
+        if depth > MAX_DEPTH {
+            return;
+        }
+        match (expected, actual) {
+            (_, Ty::Unknown) => {}
+            (FnArgType::Primitive(expected), Ty::Primitive(actual)) if expected == &actual => {}
+            (FnArgType::Primitive(FnArgPrimitive::Number(suffix)), Ty::Number(actual)) => {
+                let expected = Quantity::from_suffix(*suffix);
+                if let (Some(e), Some(a)) = (expected.physical(), actual.physical()) {
+                    if expected != actual {
+                        warnings.push(CompilationError::warn(range, format!("Expected {e}, found {a}")));
+                    }
+                }
+            }
+            (FnArgType::Array(expected), Ty::Array(elements)) => {
+                let expected = FnArgType::Primitive(expected.clone());
+                for element in elements {
+                    let actual = self.type_of(element, depth + 1);
+                    self.check(&expected, actual, element.into(), depth + 1, warnings);
+                }
+            }
+            (FnArgType::Array(expected), Ty::ArrayOf(actual)) if same_primitive(expected, &actual) => {}
+            (FnArgType::Object { properties }, actual) => {
+                self.check_fields(expected, properties, actual, range, depth, warnings)
+            }
+            (FnArgType::Named { name }, actual) => {
+                // Unknown types are reported where they're used.
+                if let Some(decl) = self.lookup_type(&name.name) {
+                    self.check_fields(expected, &decl.fields, actual, range, depth, warnings)
+                }
+            }
+            (_, actual) => warnings.push(mismatch(expected, &actual, range)),
+        }
+    }
+
+    /// Check a value of type `actual` has the fields of an object or record type.
+    fn check_fields(
+        &self,
+        expected: &FnArgType,
+        fields: &[Parameter],
+        actual: Ty<'a>,
+        range: SourceRange,
+        depth: usize,
+        warnings: &mut Vec<CompilationError>,
+    ) {
+        let missing = |field: &Parameter| {
+            let message = match expected {
+                FnArgType::Named { name } => {
+                    format!("Missing field `{}` required by `{}`", field.identifier.name, name.name)
+                }
+                _ => format!("Missing field `{}`", field.identifier.name),
+            };
+            CompilationError::warn(range, message)
+        };
+        match actual {
+            Ty::Object(properties) => {
+                for field in fields {
+                    match properties.iter().find(|p| p.key.name == field.identifier.name) {
+                        Some(property) => {
+                            if let Some(type_) = &field.type_ {
+                                let actual = self.type_of(&property.value, depth + 1);
+                                self.check(type_, actual, (&property.value).into(), depth + 1, warnings);
+                            }
+                        }
+                        None if !field.optional() => warnings.push(missing(field)),
+                        None => {}
+                    }
+                }
+            }
+            Ty::Record {
+                fields: actual_fields, ..
+            } => {
+                for field in fields {
+                    match actual_fields
+                        .iter()
+                        .find(|f| f.identifier.name == field.identifier.name)
+                    {
+                        Some(actual_field) => {
+                            if let (Some(type_), Some(actual_type)) = (&field.type_, &actual_field.type_) {
+                                self.check(type_, self.declared(actual_type), range, depth + 1, warnings);
+                            }
+                        }
+                        None if !field.optional() => warnings.push(missing(field)),
+                        None => {}
+                    }
+                }
+            }
+            Ty::Unknown => {}
+            actual => warnings.push(mismatch(expected, &actual, range)),
+        }
+    }
+}
+
+fn literal_type<'a>(value: &LiteralValue) -> Ty<'a> {
+    match value {
+        LiteralValue::Number { suffix, .. } => Ty::Number(Quantity::from_suffix(*suffix)),
+        LiteralValue::String(_) => Ty::Primitive(FnArgPrimitive::String),
+        LiteralValue::Bool(_) => Ty::Primitive(FnArgPrimitive::Boolean),
+    }
+}
+
+/// Are two primitive types the same? The units of arrays of numbers aren't checked.
+fn same_primitive(a: &FnArgPrimitive, b: &FnArgPrimitive) -> bool {
+    match (a, b) {
+        (FnArgPrimitive::Number(_), FnArgPrimitive::Number(_)) => true,
+        _ => a == b,
+    }
+}
+
+fn mismatch(expected: &FnArgType, actual: &Ty, range: SourceRange) -> CompilationError {
+    CompilationError::warn(
+        range,
+        format!("Expected {}, found {}", describe_expected(expected), describe(actual)),
+    )
+}
+
+fn describe_primitive(primitive: &FnArgPrimitive) -> &'static str {
+    match primitive {
+        FnArgPrimitive::String => "a string",
+        FnArgPrimitive::Number(_) => "a number",
+        FnArgPrimitive::Boolean => "a boolean",
+        FnArgPrimitive::Tag => "a tag",
+        FnArgPrimitive::Sketch => "a sketch",
+        FnArgPrimitive::SketchSurface => "a sketch surface",
+        FnArgPrimitive::Solid => "a solid",
+    }
+}
+
+fn describe_expected(type_: &FnArgType) -> String {
+    match type_ {
+        FnArgType::Primitive(primitive) => describe_primitive(primitive).to_owned(),
+        FnArgType::Array(primitive) => format!("an array of `{primitive}`"),
+        FnArgType::Object { .. } => "an object".to_owned(),
+        FnArgType::Named { name } => format!("a `{}`", name.name),
+    }
+}
+
+fn describe(ty: &Ty) -> String {
+    match ty {
+        Ty::Unknown => "a value".to_owned(),
+        Ty::Primitive(primitive) => describe_primitive(primitive).to_owned(),
+        Ty::Number(_) => "a number".to_owned(),
+        Ty::Function => "a function".to_owned(),
+        Ty::Array(_) | Ty::ArrayOf(_) => "an array".to_owned(),
+        Ty::Object(_) | Ty::Record { name: None, .. } => "an object".to_owned(),
+        Ty::Record { name: Some(name), .. } => format!("a `{name}`"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[track_caller]
+    fn warnings(code: &str) -> Vec<String> {
+        let program = crate::parsing::top_level_parse(code).unwrap();
+        check_program(&program).into_iter().map(|e| e.message).collect()
+    }
+
+    #[track_caller]
+    fn assert_ok(code: &str) {
+        let errs = warnings(code);
+        assert!(errs.is_empty(), "found: {errs:#?}");
+    }
+
+    const BOLT: &str = "type Bolt = { diameter: number(mm), length: number(mm), label?: string }\n";
+
+    #[test]
+    fn record_argument_ok() {
+        assert_ok(&format!(
+            "{BOLT}
+fn area(bolt: Bolt): number {{
+  return bolt.diameter * bolt.length
+}}
+a = area({{ diameter = 5, length = 10 }})
+"
+        ));
+    }
+
+    #[test]
+    fn missing_field() {
+        let errs = warnings(&format!(
+            "{BOLT}
+fn area(bolt: Bolt) {{
+  return 1
+}}
+b = {{ diameter = 5 }}
+a = area(b)
+"
+        ));
+        assert_eq!(errs, vec!["Missing field `length` required by `Bolt`"]);
+    }
+
+    #[test]
+    fn wrong_field_type() {
+        let errs = warnings(&format!(
+            "{BOLT}
+fn area(bolt: Bolt) {{
+  return 1
+}}
+a = area({{ diameter = 5, length = 10, label = 3 }})
+"
+        ));
+        assert_eq!(errs, vec!["Expected a string, found a number"]);
+    }
+
+    #[test]
+    fn wrong_argument_type() {
+        let errs = warnings(
+            "\
+fn double(x: number) {
+  return x * 2
+}
+fn shout(@s: string) {
+  return s
+}
+a = double('two')
+b = shout(3 > 2)
+c = double([1, 2])
+",
+        );
+        assert_eq!(
+            errs,
+            vec![
+                "Expected a number, found a string",
+                "Expected a string, found a boolean",
+                "Expected a number, found an array",
+            ]
+        );
+    }
+
+    #[test]
+    fn wrong_argument_type_kw() {
+        let errs = warnings(
+            "\
+fn scale(@x: number, factor: number) {
+  return x * factor
+}
+a = scale('one', factor = 2)
+b = scale(1, factor = true)
+",
+        );
+        assert_eq!(
+            errs,
+            vec![
+                "Expected a number, found a string",
+                "Expected a number, found a boolean"
+            ]
+        );
+    }
+
+    #[test]
+    fn wrong_return_type() {
+        let errs = warnings(
+            "\
+fn name(): string {
+  return 5
+}
+fn count(xs: number[]): number {
+  return xs
+}
+",
+        );
+        assert_eq!(
+            errs,
+            vec!["Expected a string, found a number", "Expected a number, found an array"]
+        );
+    }
+
+    #[test]
+    fn follows_variables_and_return_types() {
+        let errs = warnings(
+            "\
+fn name(): string {
+  return 'bolt'
+}
+fn double(x: number) {
+  return x * 2
+}
+n = name()
+a = double(n)
+",
+        );
+        assert_eq!(errs, vec!["Expected a number, found a string"]);
+    }
+
+    #[test]
+    fn record_passed_through() {
+        let errs = warnings(
+            "\
+type Bolt = { diameter: number, length: number }
+type Washer = { diameter: number }
+fn area(bolt: Bolt) {
+  return 1
+}
+fn fits(bolt: Bolt, washer: Washer) {
+  a = area(bolt)
+  return area(washer)
+}
+",
+        );
+        assert_eq!(errs, vec!["Missing field `length` required by `Bolt`"]);
+    }
+
+    #[test]
+    fn unknown_type() {
+        let errs = warnings(
+            "\
+fn f(x: Nut) {
+  return x
+}
+",
+        );
+        assert_eq!(errs, vec!["Unknown type `Nut`"]);
+    }
+
+    #[test]
+    fn duplicate_type() {
+        let errs = warnings(
+            "\
+type Bolt = { diameter: number }
+type Bolt = { length: number }
+",
+        );
+        assert_eq!(errs, vec!["The type `Bolt` is declared more than once"]);
+    }
+
+    #[test]
+    fn untyped_values_are_not_reported() {
+        assert_ok(
+            "\
+fn double(x: number) {
+  return x * 2
+}
+fn id(x) {
+  return x
+}
+a = double(id('two'))
+b = double(startSketchOn('XY'))
+c = double(-a)
+",
+        );
+    }
+
+    #[test]
+    fn recursive_types_terminate() {
+        assert_ok(
+            "\
+type Chain = { next?: Chain }
+fn f(a: Chain, b: Chain) {
+  return f(b, a)
+}
+",
+        );
+    }
+
+    #[test]
+    fn incompatible_units() {
+        let errs = warnings(
+            "\
+a = 10mm + 90deg
+b = 10mm * 10mm > 5mm
+c = 1inch - 2mm + 3
+d = 2rad == 10
+",
+        );
+        assert_eq!(
+            errs,
+            vec![
+                "Cannot add a length and an angle",
+                "Cannot compare an area and a length"
+            ]
+        );
+    }
+
+    #[test]
+    fn units_through_variables_and_params() {
+        let errs = warnings(
+            "\
+fn extend(x: number(mm)) {
+  return x + 45deg
+}
+angle = 30deg
+e = extend(angle)
+ratio = 10mm / 2mm
+f = ratio + 1deg
+",
+        );
+        assert_eq!(
+            errs,
+            vec!["Cannot add a length and an angle", "Expected a length, found an angle"]
+        );
+    }
+
+    #[test]
+    fn trig_needs_angles() {
+        let errs = warnings(
+            "\
+a = cos(30deg) + sin(0.5) + tan(-1rad)
+width = 10mm
+b = sin(width)
+",
+        );
+        assert_eq!(errs, vec!["`sin` expects an angle, found a length"]);
+    }
+
+    #[test]
+    fn params_shadow_outer_names() {
+        assert_ok(
+            "\
+x = 'outer'
+fn double(x: number) {
+  return x * 2
+}
+fn f(x) {
+  return double(x)
+}
+",
+        );
+    }
+}
@@ -278,6 +278,11 @@ pub struct ModelingSettings {
     /// Whether or not to show a scale grid in the 3D modeling view
     #[serde(default, alias = "showScaleGrid", skip_serializing_if = "is_default")]
     pub show_scale_grid: bool,
+    /// Whether to store the results of executing imported modules in the
+    /// project's `.kcl-cache` directory, so they can be reused when the
+    /// project is opened again.
+    #[serde(default, alias = "cacheImports", skip_serializing_if = "is_default")]
+    pub cache_imports: bool,
 }
 
 #[derive(Debug, Copy, Clone, Deserialize, Serialize, JsonSchema, ts_rs::TS, PartialEq, Eq)]
@@ -619,6 +624,7 @@ textWrapping = true
                         show_debug_panel: true,
                         enable_ssao: false.into(),
                         show_scale_grid: false,
+                        cache_imports: false,
                     },
                     text_editor: TextEditorSettings {
                         text_wrapping: true.into(),
@@ -682,6 +688,7 @@ includeSettings = false
                         show_debug_panel: true,
                         enable_ssao: true.into(),
                         show_scale_grid: false,
+                        cache_imports: false,
                     },
                     text_editor: TextEditorSettings {
                         text_wrapping: false.into(),
@@ -750,6 +757,7 @@ defaultProjectName = "projects-$nnn"
                         show_debug_panel: true,
                         enable_ssao: true.into(),
                         show_scale_grid: false,
+                        cache_imports: false,
                     },
                     text_editor: TextEditorSettings {
                         text_wrapping: false.into(),
@@ -830,6 +838,7 @@ projectDirectory = "/Users/macinatormax/Documents/kittycad-modeling-projects""#;
                         show_debug_panel: false,
                         enable_ssao: true.into(),
                         show_scale_grid: false,
+                        cache_imports: false,
                     },
                     text_editor: TextEditorSettings {
                         text_wrapping: true.into(),
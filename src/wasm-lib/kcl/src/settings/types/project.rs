@@ -1,14 +1,19 @@
 //! Types specific for modeling-app projects.
 
 use anyhow::Result;
+use indexmap::IndexMap;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
-use crate::settings::types::{
-    AppColor, AppSettings, AppTheme, CommandBarSettings, ModelingSettings, TextEditorSettings,
+use crate::{
+    lint::LintLevel,
+    settings::types::{AppColor, AppSettings, AppTheme, CommandBarSettings, ModelingSettings, TextEditorSettings},
 };
 
+/// The name of the file which holds a project's configuration, in the project's directory.
+pub const PROJECT_SETTINGS_FILE_NAME: &str = "project.toml";
+
 /// High level project configuration.
 #[derive(Debug, Default, Clone, Deserialize, Serialize, JsonSchema, ts_rs::TS, PartialEq, Validate)]
 #[ts(export)]
@@ -74,6 +79,28 @@ pub struct PerProjectSettings {
     #[serde(default, alias = "commandBar")]
     #[validate(nested)]
     pub command_bar: CommandBarSettings,
+    /// Settings that affect which lints are checked, and how seriously.
+    #[serde(default)]
+    #[validate(nested)]
+    pub lint: LintSettings,
+}
+
+/// Settings that affect the behavior of the KCL linter.
+#[derive(Debug, Default, Clone, Deserialize, Serialize, JsonSchema, ts_rs::TS, PartialEq, Eq, Validate)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub struct LintSettings {
+    /// The level of each lint rule, by its code, e.g. `Z0001 = "off"`.
+    /// Rules which are not listed are checked at their default level.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub rules: IndexMap<String, LintLevel>,
+}
+
+impl LintSettings {
+    /// The level the project has set for a lint rule, if any.
+    pub fn level(&self, code: &str) -> Option<LintLevel> {
+        self.rules.get(code).copied()
+    }
 }
 
 #[cfg(test)]
@@ -81,10 +108,13 @@ mod tests {
     use pretty_assertions::assert_eq;
 
     use super::{
-        AppSettings, AppTheme, CommandBarSettings, ModelingSettings, PerProjectSettings, ProjectConfiguration,
-        TextEditorSettings,
+        AppSettings, AppTheme, CommandBarSettings, LintSettings, ModelingSettings, PerProjectSettings,
+        ProjectConfiguration, TextEditorSettings,
+    };
+    use crate::{
+        lint::LintLevel,
+        settings::types::{AppearanceSettings, UnitLength},
     };
-    use crate::settings::types::{AppearanceSettings, UnitLength};
 
     #[test]
     // Test that we can deserialize a project file from the old format.
@@ -135,6 +165,7 @@ includeSettings = false
                         show_debug_panel: true,
                         enable_ssao: true.into(),
                         show_scale_grid: false,
+                        cache_imports: false,
                     },
                     text_editor: TextEditorSettings {
                         text_wrapping: false.into(),
@@ -143,6 +174,7 @@ includeSettings = false
                     command_bar: CommandBarSettings {
                         include_settings: false.into()
                     },
+                    lint: Default::default(),
                 }
             }
         );
@@ -166,6 +198,8 @@ includeSettings = false
 [settings.text_editor]
 
 [settings.command_bar]
+
+[settings.lint]
 "#
         );
 
@@ -173,6 +207,29 @@ includeSettings = false
         assert_eq!(parsed, ProjectConfiguration::default());
     }
 
+    #[test]
+    fn test_project_settings_lint_levels() {
+        let settings_file = r#"[settings.lint.rules]
+Z0001 = "off"
+Z0003 = "error"
+"#;
+
+        let parsed = ProjectConfiguration::backwards_compatible_toml_parse(settings_file).unwrap();
+        assert_eq!(
+            parsed.settings.lint,
+            LintSettings {
+                rules: [
+                    ("Z0001".to_owned(), LintLevel::Off),
+                    ("Z0003".to_owned(), LintLevel::Error)
+                ]
+                .into_iter()
+                .collect(),
+            }
+        );
+        assert_eq!(parsed.settings.lint.level("Z0001"), Some(LintLevel::Off));
+        assert_eq!(parsed.settings.lint.level("Z0002"), None);
+    }
+
     #[test]
     fn test_project_settings_color_validation_error() {
         let settings_file = r#"[settings.app.appearance]
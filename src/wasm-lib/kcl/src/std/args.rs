@@ -405,6 +405,7 @@ impl Args {
         let mut numbers = numbers.into_iter();
         let (a, ta) = numbers.next().unwrap();
         let (b, tb) = numbers.next().unwrap();
+        let b = tb.convert_to(b, &ta);
         let ty = ta.combine(&tb);
         Ok((a, b, ty))
     }
@@ -1260,7 +1261,7 @@ impl<'a> FromKclValue<'a> for crate::execution::EdgeCut {
 }
 
 macro_rules! impl_from_kcl_for_vec {
-    ($typ:path) => {
+    ($typ:ty) => {
         impl<'a> FromKclValue<'a> for Vec<$typ> {
             fn from_kcl_val(arg: &'a KclValue) -> Option<Self> {
                 arg.as_array()?
@@ -1278,6 +1279,8 @@ impl_from_kcl_for_vec!(crate::execution::Metadata);
 impl_from_kcl_for_vec!(super::fillet::EdgeReference);
 impl_from_kcl_for_vec!(ExtrudeSurface);
 impl_from_kcl_for_vec!(Sketch);
+impl_from_kcl_for_vec!(f64);
+impl_from_kcl_for_vec!([f64; 2]);
 
 impl<'a> FromKclValue<'a> for SourceRange {
     fn from_kcl_val(arg: &'a KclValue) -> Option<Self> {
@@ -1384,6 +1387,16 @@ impl<'a> FromKclValue<'a> for super::axis_or_reference::Axis2dOrEdgeReference {
     }
 }
 
+impl<'a> FromKclValue<'a> for super::mirror::MirrorPlane {
+    fn from_kcl_val(arg: &'a KclValue) -> Option<Self> {
+        let case1 = super::sketch::PlaneData::from_kcl_val;
+        let case2 = TagIdentifier::from_kcl_val;
+        case1(arg)
+            .map(Self::Plane)
+            .or_else(|| case2(arg).map(Box::new).map(Self::Face))
+    }
+}
+
 impl<'a> FromKclValue<'a> for super::axis_or_reference::Axis3dOrEdgeReference {
     fn from_kcl_val(arg: &'a KclValue) -> Option<Self> {
         let case1 = super::axis_or_reference::AxisAndOrigin3d::from_kcl_val;
@@ -1579,6 +1592,15 @@ impl<'a> FromKclValue<'a> for SolidSet {
     }
 }
 
+impl<'a> FromKclValue<'a> for super::transform::SolidOrSketchSet {
+    fn from_kcl_val(arg: &'a KclValue) -> Option<Self> {
+        if let Some(solids) = SolidSet::from_kcl_val(arg) {
+            return Some(Self::Solids(solids));
+        }
+        SketchSet::from_kcl_val(arg).map(Self::Sketches)
+    }
+}
+
 impl<'a> FromKclValue<'a> for SketchOrSurface {
     fn from_kcl_val(arg: &'a KclValue) -> Option<Self> {
         match arg {
@@ -14,18 +14,19 @@ use uuid::Uuid;
 use crate::{
     errors::{KclError, KclErrorDetails},
     execution::{
-        ArtifactId, ExecState, ExtrudeSurface, GeoMeta, KclValue, Path, Sketch, SketchSet, SketchSurface, Solid,
-        SolidSet,
+        ArtifactId, ExecState, ExtrudeSurface, GeoMeta, KclValue, Path, Point3d, Sketch, SketchSet, SketchSurface,
+        Solid, SolidSet,
     },
-    std::Args,
+    std::{transform::Transform, Args},
 };
 
 /// Extrudes by a given amount.
 pub async fn extrude(exec_state: &mut ExecState, args: Args) -> Result<KclValue, KclError> {
     let sketch_set = args.get_unlabeled_kw_arg("sketch_set")?;
     let length = args.get_kw_arg("length")?;
+    let symmetric = args.get_kw_arg_opt("symmetric")?;
 
-    let result = inner_extrude(sketch_set, length, exec_state, args).await?;
+    let result = inner_extrude(sketch_set, length, symmetric, exec_state, args).await?;
 
     Ok(result.into())
 }
@@ -76,6 +77,9 @@ pub async fn extrude(exec_state: &mut ExecState, args: Args) -> Result<KclValue,
 ///
 /// example = extrude(exampleSketch, length = 10)
 /// ```
+///
+/// With `symmetric = true`, the sketch is extruded half the length to each side of its plane,
+/// rather than the whole length in front of it.
 #[stdlib {
     name = "extrude",
     feature_tree_operation = true,
@@ -84,11 +88,13 @@ pub async fn extrude(exec_state: &mut ExecState, args: Args) -> Result<KclValue,
     args = {
         sketch_set = { docs = "Which sketches should be extruded"},
         length = { docs = "How far to extrude the given sketches"},
+        symmetric = { docs = "If true, extrude the same distance to both sides of the sketch plane, for a total of `length`. Defaults to false." },
     }
 }]
 async fn inner_extrude(
     sketch_set: SketchSet,
     length: f64,
+    symmetric: Option<bool>,
     exec_state: &mut ExecState,
     args: Args,
 ) -> Result<SolidSet, KclError> {
@@ -136,7 +142,16 @@ async fn inner_extrude(
             ModelingCmd::SketchModeDisable(mcmd::SketchModeDisable::default()),
         )
         .await?;
-        solids.push(do_post_extrude(sketch.clone(), id.into(), length, exec_state, args.clone()).await?);
+        let mut solid = do_post_extrude(sketch.clone(), id.into(), length, exec_state, args.clone()).await?;
+        if symmetric.unwrap_or(false) {
+            // Center the extrusion on the sketch plane.
+            let normal = sketch.on.z_axis();
+            let offset = -length / 2.0;
+            Transform::Translate(Point3d::new(normal.x * offset, normal.y * offset, normal.z * offset))
+                .apply_to_solid(&mut solid, exec_state, &args)
+                .await?;
+        }
+        solids.push(solid);
     }
 
     Ok(solids.into())
@@ -243,7 +258,8 @@ pub(crate) async fn do_post_extrude(
                     Path::Arc { .. }
                     | Path::TangentialArc { .. }
                     | Path::TangentialArcTo { .. }
-                    | Path::Circle { .. } => {
+                    | Path::Circle { .. }
+                    | Path::Bezier { .. } => {
                         let extrude_surface = ExtrudeSurface::ExtrudeArc(crate::execution::ExtrudeArc {
                             face_id: *actual_face_id,
                             tag: path.get_base().tag.clone(),
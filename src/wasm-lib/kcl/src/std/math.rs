@@ -6,7 +6,10 @@ use derive_docs::stdlib;
 use super::args::FromArgs;
 use crate::{
     errors::{KclError, KclErrorDetails},
-    execution::{kcl_value::NumericType, ExecState, KclValue},
+    execution::{
+        kcl_value::{NumericType, UnitType},
+        ExecState, KclValue, UnitAngle,
+    },
     std::Args,
 };
 
@@ -45,9 +48,19 @@ fn inner_rem(num: f64, divisor: f64) -> f64 {
     num % divisor
 }
 
+/// Get the angle passed to a trigonometric function, in radians. Numbers which aren't angles are
+/// taken to be in radians already; passing a length is reported by the type check, but still runs.
+fn get_angle_in_radians(args: &Args) -> Result<f64, KclError> {
+    let (num, ty) = args.get_number_with_type()?;
+    match ty {
+        NumericType::Known(UnitType::Angle(angle)) => Ok(angle.convert_to(num, UnitAngle::Radians)),
+        _ => Ok(num),
+    }
+}
+
 /// Compute the cosine of a number (in radians).
 pub async fn cos(_exec_state: &mut ExecState, args: Args) -> Result<KclValue, KclError> {
-    let num = args.get_number()?;
+    let num = get_angle_in_radians(&args)?;
     let result = inner_cos(num)?;
 
     Ok(args.make_user_val_from_f64_with_type(result, NumericType::count()))
@@ -77,7 +90,7 @@ fn inner_cos(num: f64) -> Result<f64, KclError> {
 
 /// Compute the sine of a number (in radians).
 pub async fn sin(_exec_state: &mut ExecState, args: Args) -> Result<KclValue, KclError> {
-    let num = args.get_number()?;
+    let num = get_angle_in_radians(&args)?;
     let result = inner_sin(num)?;
 
     Ok(args.make_user_val_from_f64_with_type(result, NumericType::count()))
@@ -107,7 +120,7 @@ fn inner_sin(num: f64) -> Result<f64, KclError> {
 
 /// Compute the tangent of a number (in radians).
 pub async fn tan(_exec_state: &mut ExecState, args: Args) -> Result<KclValue, KclError> {
-    let num = args.get_number()?;
+    let num = get_angle_in_radians(&args)?;
     let result = inner_tan(num)?;
 
     Ok(args.make_user_val_from_f64_with_type(result, NumericType::count()))
@@ -0,0 +1,526 @@
+//! Functions for measuring solids.
+
+use std::f64::consts::{FRAC_PI_2, TAU};
+
+use anyhow::Result;
+use derive_docs::stdlib;
+use kcmc::{
+    each_cmd as mcmd,
+    ok_response::OkModelingCmdResponse,
+    units::{UnitArea, UnitDensity, UnitMass, UnitVolume},
+    websocket::OkWebSocketResponseData,
+    ModelingCmd,
+};
+use kittycad_modeling_cmds as kcmc;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    errors::{KclError, KclErrorDetails},
+    execution::{
+        bezier_point,
+        kcl_value::{NumericType, UnitLen, UnitType},
+        ExecState, KclValue, Metadata, Path, Point3d, SketchSurface, Solid, SolidSet,
+    },
+    std::Args,
+};
+
+/// Returns the volume of solids.
+pub async fn volume(exec_state: &mut ExecState, args: Args) -> Result<KclValue, KclError> {
+    let solids = args.get_unlabeled_kw_arg("solids")?;
+
+    let volume = inner_volume(solids, exec_state, args.clone()).await?;
+    // There's no unit type for volumes.
+    Ok(args.make_user_val_from_f64_with_type(volume, NumericType::Unknown))
+}
+
+/// Compute the volume of one or more solids, in the cube of their length units.
+///
+/// Fillets and chamfers are applied at the end of the program, so they aren't included.
+///
+/// ```no_run
+/// cube = startSketchOn('XY')
+///   |> startProfileAt([0, 0], %)
+///   |> line(end = [10, 0])
+///   |> line(end = [0, 10])
+///   |> line(end = [-10, 0])
+///   |> close()
+///   |> extrude(length = 10)
+///
+/// assertLessThan(volume(cube), 1500, "the cube should be about 1000 cubic millimeters")
+/// ```
+#[stdlib {
+    name = "volume",
+    // Unpublished until the examples have run against the engine. The mock engine answers
+    // every mass property query with a stand-in value, so the examples' assertions haven't been
+    // checked against real measurements, and the docs need each example's render.
+    unpublished = true,
+    keywords = true,
+    unlabeled_first = true,
+    args = {
+        solids = { docs = "The solids to measure." },
+    }
+}]
+async fn inner_volume(solids: SolidSet, exec_state: &mut ExecState, args: Args) -> Result<f64, KclError> {
+    let units = units_of(&solids);
+    // The engine has no cubic millimeters.
+    let (output_unit, factor) = match units {
+        UnitLen::Mm => (UnitVolume::CubicCentimeters, 1000.0),
+        UnitLen::Cm => (UnitVolume::CubicCentimeters, 1.0),
+        UnitLen::M => (UnitVolume::CubicMeters, 1.0),
+        UnitLen::Inches => (UnitVolume::CubicInches, 1.0),
+        UnitLen::Feet => (UnitVolume::CubicFeet, 1.0),
+        UnitLen::Yards => (UnitVolume::CubicYards, 1.0),
+    };
+
+    let cmd = ModelingCmd::from(mcmd::Volume {
+        entity_ids: entity_ids(&solids),
+        output_unit,
+    });
+    match query(cmd, exec_state, &args).await? {
+        OkModelingCmdResponse::Volume(volume) => Ok(volume.volume * factor),
+        resp => Err(unexpected_response("Volume", resp, &args)),
+    }
+}
+
+/// Returns the surface area of solids.
+pub async fn surface_area(exec_state: &mut ExecState, args: Args) -> Result<KclValue, KclError> {
+    let solids: SolidSet = args.get_unlabeled_kw_arg("solids")?;
+    let units = units_of(&solids);
+
+    let area = inner_surface_area(solids, exec_state, args.clone()).await?;
+    Ok(args.make_user_val_from_f64_with_type(area, NumericType::Known(UnitType::Area(units))))
+}
+
+/// Compute the total area of the faces of one or more solids, in the square of their length units.
+///
+/// Fillets and chamfers are applied at the end of the program, so they aren't included.
+///
+/// ```no_run
+/// plate = startSketchOn('XY')
+///   |> startProfileAt([0, 0], %)
+///   |> line(end = [40, 0])
+///   |> line(end = [0, 20])
+///   |> line(end = [-40, 0])
+///   |> close()
+///   |> extrude(length = 2)
+///
+/// // Budget for the amount of paint needed.
+/// assertLessThan(surfaceArea(plate), 2000, "plate should need less than 2000 square millimeters of paint")
+/// ```
+#[stdlib {
+    name = "surfaceArea",
+    // Unpublished for the same reason as `volume`.
+    unpublished = true,
+    keywords = true,
+    unlabeled_first = true,
+    args = {
+        solids = { docs = "The solids to measure." },
+    }
+}]
+async fn inner_surface_area(solids: SolidSet, exec_state: &mut ExecState, args: Args) -> Result<f64, KclError> {
+    let output_unit = match units_of(&solids) {
+        UnitLen::Mm => UnitArea::SquareMillimeters,
+        UnitLen::Cm => UnitArea::SquareCentimeters,
+        UnitLen::M => UnitArea::SquareMeters,
+        UnitLen::Inches => UnitArea::SquareInches,
+        UnitLen::Feet => UnitArea::SquareFeet,
+        UnitLen::Yards => UnitArea::SquareYards,
+    };
+
+    let cmd = ModelingCmd::from(mcmd::SurfaceArea {
+        entity_ids: entity_ids(&solids),
+        output_unit,
+    });
+    match query(cmd, exec_state, &args).await? {
+        OkModelingCmdResponse::SurfaceArea(area) => Ok(area.surface_area),
+        resp => Err(unexpected_response("SurfaceArea", resp, &args)),
+    }
+}
+
+/// Returns the center of mass of solids.
+pub async fn center_of_mass(exec_state: &mut ExecState, args: Args) -> Result<KclValue, KclError> {
+    let solids: SolidSet = args.get_unlabeled_kw_arg("solids")?;
+    let units = units_of(&solids);
+
+    let center = inner_center_of_mass(solids, exec_state, args.clone()).await?;
+    Ok(point_value(center, units, &args))
+}
+
+/// Compute the center of mass of one or more solids, assuming they're all of the same uniform
+/// density.
+///
+/// Fillets and chamfers are applied at the end of the program, so they aren't included.
+///
+/// ```no_run
+/// arm = startSketchOn('XZ')
+///   |> startProfileAt([0, 0], %)
+///   |> line(end = [40, 0])
+///   |> line(end = [0, 5])
+///   |> line(end = [-40, 0])
+///   |> close()
+///   |> extrude(length = 5)
+///
+/// // Put a pivot under the arm's center of mass.
+/// center = centerOfMass(arm)
+/// pivot = startSketchOn('XY')
+///   |> circle({ center = [center[0], center[1]], radius = 2 }, %)
+///   |> extrude(length = -10)
+/// ```
+#[stdlib {
+    name = "centerOfMass",
+    // Unpublished for the same reason as `volume`.
+    unpublished = true,
+    keywords = true,
+    unlabeled_first = true,
+    args = {
+        solids = { docs = "The solids to measure." },
+    }
+}]
+async fn inner_center_of_mass(solids: SolidSet, exec_state: &mut ExecState, args: Args) -> Result<[f64; 3], KclError> {
+    let cmd = ModelingCmd::from(mcmd::CenterOfMass {
+        entity_ids: entity_ids(&solids),
+        output_unit: units_of(&solids).into(),
+    });
+    match query(cmd, exec_state, &args).await? {
+        OkModelingCmdResponse::CenterOfMass(center) => {
+            let center = center.center_of_mass;
+            Ok([center.x, center.y, center.z])
+        }
+        resp => Err(unexpected_response("CenterOfMass", resp, &args)),
+    }
+}
+
+/// Returns the mass of solids.
+pub async fn mass(exec_state: &mut ExecState, args: Args) -> Result<KclValue, KclError> {
+    let solids = args.get_unlabeled_kw_arg("solids")?;
+    let density = args.get_kw_arg("density")?;
+
+    let mass = inner_mass(solids, density, exec_state, args.clone()).await?;
+    // There's no unit type for masses.
+    Ok(args.make_user_val_from_f64_with_type(mass, NumericType::Unknown))
+}
+
+/// Compute the mass of one or more solids made of a material with the given density.
+///
+/// For solids in metric units the density is in kilograms per cubic meter and the mass is in
+/// kilograms. For solids in imperial units the density is in pounds per cubic foot and the mass is
+/// in pounds.
+///
+/// Fillets and chamfers are applied at the end of the program, so they aren't included.
+///
+/// ```no_run
+/// rod = startSketchOn('XY')
+///   |> circle({ center = [0, 0], radius = 10 }, %)
+///   |> extrude(length = 500)
+///
+/// // Aluminium is 2700 kg per cubic meter.
+/// assertLessThan(mass(rod, density = 2700), 2, "the rod should weigh less than two kilograms")
+/// ```
+#[stdlib {
+    name = "mass",
+    // Unpublished for the same reason as `volume`.
+    unpublished = true,
+    keywords = true,
+    unlabeled_first = true,
+    args = {
+        solids = { docs = "The solids to measure." },
+        density = { docs = "The density of the material the solids are made of." },
+    }
+}]
+async fn inner_mass(solids: SolidSet, density: f64, exec_state: &mut ExecState, args: Args) -> Result<f64, KclError> {
+    if density <= 0.0 {
+        return Err(KclError::Semantic(KclErrorDetails {
+            message: format!("Density must be greater than zero, but it was {density}"),
+            source_ranges: vec![args.source_range],
+        }));
+    }
+
+    let (material_density_unit, output_unit) = match units_of(&solids) {
+        UnitLen::Mm | UnitLen::Cm | UnitLen::M => (UnitDensity::KilogramsPerCubicMeter, UnitMass::Kilograms),
+        UnitLen::Inches | UnitLen::Feet | UnitLen::Yards => (UnitDensity::PoundsPerCubicFeet, UnitMass::Pounds),
+    };
+
+    let cmd = ModelingCmd::from(mcmd::Mass {
+        entity_ids: entity_ids(&solids),
+        material_density: density,
+        material_density_unit,
+        output_unit,
+    });
+    match query(cmd, exec_state, &args).await? {
+        OkModelingCmdResponse::Mass(mass) => Ok(mass.mass),
+        resp => Err(unexpected_response("Mass", resp, &args)),
+    }
+}
+
+/// An axis-aligned box which contains some geometry.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, ts_rs::TS, JsonSchema)]
+#[ts(export)]
+#[serde(rename_all = "camelCase")]
+pub struct Bounds {
+    /// The corner with the smallest x, y and z coordinates.
+    pub min: [f64; 3],
+    /// The corner with the largest x, y and z coordinates.
+    pub max: [f64; 3],
+}
+
+/// Returns the bounding box of solids.
+pub async fn bounding_box(exec_state: &mut ExecState, args: Args) -> Result<KclValue, KclError> {
+    let solids: SolidSet = args.get_unlabeled_kw_arg("solids")?;
+    let units = units_of(&solids);
+
+    let bounds = inner_bounding_box(solids, exec_state, args.clone()).await?;
+    let meta = vec![Metadata {
+        source_range: args.source_range,
+    }];
+    Ok(KclValue::Object {
+        value: [
+            ("min".to_owned(), point_value(bounds.min, units, &args)),
+            ("max".to_owned(), point_value(bounds.max, units, &args)),
+        ]
+        .into_iter()
+        .collect(),
+        meta,
+    })
+}
+
+/// Compute the smallest box, aligned with the x, y and z axes, which contains one or more solids.
+///
+/// The box is computed from the profiles the solids were extruded from, so it's only available
+/// for extrusions of sketches on planes. Fillets and chamfers only ever remove material, so the
+/// box contains them too, though it may not be the smallest box which does.
+///
+/// ```no_run
+/// bracket = startSketchOn('XZ')
+///   |> startProfileAt([0, 0], %)
+///   |> line(end = [20, 0])
+///   |> line(end = [0, 4])
+///   |> line(end = [-16, 0])
+///   |> line(end = [0, 16])
+///   |> line(end = [-4, 0])
+///   |> close()
+///   |> extrude(length = 10)
+///
+/// // Check the bracket fits in its packaging.
+/// bounds = boundingBox(bracket)
+/// assertLessThan(bounds.max[2] - bounds.min[2], 25, "bracket should be less than 25mm tall")
+/// ```
+#[stdlib {
+    name = "boundingBox",
+    // Unpublished until the example has been rendered by the engine, which the docs embed.
+    // The box itself is computed in KCL, so the example test already checks it.
+    unpublished = true,
+    keywords = true,
+    unlabeled_first = true,
+    args = {
+        solids = { docs = "The solids to measure." },
+    }
+}]
+async fn inner_bounding_box(solids: SolidSet, _exec_state: &mut ExecState, args: Args) -> Result<Bounds, KclError> {
+    let units = units_of(&solids);
+    let solids: Vec<Box<Solid>> = solids.into();
+
+    let mut bounds: Option<Bounds> = None;
+    for solid in &solids {
+        for point in extrusion_points(solid, &args)? {
+            let point = [point.x, point.y, point.z].map(|c| solid.units.convert_to(c, units));
+            let b = bounds.get_or_insert(Bounds { min: point, max: point });
+            b.min = std::array::from_fn(|i| b.min[i].min(point[i]));
+            b.max = std::array::from_fn(|i| b.max[i].max(point[i]));
+        }
+    }
+
+    bounds.ok_or_else(|| {
+        KclError::Semantic(KclErrorDetails {
+            message: "Can't compute the bounding box of an empty set of solids".to_owned(),
+            source_ranges: vec![args.source_range],
+        })
+    })
+}
+
+/// Points whose bounding box is the bounding box of the extruded solid.
+fn extrusion_points(solid: &Solid, args: &Args) -> Result<Vec<Point3d>, KclError> {
+    let SketchSurface::Plane(plane) = &solid.sketch.on else {
+        return Err(KclError::Semantic(KclErrorDetails {
+            message: "The bounding box of a solid sketched on a face isn't available yet".to_owned(),
+            source_ranges: vec![args.source_range],
+        }));
+    };
+    // Revolves, sweeps and lofts have no height.
+    if solid.height == 0.0 {
+        return Err(KclError::Semantic(KclErrorDetails {
+            message: "The bounding box is only available for extruded solids".to_owned(),
+            source_ranges: vec![args.source_range],
+        }));
+    }
+
+    let mut profile = vec![solid.sketch.start.from];
+    for path in &solid.sketch.paths {
+        profile.extend(path_extremes(path));
+    }
+
+    let at = |p: [f64; 2], offset: f64| {
+        Point3d::new(
+            plane.origin.x + p[0] * plane.x_axis.x + p[1] * plane.y_axis.x + offset * plane.z_axis.x,
+            plane.origin.y + p[0] * plane.x_axis.y + p[1] * plane.y_axis.y + offset * plane.z_axis.y,
+            plane.origin.z + p[0] * plane.x_axis.z + p[1] * plane.y_axis.z + offset * plane.z_axis.z,
+        )
+    };
+    Ok(profile
+        .into_iter()
+        .flat_map(|p| [at(p, 0.0), at(p, solid.height)])
+        .collect())
+}
+
+/// Points on the path whose bounding box is the bounding box of the path.
+fn path_extremes(path: &Path) -> Vec<[f64; 2]> {
+    let base = path.get_base();
+    let mut points = vec![base.from, base.to];
+    match path {
+        Path::Circle { center, radius, .. } => {
+            points.extend(
+                [0.0, FRAC_PI_2, 2.0 * FRAC_PI_2, 3.0 * FRAC_PI_2].map(|angle| at_angle(*center, *radius, angle)),
+            );
+        }
+        Path::Arc {
+            center, radius, ccw, ..
+        } => points.extend(arc_extremes(*center, *radius, base.from, base.to, *ccw)),
+        Path::TangentialArc { center, ccw, .. } | Path::TangentialArcTo { center, ccw, .. } => {
+            let radius = (base.from[0] - center[0]).hypot(base.from[1] - center[1]);
+            points.extend(arc_extremes(*center, radius, base.from, base.to, *ccw));
+        }
+        Path::Bezier { control1, control2, .. } => {
+            points.extend(bezier_extremes([base.from, *control1, *control2, base.to]));
+        }
+        Path::ToPoint { .. } | Path::Horizontal { .. } | Path::AngledLineTo { .. } | Path::Base { .. } => {}
+    }
+    points
+}
+
+/// The points where an arc is furthest along the x and y axes, besides its ends.
+fn arc_extremes(center: [f64; 2], radius: f64, from: [f64; 2], to: [f64; 2], ccw: bool) -> Vec<[f64; 2]> {
+    let start = (from[1] - center[1]).atan2(from[0] - center[0]);
+    let mut end = (to[1] - center[1]).atan2(to[0] - center[0]);
+    // An arc which ends where it starts is a whole circle.
+    if ccw {
+        while end <= start {
+            end += TAU;
+        }
+    } else {
+        while end >= start {
+            end -= TAU;
+        }
+    }
+    let (low, high) = if ccw { (start, end) } else { (end, start) };
+
+    let first = (low / FRAC_PI_2).ceil() as i64;
+    let last = (high / FRAC_PI_2).floor() as i64;
+    (first..=last)
+        .map(|quarter| at_angle(center, radius, quarter as f64 * FRAC_PI_2))
+        .collect()
+}
+
+/// The points where a cubic Bézier curve turns back along the x or y axis, besides its ends.
+fn bezier_extremes(controls: [[f64; 2]; 4]) -> Vec<[f64; 2]> {
+    let mut params = Vec::new();
+    for axis in 0..2 {
+        let [p0, p1, p2, p3] = controls.map(|p| p[axis]);
+        // The derivative is a quadratic a t² + b t + c.
+        let a = 3.0 * (-p0 + 3.0 * p1 - 3.0 * p2 + p3);
+        let b = 6.0 * (p0 - 2.0 * p1 + p2);
+        let c = 3.0 * (p1 - p0);
+        if a.abs() < 1e-12 {
+            if b.abs() > 1e-12 {
+                params.push(-c / b);
+            }
+            continue;
+        }
+        let discriminant = b * b - 4.0 * a * c;
+        if discriminant >= 0.0 {
+            let root = discriminant.sqrt();
+            params.push((-b + root) / (2.0 * a));
+            params.push((-b - root) / (2.0 * a));
+        }
+    }
+    params
+        .into_iter()
+        .filter(|t| (0.0..=1.0).contains(t))
+        .map(|t| bezier_point(controls, t))
+        .collect()
+}
+
+fn at_angle(center: [f64; 2], radius: f64, angle: f64) -> [f64; 2] {
+    [center[0] + radius * angle.cos(), center[1] + radius * angle.sin()]
+}
+
+/// The units measurements of the solids are given in.
+fn units_of(solids: &SolidSet) -> UnitLen {
+    match solids {
+        SolidSet::Solid(solid) => solid.units,
+        SolidSet::Solids(solids) => solids.first().map(|solid| solid.units).unwrap_or_default(),
+    }
+}
+
+fn entity_ids(solids: &SolidSet) -> Vec<uuid::Uuid> {
+    match solids {
+        SolidSet::Solid(solid) => vec![solid.id],
+        SolidSet::Solids(solids) => solids.iter().map(|solid| solid.id).collect(),
+    }
+}
+
+fn point_value(p: [f64; 3], units: UnitLen, args: &Args) -> KclValue {
+    KclValue::Array {
+        value: p
+            .into_iter()
+            .map(|c| args.make_user_val_from_f64_with_type(c, units.into()))
+            .collect(),
+        meta: vec![Metadata {
+            source_range: args.source_range,
+        }],
+    }
+}
+
+/// Send a query to the engine and wait for its answer.
+async fn query(cmd: ModelingCmd, exec_state: &mut ExecState, args: &Args) -> Result<OkModelingCmdResponse, KclError> {
+    // Imported modules are executed without sending their geometry to the engine, so there's
+    // nothing to measure. Answer with the mock engine's stand-ins, so the module can still be
+    // imported.
+    if args.ctx.no_engine_commands() {
+        return Ok(crate::engine::conn_mock::mock_response(&cmd));
+    }
+
+    match args.send_modeling_cmd(exec_state.next_uuid(), cmd).await? {
+        OkWebSocketResponseData::Modeling { modeling_response } => Ok(modeling_response),
+        resp => Err(KclError::Engine(KclErrorDetails {
+            message: format!("Expected a modeling response from the engine, found {:?}", resp),
+            source_ranges: vec![args.source_range],
+        })),
+    }
+}
+
+fn unexpected_response(cmd_name: &str, resp: OkModelingCmdResponse, args: &Args) -> KclError {
+    KclError::Engine(KclErrorDetails {
+        message: format!("mcmd::{cmd_name} response was not as expected: {:?}", resp),
+        source_ranges: vec![args.source_range],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arc_extremes_in_sweep() {
+        // A quarter circle from 45° to 135° passes through the top of the circle.
+        let from = at_angle([0.0, 0.0], 1.0, FRAC_PI_2 / 2.0);
+        let to = at_angle([0.0, 0.0], 1.0, 3.0 * FRAC_PI_2 / 2.0);
+        let extremes = arc_extremes([0.0, 0.0], 1.0, from, to, true);
+        assert_eq!(extremes.len(), 1);
+        assert!((extremes[0][1] - 1.0).abs() < 1e-9);
+
+        // The other way round it passes through the other three.
+        assert_eq!(arc_extremes([0.0, 0.0], 1.0, from, to, false).len(), 3);
+        // A whole circle.
+        assert_eq!(arc_extremes([0.0, 0.0], 1.0, from, from, true).len(), 4);
+    }
+}
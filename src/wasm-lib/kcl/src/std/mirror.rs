@@ -2,15 +2,24 @@
 
 use anyhow::Result;
 use derive_docs::stdlib;
-use kcmc::{each_cmd as mcmd, ModelingCmd};
+use kcmc::{
+    each_cmd as mcmd,
+    ok_response::OkModelingCmdResponse,
+    shared::{Angle, OriginType, Rotation, Transform},
+    websocket::OkWebSocketResponseData,
+    ModelingCmd,
+};
 use kittycad_modeling_cmds::{self as kcmc};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    errors::KclError,
-    execution::{ExecState, KclValue, Sketch, SketchSet},
-    std::{axis_or_reference::Axis2dOrEdgeReference, Args},
+    errors::{KclError, KclErrorDetails},
+    execution::{
+        ExecState, KclValue, Plane, PlaneType, Point3d, Sketch, SketchSet, SketchSurface, Solid, SolidSet,
+        TagIdentifier,
+    },
+    std::{axis_or_reference::Axis2dOrEdgeReference, sketch::PlaneData, Args},
 };
 
 /// Data for a mirror.
@@ -146,3 +155,308 @@ async fn inner_mirror_2d(
 
     Ok(starting_sketches)
 }
+
+/// A plane to mirror across.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, ts_rs::TS, JsonSchema)]
+#[ts(export)]
+#[serde(untagged)]
+pub enum MirrorPlane {
+    /// A standard plane, or a custom one like an offset plane.
+    Plane(PlaneData),
+    /// The plane of a tagged planar face.
+    Face(Box<TagIdentifier>),
+}
+
+/// Mirror solids.
+pub async fn mirror_3d(exec_state: &mut ExecState, args: Args) -> Result<KclValue, KclError> {
+    let solids = args.get_unlabeled_kw_arg("solids")?;
+    let plane = args.get_kw_arg("plane")?;
+
+    let solids = inner_mirror_3d(solids, plane, exec_state, args).await?;
+    Ok(solids.into())
+}
+
+/// Make mirrored copies of one or more solids, across a plane.
+///
+/// The plane can be a standard plane like `'XZ'`, an offset plane or a tagged planar face.
+/// The originals are left in place, so a symmetric part is the original and its copy.
+///
+/// The copy has none of the original's tags, since the engine doesn't say which of the copy's
+/// faces and edges match the original's. Fillet and chamfer the original before mirroring it,
+/// and the copy will have them too.
+///
+/// ```no_run
+/// // Mirror a filleted bracket across the YZ plane.
+/// bracket = startSketchOn('XY')
+///   |> startProfileAt([5, 0], %)
+///   |> line(end = [10, 0])
+///   |> line(end = [0, 10], tag = $seg01)
+///   |> line(end = [-10, 0])
+///   |> close()
+///   |> extrude(length = 5)
+///   |> fillet({ radius = 1, tags = [getNextAdjacentEdge(seg01)] }, %)
+///
+/// mirror3d(bracket, plane = 'YZ')
+/// ```
+///
+/// ```no_run
+/// // Mirror across an offset plane, to make two copies of a part facing each other.
+/// part = startSketchOn('XY')
+///   |> circle({ center = [0, 0], radius = 5 }, %)
+///   |> extrude(length = 3)
+///
+/// mirror3d(part, plane = offsetPlane('XY', offset = 10))
+/// ```
+///
+/// ```no_run
+/// // Mirror a wedge across one of its own faces.
+/// wedge = startSketchOn('XZ')
+///   |> startProfileAt([0, 0], %)
+///   |> line(end = [10, 0])
+///   |> line(end = [0, 5], tag = $side)
+///   |> close()
+///   |> extrude(length = 10)
+///
+/// mirror3d(wedge, plane = side)
+/// ```
+#[stdlib {
+    name = "mirror3d",
+    // Unpublished until the examples have run against the engine. The mock engine says every
+    // face is the XY plane, so mirroring across a tagged face hasn't been checked, and the docs
+    // need each example's render.
+    unpublished = true,
+    keywords = true,
+    unlabeled_first = true,
+    args = {
+        solids = { docs = "The solids to mirror." },
+        plane = { docs = "The plane to mirror across: a standard plane like `'XZ'`, a custom plane or a tagged planar face." },
+    }
+}]
+async fn inner_mirror_3d(
+    solids: SolidSet,
+    plane: MirrorPlane,
+    exec_state: &mut ExecState,
+    args: Args,
+) -> Result<Vec<Box<Solid>>, KclError> {
+    let reflection = match plane {
+        MirrorPlane::Plane(data) => {
+            let plane = Plane::from_plane_data(data, exec_state);
+            Reflection::new(plane.origin, plane.z_axis)
+        }
+        MirrorPlane::Face(tag) => face_reflection(&tag, exec_state, &args).await?,
+    };
+
+    // Flush the fillets and chamfers of these solids, so they're mirrored too.
+    args.flush_batch_for_solid_set(exec_state, solids.clone().into())
+        .await?;
+
+    let solids: Vec<Box<Solid>> = solids.into();
+    let mut mirrored = Vec::with_capacity(solids.len());
+    for solid in solids {
+        let id = if args.ctx.no_engine_commands() {
+            exec_state.next_uuid()
+        } else {
+            send_reflection(&reflection, &solid, exec_state, &args).await?
+        };
+        mirrored.push(Box::new(reflection.solid(&solid, id)));
+    }
+    Ok(mirrored)
+}
+
+/// Copy a solid across a plane in the engine, returning the copy's id.
+async fn send_reflection(
+    reflection: &Reflection,
+    solid: &Solid,
+    exec_state: &mut ExecState,
+    args: &Args,
+) -> Result<uuid::Uuid, KclError> {
+    let resp = args
+        .send_modeling_cmd(
+            exec_state.next_uuid(),
+            ModelingCmd::from(mcmd::EntityLinearPatternTransform {
+                entity_id: solid.id,
+                transform: Default::default(),
+                transforms: vec![reflection.transforms()],
+            }),
+        )
+        .await?;
+
+    let OkWebSocketResponseData::Modeling {
+        modeling_response: OkModelingCmdResponse::EntityLinearPatternTransform(pattern_info),
+    } = &resp
+    else {
+        return Err(KclError::Engine(KclErrorDetails {
+            message: format!("EntityLinearPattern response was not as expected: {:?}", resp),
+            source_ranges: vec![args.source_range],
+        }));
+    };
+    pattern_info.entity_ids.first().copied().ok_or_else(|| {
+        KclError::Engine(KclErrorDetails {
+            message: "The engine didn't make a mirrored copy of the solid".to_owned(),
+            source_ranges: vec![args.source_range],
+        })
+    })
+}
+
+/// The reflection across the plane of a tagged face.
+async fn face_reflection(tag: &TagIdentifier, exec_state: &mut ExecState, args: &Args) -> Result<Reflection, KclError> {
+    // Imported modules are executed without sending their geometry to the engine.
+    if args.ctx.engine.execution_kind().is_isolated() {
+        return Err(KclError::Semantic(KclErrorDetails {
+            message: "`mirror3d` can't mirror across a face in a module which is imported".to_owned(),
+            source_ranges: vec![args.source_range],
+        }));
+    }
+
+    let face_id = args.get_adjacent_face_to_tag(exec_state, tag, true).await?;
+    let resp = args
+        .send_modeling_cmd(
+            exec_state.next_uuid(),
+            ModelingCmd::from(mcmd::FaceIsPlanar { object_id: face_id }),
+        )
+        .await?;
+    let OkWebSocketResponseData::Modeling {
+        modeling_response:
+            OkModelingCmdResponse::FaceIsPlanar(kcmc::output::FaceIsPlanar {
+                origin: Some(origin),
+                z_axis: Some(normal),
+                ..
+            }),
+    } = resp
+    else {
+        return Err(KclError::Type(KclErrorDetails {
+            message: format!("Tag `{}` is not a planar face", tag.value),
+            source_ranges: vec![args.source_range],
+        }));
+    };
+    Ok(Reflection::new(
+        Point3d::new(origin.x.0, origin.y.0, origin.z.0),
+        Point3d::new(normal.x, normal.y, normal.z),
+    ))
+}
+
+/// A reflection across a plane.
+#[derive(Debug, Clone, Copy)]
+struct Reflection {
+    /// A point on the plane.
+    origin: Point3d,
+    /// The plane's unit normal.
+    normal: Point3d,
+}
+
+impl Reflection {
+    fn new(origin: Point3d, normal: Point3d) -> Self {
+        let length = dot(normal, normal).sqrt();
+        Self {
+            origin,
+            normal: scale_by(normal, 1.0 / length),
+        }
+    }
+
+    /// The reflection as the engine expects it. A reflection through a plane is an inversion
+    /// through a point on it, then a half turn about its normal.
+    fn transforms(&self) -> Vec<Transform> {
+        vec![
+            Transform {
+                translate: scale_by(self.origin, -1.0).into(),
+                ..Default::default()
+            },
+            Transform {
+                scale: kcmc::shared::Point3d {
+                    x: -1.0,
+                    y: -1.0,
+                    z: -1.0,
+                },
+                rotation: Rotation {
+                    axis: kcmc::shared::Point3d {
+                        x: self.normal.x,
+                        y: self.normal.y,
+                        z: self.normal.z,
+                    },
+                    angle: Angle::from_degrees(180.0),
+                    origin: OriginType::Global,
+                },
+                ..Default::default()
+            },
+            Transform {
+                translate: self.origin.into(),
+                ..Default::default()
+            },
+        ]
+    }
+
+    fn point(&self, p: Point3d) -> Point3d {
+        let distance = dot(sub(p, self.origin), self.normal);
+        sub(p, scale_by(self.normal, 2.0 * distance))
+    }
+
+    /// Reflect a direction, e.g. the axis of a plane.
+    fn direction(&self, d: Point3d) -> Point3d {
+        sub(d, scale_by(self.normal, 2.0 * dot(d, self.normal)))
+    }
+
+    /// The KCL-side copy of a solid mirrored in the engine.
+    fn solid(&self, solid: &Solid, id: uuid::Uuid) -> Solid {
+        let mut mirrored = solid.clone();
+        mirrored.id = id;
+        mirrored.artifact_id = id.into();
+        // The engine doesn't tell us the ids of the copy's faces and edges, so drop everything
+        // which refers to the original's. Otherwise, e.g. filleting a tagged edge of the copy
+        // would fillet the original.
+        mirrored.sketch.id = id;
+        mirrored.sketch.artifact_id = id.into();
+        mirrored.sketch.tags.clear();
+        mirrored.value.clear();
+        mirrored.start_cap_id = None;
+        mirrored.end_cap_id = None;
+        mirrored.edge_cuts.clear();
+
+        // Sketch coordinates are relative to the sketch plane, so reflecting the plane reflects
+        // the sketch. The plane's axes are left-handed afterwards, like the copy.
+        match &mut mirrored.sketch.on {
+            SketchSurface::Plane(plane) => {
+                plane.origin = self.point(plane.origin);
+                plane.x_axis = self.direction(plane.x_axis);
+                plane.y_axis = self.direction(plane.y_axis);
+                plane.z_axis = self.direction(plane.z_axis);
+                plane.value = PlaneType::Custom;
+            }
+            SketchSurface::Face(face) => {
+                face.x_axis = self.direction(face.x_axis);
+                face.y_axis = self.direction(face.y_axis);
+                face.z_axis = self.direction(face.z_axis);
+            }
+        }
+        mirrored
+    }
+}
+
+fn sub(a: Point3d, b: Point3d) -> Point3d {
+    Point3d::new(a.x - b.x, a.y - b.y, a.z - b.z)
+}
+
+fn scale_by(a: Point3d, factor: f64) -> Point3d {
+    Point3d::new(a.x * factor, a.y * factor, a.z * factor)
+}
+
+fn dot(a: Point3d, b: Point3d) -> f64 {
+    a.x * b.x + a.y * b.y + a.z * b.z
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reflect_across_offset_plane() {
+        let reflection = Reflection::new(Point3d::new(0.0, 0.0, 10.0), Point3d::new(0.0, 0.0, 2.0));
+        assert_eq!(
+            reflection.point(Point3d::new(1.0, 2.0, 3.0)),
+            Point3d::new(1.0, 2.0, 17.0)
+        );
+        assert_eq!(
+            reflection.direction(Point3d::new(0.0, 1.0, 1.0)),
+            Point3d::new(0.0, 1.0, -1.0)
+        );
+    }
+}
@@ -13,6 +13,7 @@ pub mod helix;
 pub mod import;
 pub mod loft;
 pub mod math;
+pub mod measure;
 pub mod mirror;
 pub mod patterns;
 pub mod planes;
@@ -22,7 +23,11 @@ pub mod segment;
 pub mod shapes;
 pub mod shell;
 pub mod sketch;
+pub mod spline;
 pub mod sweep;
+pub mod text;
+pub mod thread;
+pub mod transform;
 pub mod types;
 pub mod units;
 pub mod utils;
@@ -69,9 +74,15 @@ lazy_static! {
         Box::new(crate::std::segment::TangentToEnd),
         Box::new(crate::std::segment::AngleToMatchLengthX),
         Box::new(crate::std::segment::AngleToMatchLengthY),
+        Box::new(crate::std::measure::Volume),
+        Box::new(crate::std::measure::SurfaceArea),
+        Box::new(crate::std::measure::CenterOfMass),
+        Box::new(crate::std::measure::BoundingBox),
+        Box::new(crate::std::measure::Mass),
         Box::new(crate::std::shapes::Circle),
         Box::new(crate::std::shapes::CircleThreePoint),
         Box::new(crate::std::shapes::Polygon),
+        Box::new(crate::std::text::Text),
         Box::new(crate::std::sketch::Line),
         Box::new(crate::std::sketch::XLineTo),
         Box::new(crate::std::sketch::XLine),
@@ -96,8 +107,14 @@ lazy_static! {
         Box::new(crate::std::sketch::TangentialArcTo),
         Box::new(crate::std::sketch::TangentialArcToRelative),
         Box::new(crate::std::sketch::BezierCurve),
+        Box::new(crate::std::spline::SplineThrough),
+        Box::new(crate::std::spline::Nurbs),
         Box::new(crate::std::sketch::Hole),
         Box::new(crate::std::mirror::Mirror2D),
+        Box::new(crate::std::mirror::Mirror3D),
+        Box::new(crate::std::transform::Translate),
+        Box::new(crate::std::transform::Rotate),
+        Box::new(crate::std::transform::Scale),
         Box::new(crate::std::patterns::PatternLinear2D),
         Box::new(crate::std::patterns::PatternLinear3D),
         Box::new(crate::std::patterns::PatternCircular2D),
@@ -119,6 +136,7 @@ lazy_static! {
         Box::new(crate::std::shell::Hollow),
         Box::new(crate::std::revolve::Revolve),
         Box::new(crate::std::sweep::Sweep),
+        Box::new(crate::std::thread::Thread),
         Box::new(crate::std::loft::Loft),
         Box::new(crate::std::planes::OffsetPlane),
         Box::new(crate::std::import::Import),
@@ -323,6 +323,17 @@ async fn inner_polygon(
         })
         .collect();
 
+    sketch_closed_polyline(&vertices, sketch_surface, tag, exec_state, args).await
+}
+
+/// Sketch a closed profile of straight lines through the vertices, which are in order.
+pub(crate) async fn sketch_closed_polyline(
+    vertices: &[[f64; 2]],
+    sketch_surface: SketchSurface,
+    tag: Option<TagNode>,
+    exec_state: &mut ExecState,
+    args: Args,
+) -> Result<Sketch, KclError> {
     let mut sketch =
         crate::std::sketch::inner_start_profile_at(vertices[0], sketch_surface, None, exec_state, args.clone()).await?;
 
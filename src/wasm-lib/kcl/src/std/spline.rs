@@ -0,0 +1,684 @@
+//! Standard library smooth curves: interpolating splines and NURBS.
+//! Both are drawn as a chain of cubic Bézier curves, which is what the engine can sketch.
+
+use anyhow::Result;
+use derive_docs::stdlib;
+use kcmc::shared::Point2d as KPoint2d;
+use kcmc::{each_cmd as mcmd, length_unit::LengthUnit, shared::PathSegment, ModelingCmd};
+use kittycad_modeling_cmds as kcmc;
+
+use crate::{
+    errors::{KclError, KclErrorDetails},
+    execution::{BasePath, ExecState, GeoMeta, KclValue, Path, Sketch},
+    parsing::ast::types::TagNode,
+    std::{sketch::NEW_TAG_KW, Args},
+};
+
+/// How many Bézier curves approximate each span of a NURBS curve which a single cubic can't
+/// represent exactly.
+const PIECES_PER_SPAN: usize = 16;
+
+const EPSILON: f64 = 1e-9;
+
+/// Draw a smooth curve through some points.
+pub async fn spline_through(exec_state: &mut ExecState, args: Args) -> Result<KclValue, KclError> {
+    let sketch = args.get_unlabeled_kw_arg("sketch")?;
+    let points = args.get_kw_arg("points")?;
+    let tangents = args.get_kw_arg_opt("tangents")?;
+    let periodic = args.get_kw_arg_opt("periodic")?;
+    let tag = args.get_kw_arg_opt(NEW_TAG_KW)?;
+
+    let new_sketch = inner_spline_through(sketch, points, tangents, periodic, tag, exec_state, args).await?;
+    Ok(KclValue::Sketch {
+        value: Box::new(new_sketch),
+    })
+}
+
+/// Extend the current sketch with a smooth curve which passes through each of the given points.
+///
+/// The curve is a cubic spline which starts at the current pen position. Without `tangents`, it
+/// is as straight as possible at its ends. A periodic spline returns to where it started, without
+/// a corner there, so it closes the profile smoothly.
+///
+/// ```no_run
+/// blob = startSketchOn('XY')
+///   |> startProfileAt([0, 0], %)
+///   |> splineThrough(points = [[10, 5], [20, -5], [30, 0]])
+///   |> line(endAbsolute = [30, -20])
+///   |> line(endAbsolute = [0, -20])
+///   |> close()
+///   |> extrude(length = 5)
+/// ```
+///
+/// ```no_run
+/// // Leave and arrive straight up.
+/// wave = startSketchOn('XZ')
+///   |> startProfileAt([0, 0], %)
+///   |> splineThrough(points = [[10, 10], [20, 0]], tangents = [[0, 1], [0, -1]], tag = $crest)
+///   |> close()
+///   |> extrude(length = 2)
+/// ```
+///
+/// ```no_run
+/// pebble = startSketchOn('XY')
+///   |> startProfileAt([10, 0], %)
+///   |> splineThrough(points = [[0, 6], [-10, 0], [0, -4]], periodic = true)
+///   |> extrude(length = 3)
+/// ```
+#[stdlib {
+    name = "splineThrough",
+    // Unpublished until the examples have been rendered by the engine, which the docs embed.
+    // The curve is sent as a Bézier per span, and only a render shows they join smoothly, e.g.
+    // where a periodic spline closes.
+    unpublished = true,
+    keywords = true,
+    unlabeled_first = true,
+    args = {
+        sketch = { docs = "Which sketch should this path be added to?" },
+        points = { docs = "The points the curve passes through, in order, after the current pen position." },
+        tangents = { docs = "The directions the curve leaves its start and arrives at its end in, e.g. `[[1, 0], [0, 1]]`. Only their direction matters. Incompatible with `periodic`." },
+        periodic = { docs = "Whether the curve returns smoothly to the current pen position. Defaults to false." },
+        tag = { docs = "Create a new tag which refers to the last piece of this curve" },
+    }
+}]
+async fn inner_spline_through(
+    sketch: Sketch,
+    points: Vec<[f64; 2]>,
+    tangents: Option<Vec<[f64; 2]>>,
+    periodic: Option<bool>,
+    tag: Option<TagNode>,
+    exec_state: &mut ExecState,
+    args: Args,
+) -> Result<Sketch, KclError> {
+    let from: [f64; 2] = sketch.current_pen_position()?.into();
+    let mut through = Vec::with_capacity(points.len() + 1);
+    through.push(from);
+    through.extend(points);
+
+    let ends = match (tangents, periodic.unwrap_or_default()) {
+        (Some(_), true) => {
+            return Err(KclError::Semantic(KclErrorDetails {
+                message: "A periodic spline has no ends, so it can't have tangents".to_owned(),
+                source_ranges: vec![args.source_range],
+            }));
+        }
+        (_, true) => SplineEnds::Periodic,
+        (None, false) => SplineEnds::Natural,
+        (Some(tangents), false) => {
+            let [start, end] = tangents.as_slice() else {
+                return Err(KclError::Semantic(KclErrorDetails {
+                    message: format!(
+                        "Expected a tangent for the start and the end of the spline, found {}",
+                        tangents.len()
+                    ),
+                    source_ranges: vec![args.source_range],
+                }));
+            };
+            SplineEnds::Clamped(*start, *end)
+        }
+    };
+    let pieces = interpolate(&through, ends).map_err(|message| {
+        KclError::Semantic(KclErrorDetails {
+            message,
+            source_ranges: vec![args.source_range],
+        })
+    })?;
+
+    sketch_bezier_pieces(sketch, &pieces, tag, exec_state, &args).await
+}
+
+/// Draw a NURBS curve.
+pub async fn nurbs(exec_state: &mut ExecState, args: Args) -> Result<KclValue, KclError> {
+    let sketch = args.get_unlabeled_kw_arg("sketch")?;
+    let control_points = args.get_kw_arg("controlPoints")?;
+    let weights = args.get_kw_arg_opt("weights")?;
+    let knots = args.get_kw_arg_opt("knots")?;
+    let tag = args.get_kw_arg_opt(NEW_TAG_KW)?;
+
+    let new_sketch = inner_nurbs(sketch, control_points, weights, knots, tag, exec_state, args).await?;
+    Ok(KclValue::Sketch {
+        value: Box::new(new_sketch),
+    })
+}
+
+/// Extend the current sketch with a NURBS (non-uniform rational B-spline) curve.
+///
+/// The current pen position is the first control point, with a weight of 1, and the curve ends
+/// at the last control point. The degree of the curve is the number of knots, less the number of
+/// control points (including the pen position), less one. The knot vector must be clamped: its
+/// first and last values are each repeated one more time than the degree.
+///
+/// Without `knots`, the curve is cubic (or of lower degree, if there are too few control points)
+/// with evenly spaced knots. Without `weights`, every control point has a weight of 1.
+///
+/// ```no_run
+/// // A quarter of a circle, as a quadratic NURBS curve.
+/// quarter = startSketchOn('XY')
+///   |> startProfileAt([10, 0], %)
+///   |> nurbs(controlPoints = [[10, 10], [0, 10]], weights = [0.7071067811865476, 1], knots = [0, 0, 0, 1, 1, 1])
+///   |> line(endAbsolute = [0, 0])
+///   |> close()
+///   |> extrude(length = 2)
+/// ```
+///
+/// ```no_run
+/// curve = startSketchOn('XZ')
+///   |> startProfileAt([0, 0], %)
+///   |> nurbs(controlPoints = [[5, 10], [10, -10], [15, 10], [20, 0]], tag = $top)
+///   |> line(endAbsolute = [20, -15])
+///   |> line(endAbsolute = [0, -15])
+///   |> close()
+///   |> extrude(length = 2)
+/// ```
+#[stdlib {
+    name = "nurbs",
+    // Unpublished until the examples have been rendered by the engine, which the docs embed.
+    // Rational and higher degree curves are approximated by several Béziers per span, and the
+    // renders are needed to check the approximation is close enough, as in the quarter circle.
+    unpublished = true,
+    keywords = true,
+    unlabeled_first = true,
+    args = {
+        sketch = { docs = "Which sketch should this path be added to?" },
+        control_points = { docs = "The control points of the curve, after the current pen position." },
+        weights = { docs = "The weight of each of the `controlPoints`. Each must be greater than zero. Defaults to 1 for every point." },
+        knots = { docs = "The knot vector of the curve, which must be clamped and never decrease. Defaults to evenly spaced knots for a cubic curve." },
+        tag = { docs = "Create a new tag which refers to the last piece of this curve" },
+    }
+}]
+async fn inner_nurbs(
+    sketch: Sketch,
+    control_points: Vec<[f64; 2]>,
+    weights: Option<Vec<f64>>,
+    knots: Option<Vec<f64>>,
+    tag: Option<TagNode>,
+    exec_state: &mut ExecState,
+    args: Args,
+) -> Result<Sketch, KclError> {
+    let from: [f64; 2] = sketch.current_pen_position()?.into();
+    let mut points = Vec::with_capacity(control_points.len() + 1);
+    points.push(from);
+    points.extend(control_points);
+    let weights = weights.map(|weights| std::iter::once(1.0).chain(weights).collect());
+
+    let pieces = NurbsCurve::new(points, weights, knots)
+        .map(|curve| curve.to_bezier_pieces())
+        .map_err(|message| {
+            KclError::Semantic(KclErrorDetails {
+                message,
+                source_ranges: vec![args.source_range],
+            })
+        })?;
+
+    sketch_bezier_pieces(sketch, &pieces, tag, exec_state, &args).await
+}
+
+/// Extend the sketch with a Bézier curve for each piece, given by its control points and end.
+/// The tag goes on the last piece.
+async fn sketch_bezier_pieces(
+    sketch: Sketch,
+    pieces: &[[[f64; 2]; 3]],
+    tag: Option<TagNode>,
+    exec_state: &mut ExecState,
+    args: &Args,
+) -> Result<Sketch, KclError> {
+    let mut new_sketch = sketch;
+    let mut from: [f64; 2] = new_sketch.current_pen_position()?.into();
+    for (i, &[control1, control2, to]) in pieces.iter().enumerate() {
+        let id = exec_state.next_uuid();
+        let relative = |p: [f64; 2]| {
+            KPoint2d::from([p[0] - from[0], p[1] - from[1]])
+                .with_z(0.0)
+                .map(LengthUnit)
+        };
+        args.batch_modeling_cmd(
+            id,
+            ModelingCmd::from(mcmd::ExtendPath {
+                path: new_sketch.id.into(),
+                segment: PathSegment::Bezier {
+                    control1: relative(control1),
+                    control2: relative(control2),
+                    end: relative(to),
+                    relative: true,
+                },
+            }),
+        )
+        .await?;
+
+        let tag = if i + 1 == pieces.len() { tag.clone() } else { None };
+        let current_path = Path::Bezier {
+            base: BasePath {
+                from,
+                to,
+                tag: tag.clone(),
+                geo_meta: GeoMeta {
+                    id,
+                    metadata: args.source_range.into(),
+                },
+            },
+            control1,
+            control2,
+        };
+        if let Some(tag) = &tag {
+            new_sketch.add_tag(tag, &current_path);
+        }
+        new_sketch.paths.push(current_path);
+        from = to;
+    }
+    Ok(new_sketch)
+}
+
+/// How an interpolating spline behaves at its ends.
+#[derive(Debug, Clone, Copy)]
+enum SplineEnds {
+    /// No bending at either end.
+    Natural,
+    /// Leaving the start and arriving at the end in these directions.
+    Clamped([f64; 2], [f64; 2]),
+    /// Joining the end back to the start.
+    Periodic,
+}
+
+/// The pieces of a cubic spline through the points, as the two control points and end of each
+/// Bézier curve.
+fn interpolate(points: &[[f64; 2]], ends: SplineEnds) -> Result<Vec<[[f64; 2]; 3]>, String> {
+    let min_points = match ends {
+        SplineEnds::Periodic => 3,
+        _ => 2,
+    };
+    if points.len() < min_points {
+        return Err(format!(
+            "Expected at least {} points for the spline to pass through, found {}",
+            min_points - 1,
+            points.len() - 1
+        ));
+    }
+    if points.windows(2).any(|w| distance(w[0], w[1]) < EPSILON) {
+        return Err("The spline can't pass through the same point twice in a row".to_owned());
+    }
+
+    // Solve for the derivative of the spline at each point, with the points evenly spaced in
+    // parameter. Every inner point gives D[i - 1] + 4 D[i] + D[i + 1] = 3 (P[i + 1] - P[i - 1]).
+    let n = points.len();
+    let mut matrix = vec![vec![0.0; n]; n];
+    let mut rhs = vec![[0.0; 2]; n];
+    for i in 1..n - 1 {
+        matrix[i][i - 1] = 1.0;
+        matrix[i][i] = 4.0;
+        matrix[i][i + 1] = 1.0;
+        rhs[i] = scale(sub(points[i + 1], points[i - 1]), 3.0);
+    }
+    match ends {
+        SplineEnds::Natural => {
+            matrix[0][0] = 2.0;
+            matrix[0][1] = 1.0;
+            rhs[0] = scale(sub(points[1], points[0]), 3.0);
+            matrix[n - 1][n - 2] = 1.0;
+            matrix[n - 1][n - 1] = 2.0;
+            rhs[n - 1] = scale(sub(points[n - 1], points[n - 2]), 3.0);
+        }
+        SplineEnds::Clamped(start, end) => {
+            matrix[0][0] = 1.0;
+            rhs[0] = scale(unit(start)?, distance(points[0], points[1]));
+            matrix[n - 1][n - 1] = 1.0;
+            rhs[n - 1] = scale(unit(end)?, distance(points[n - 2], points[n - 1]));
+        }
+        SplineEnds::Periodic => {
+            matrix[0][n - 1] = 1.0;
+            matrix[0][0] = 4.0;
+            matrix[0][1] = 1.0;
+            rhs[0] = scale(sub(points[1], points[n - 1]), 3.0);
+            matrix[n - 1][n - 2] = 1.0;
+            matrix[n - 1][n - 1] = 4.0;
+            matrix[n - 1][0] = 1.0;
+            rhs[n - 1] = scale(sub(points[0], points[n - 2]), 3.0);
+        }
+    }
+    let derivatives = solve(matrix, rhs);
+
+    let segments = match ends {
+        SplineEnds::Periodic => n,
+        _ => n - 1,
+    };
+    Ok((0..segments)
+        .map(|i| {
+            let next = (i + 1) % n;
+            [
+                add(points[i], scale(derivatives[i], 1.0 / 3.0)),
+                sub(points[next], scale(derivatives[next], 1.0 / 3.0)),
+                points[next],
+            ]
+        })
+        .collect())
+}
+
+/// Solve the square linear system for two right-hand sides at once, by Gaussian elimination.
+/// Spline systems are diagonally dominant, so they're never singular.
+fn solve(mut matrix: Vec<Vec<f64>>, mut rhs: Vec<[f64; 2]>) -> Vec<[f64; 2]> {
+    let n = rhs.len();
+    for col in 0..n {
+        let pivot = (col..n)
+            .max_by(|&a, &b| matrix[a][col].abs().total_cmp(&matrix[b][col].abs()))
+            .unwrap_or(col);
+        matrix.swap(col, pivot);
+        rhs.swap(col, pivot);
+        for row in col + 1..n {
+            let factor = matrix[row][col] / matrix[col][col];
+            if factor == 0.0 {
+                continue;
+            }
+            for k in col..n {
+                matrix[row][k] -= factor * matrix[col][k];
+            }
+            rhs[row] = sub(rhs[row], scale(rhs[col], factor));
+        }
+    }
+    let mut solution = vec![[0.0; 2]; n];
+    for row in (0..n).rev() {
+        let sum = (row + 1..n).fold(rhs[row], |sum, k| sub(sum, scale(solution[k], matrix[row][k])));
+        solution[row] = scale(sum, 1.0 / matrix[row][row]);
+    }
+    solution
+}
+
+/// A NURBS curve in the plane.
+#[derive(Debug, Clone)]
+struct NurbsCurve {
+    degree: usize,
+    /// The weighted control points, with the weight as the last coordinate.
+    homogeneous: Vec<[f64; 3]>,
+    knots: Vec<f64>,
+}
+
+impl NurbsCurve {
+    fn new(points: Vec<[f64; 2]>, weights: Option<Vec<f64>>, knots: Option<Vec<f64>>) -> Result<Self, String> {
+        let n = points.len();
+        if n < 2 {
+            return Err("Expected at least one control point".to_owned());
+        }
+        let weights = weights.unwrap_or_else(|| vec![1.0; n]);
+        if weights.len() != n {
+            return Err(format!(
+                "Expected a weight for each of the {} control points, found {}",
+                n - 1,
+                weights.len() - 1
+            ));
+        }
+        if let Some(weight) = weights.iter().find(|w| **w <= 0.0) {
+            return Err(format!("Weights must be greater than zero, found {weight}"));
+        }
+
+        let knots = knots.unwrap_or_else(|| clamped_uniform_knots(n, 3.min(n - 1)));
+        if knots.len() <= n + 1 {
+            return Err(format!(
+                "Expected more than {} knots for {} control points, found {}",
+                n + 1,
+                n - 1,
+                knots.len()
+            ));
+        }
+        let degree = knots.len() - n - 1;
+        if degree >= n {
+            return Err(format!(
+                "Expected at most {} knots for {} control points, found {}",
+                2 * n,
+                n - 1,
+                knots.len()
+            ));
+        }
+        if knots.windows(2).any(|w| w[1] < w[0]) {
+            return Err("Knots must never decrease".to_owned());
+        }
+        let clamped = knots[..=degree].iter().all(|k| *k == knots[0])
+            && knots[knots.len() - degree - 1..]
+                .iter()
+                .all(|k| *k == knots[knots.len() - 1]);
+        if !clamped {
+            return Err(format!(
+                "The first and last knots must each be repeated {} times, so the curve ends at its first and last control points",
+                degree + 1
+            ));
+        }
+        if knots[0] == knots[knots.len() - 1] {
+            return Err("The knots must not all be the same".to_owned());
+        }
+
+        let homogeneous = points
+            .iter()
+            .zip(&weights)
+            .map(|(p, w)| [p[0] * w, p[1] * w, *w])
+            .collect();
+        Ok(Self {
+            degree,
+            homogeneous,
+            knots,
+        })
+    }
+
+    fn is_rational(&self) -> bool {
+        let first = self.homogeneous[0][2];
+        self.homogeneous.iter().any(|h| (h[2] - first).abs() > EPSILON)
+    }
+
+    /// The curve as cubic Bézier pieces. A polynomial curve of degree at most three is exact,
+    /// with a piece per span; otherwise each span is approximated by several pieces.
+    fn to_bezier_pieces(&self) -> Vec<[[f64; 2]; 3]> {
+        let pieces_per_span = if self.degree <= 3 && !self.is_rational() {
+            1
+        } else {
+            PIECES_PER_SPAN
+        };
+        let mut pieces = Vec::new();
+        for span in self.degree..self.homogeneous.len() {
+            let (start, end) = (self.knots[span], self.knots[span + 1]);
+            if end <= start {
+                continue;
+            }
+            for i in 0..pieces_per_span {
+                let a = start + (end - start) * i as f64 / pieces_per_span as f64;
+                let b = start + (end - start) * (i + 1) as f64 / pieces_per_span as f64;
+                let (p0, d0) = self.point_and_derivative(span, a);
+                let (p3, d3) = self.point_and_derivative(span, b);
+                let third = (b - a) / 3.0;
+                pieces.push([add(p0, scale(d0, third)), sub(p3, scale(d3, third)), p3]);
+            }
+        }
+        pieces
+    }
+
+    /// The point at the parameter `u`, and the derivative there, using the polynomial of the
+    /// knot span starting at `span`.
+    fn point_and_derivative(&self, span: usize, u: f64) -> ([f64; 2], [f64; 2]) {
+        let p = self.degree;
+        let point = de_boor(&self.homogeneous, &self.knots, p, span, u);
+
+        // The derivative of a B-spline is a B-spline of one lower degree, over the inner knots.
+        let derivative_points: Vec<_> = self
+            .homogeneous
+            .windows(2)
+            .enumerate()
+            .map(|(i, w)| {
+                let factor = p as f64 / (self.knots[i + p + 1] - self.knots[i + 1]);
+                let difference = [w[1][0] - w[0][0], w[1][1] - w[0][1], w[1][2] - w[0][2]];
+                if factor.is_finite() {
+                    difference.map(|c| c * factor)
+                } else {
+                    [0.0; 3]
+                }
+            })
+            .collect();
+        let derivative = de_boor(
+            &derivative_points,
+            &self.knots[1..self.knots.len() - 1],
+            p - 1,
+            span - 1,
+            u,
+        );
+
+        // Quotient rule, from the weighted curve back to the plane.
+        let weight = point[2];
+        let at = [point[0] / weight, point[1] / weight];
+        let tangent = [
+            (derivative[0] - derivative[2] * at[0]) / weight,
+            (derivative[1] - derivative[2] * at[1]) / weight,
+        ];
+        (at, tangent)
+    }
+}
+
+/// A clamped knot vector with evenly spaced inner knots.
+fn clamped_uniform_knots(num_points: usize, degree: usize) -> Vec<f64> {
+    let last = (num_points - degree) as f64;
+    std::iter::repeat(0.0)
+        .take(degree + 1)
+        .chain((1..num_points - degree).map(|k| k as f64))
+        .chain(std::iter::repeat(last).take(degree + 1))
+        .collect()
+}
+
+/// Evaluate a B-spline at `u` with the polynomial of the knot span starting at `span`.
+fn de_boor(points: &[[f64; 3]], knots: &[f64], degree: usize, span: usize, u: f64) -> [f64; 3] {
+    let mut d: Vec<[f64; 3]> = (0..=degree).map(|j| points[j + span - degree]).collect();
+    for r in 1..=degree {
+        for j in (r..=degree).rev() {
+            let i = j + span - degree;
+            let denominator = knots[i + degree + 1 - r] - knots[i];
+            let alpha = if denominator == 0.0 {
+                0.0
+            } else {
+                (u - knots[i]) / denominator
+            };
+            for c in 0..3 {
+                d[j][c] = (1.0 - alpha) * d[j - 1][c] + alpha * d[j][c];
+            }
+        }
+    }
+    d[degree]
+}
+
+fn unit(v: [f64; 2]) -> Result<[f64; 2], String> {
+    let length = v[0].hypot(v[1]);
+    if length < EPSILON {
+        return Err("Tangents must have a direction, found [0, 0]".to_owned());
+    }
+    Ok(scale(v, 1.0 / length))
+}
+
+fn distance(a: [f64; 2], b: [f64; 2]) -> f64 {
+    (b[0] - a[0]).hypot(b[1] - a[1])
+}
+
+fn add(a: [f64; 2], b: [f64; 2]) -> [f64; 2] {
+    [a[0] + b[0], a[1] + b[1]]
+}
+
+fn sub(a: [f64; 2], b: [f64; 2]) -> [f64; 2] {
+    [a[0] - b[0], a[1] - b[1]]
+}
+
+fn scale(a: [f64; 2], factor: f64) -> [f64; 2] {
+    [a[0] * factor, a[1] * factor]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::bezier_point;
+
+    fn assert_close(actual: [f64; 2], expected: [f64; 2]) {
+        assert!(
+            distance(actual, expected) < 1e-6,
+            "expected {expected:?}, found {actual:?}"
+        );
+    }
+
+    #[test]
+    fn spline_passes_through_points() {
+        let points = [[0.0, 0.0], [10.0, 5.0], [20.0, -5.0], [30.0, 0.0]];
+        for ends in [
+            SplineEnds::Natural,
+            SplineEnds::Clamped([1.0, 0.0], [0.0, 1.0]),
+            SplineEnds::Periodic,
+        ] {
+            let pieces = interpolate(&points, ends).unwrap();
+            let ends_at: Vec<_> = pieces.iter().map(|piece| piece[2]).collect();
+            let mut expected = points[1..].to_vec();
+            if let SplineEnds::Periodic = ends {
+                expected.push(points[0]);
+            }
+            assert_eq!(ends_at, expected);
+
+            // Each piece leaves in the direction the last one arrived in.
+            let closed = matches!(ends, SplineEnds::Periodic);
+            let joins = pieces.len() - usize::from(!closed);
+            for i in 0..joins {
+                let (before, after) = (pieces[i], pieces[(i + 1) % pieces.len()]);
+                let join = before[2];
+                assert_close(sub(join, before[1]), sub(after[0], join));
+            }
+        }
+    }
+
+    #[test]
+    fn clamped_spline_leaves_along_tangent() {
+        let pieces = interpolate(
+            &[[0.0, 0.0], [10.0, 10.0], [20.0, 0.0]],
+            SplineEnds::Clamped([0.0, 2.0], [0.0, -1.0]),
+        )
+        .unwrap();
+        assert_close(pieces[0][0], [0.0, 10.0 * 2f64.sqrt() / 3.0]);
+        assert_close(pieces[1][1], [20.0, 10.0 * 2f64.sqrt() / 3.0]);
+    }
+
+    #[test]
+    fn periodic_spline_needs_two_points() {
+        let err = interpolate(&[[0.0, 0.0], [1.0, 0.0]], SplineEnds::Periodic).unwrap_err();
+        assert_eq!(
+            err,
+            "Expected at least 2 points for the spline to pass through, found 1"
+        );
+    }
+
+    #[test]
+    fn cubic_bezier_is_its_own_nurbs() {
+        let points = vec![[0.0, 0.0], [5.0, 10.0], [10.0, -10.0], [15.0, 0.0]];
+        let pieces = NurbsCurve::new(points, None, None).unwrap().to_bezier_pieces();
+        assert_eq!(pieces.len(), 1);
+        assert_close(pieces[0][0], [5.0, 10.0]);
+        assert_close(pieces[0][1], [10.0, -10.0]);
+        assert_close(pieces[0][2], [15.0, 0.0]);
+    }
+
+    #[test]
+    fn rational_quadratic_is_a_circle() {
+        let weight = std::f64::consts::FRAC_1_SQRT_2;
+        let curve = NurbsCurve::new(
+            vec![[10.0, 0.0], [10.0, 10.0], [0.0, 10.0]],
+            Some(vec![1.0, weight, 1.0]),
+            Some(vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0]),
+        )
+        .unwrap();
+        let pieces = curve.to_bezier_pieces();
+        assert_eq!(pieces.len(), PIECES_PER_SPAN);
+        let mut from = [10.0, 0.0];
+        for piece in pieces {
+            for t in [0.25, 0.5, 0.75] {
+                let p = bezier_point([from, piece[0], piece[1], piece[2]], t);
+                assert!((p[0].hypot(p[1]) - 10.0).abs() < 1e-4, "{p:?} is off the circle");
+            }
+            from = piece[2];
+        }
+        assert_close(from, [0.0, 10.0]);
+    }
+
+    #[test]
+    fn nurbs_checks_knots() {
+        let points = vec![[0.0, 0.0], [5.0, 10.0], [10.0, 0.0]];
+        let err = NurbsCurve::new(points.clone(), None, Some(vec![0.0, 0.0, 1.0, 0.5, 1.0, 1.0])).unwrap_err();
+        assert_eq!(err, "Knots must never decrease");
+        let err = NurbsCurve::new(points.clone(), None, Some(vec![0.0, 0.0, 0.5, 1.0, 1.0, 1.0])).unwrap_err();
+        assert!(err.starts_with("The first and last knots must each be repeated 3 times"));
+        let err = NurbsCurve::new(points, Some(vec![1.0, 0.0, 1.0]), None).unwrap_err();
+        assert_eq!(err, "Weights must be greater than zero, found 0");
+    }
+}
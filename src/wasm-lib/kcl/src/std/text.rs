@@ -0,0 +1,522 @@
+//! Standard library text, sketched with a font that ships with KCL.
+
+use anyhow::Result;
+use derive_docs::stdlib;
+
+use self::Segment::*;
+use crate::{
+    errors::{KclError, KclErrorDetails},
+    execution::{ExecState, KclValue, Sketch},
+    std::{
+        shapes::{sketch_closed_polyline, SketchOrSurface},
+        Args,
+    },
+};
+
+/// The name of the font which ships with KCL.
+pub const DEFAULT_FONT: &str = "default";
+
+/// Sketch text.
+pub async fn text(exec_state: &mut ExecState, args: Args) -> Result<KclValue, KclError> {
+    let sketch_surface = args.get_unlabeled_kw_arg("sketch_surface")?;
+    let value = args.get_kw_arg("value")?;
+    let size = args.get_kw_arg("size")?;
+    let position = args.get_kw_arg_opt("position")?;
+    let font = args.get_kw_arg_opt("font")?;
+
+    let sketches = inner_text(sketch_surface, value, size, position, font, exec_state, args).await?;
+    Ok(sketches.into())
+}
+
+/// Sketch the outline of some text, like a part number, as closed profiles.
+///
+/// The profiles can be extruded to emboss the text, or used as holes to engrave it.
+/// Each piece of every letter is its own profile, so the text is a set of sketches.
+///
+/// The default font, which is always available, is a sixteen-segment font like on an
+/// alphanumeric display. It has the letters A to Z (lowercase letters are drawn as uppercase),
+/// the digits and the characters `-`, `_`, `+`, `/` and `.`.
+///
+/// ```no_run
+/// // Emboss a part number on a plate.
+/// plate = startSketchOn('XY')
+///   |> startProfileAt([0, 0], %)
+///   |> line(end = [60, 0])
+///   |> line(end = [0, 20])
+///   |> line(end = [-60, 0])
+///   |> close()
+///   |> extrude(length = 2)
+///
+/// partNumber = startSketchOn(plate, 'end')
+///   |> text(value = "PN-1234", size = 8, position = [5, 6])
+///   |> extrude(length = 1)
+/// ```
+///
+/// ```no_run
+/// // Cut a label out of a plate.
+/// label = startSketchOn('XZ')
+///   |> startProfileAt([-5, -5], %)
+///   |> line(end = [50, 0])
+///   |> line(end = [0, 20])
+///   |> line(end = [-50, 0])
+///   |> close()
+///   |> hole(text(startSketchOn('XZ'), value = "KCL 2", size = 10, position = [0, 0]), %)
+///
+/// example = extrude(label, length = 1)
+/// ```
+#[stdlib {
+    name = "text",
+    // Unpublished until the examples have been rendered by the engine, which the docs embed.
+    // Every segment of every glyph is its own small profile, and only a render shows the engine
+    // extrudes and cuts them all cleanly.
+    unpublished = true,
+    keywords = true,
+    unlabeled_first = true,
+    args = {
+        sketch_surface = { docs = "The plane or face to sketch the text on, or a sketch whose surface to use." },
+        value = { docs = "The text to sketch." },
+        size = { docs = "The height of the capital letters." },
+        position = { docs = "Where the bottom left corner of the text goes. Defaults to the origin." },
+        font = { docs = "The font to use. Only the default font, `\"default\"`, is available." },
+    }
+}]
+async fn inner_text(
+    sketch_surface: SketchOrSurface,
+    value: String,
+    size: f64,
+    position: Option<[f64; 2]>,
+    font: Option<String>,
+    exec_state: &mut ExecState,
+    args: Args,
+) -> Result<Vec<Box<Sketch>>, KclError> {
+    if let Some(font) = font.filter(|font| font != DEFAULT_FONT) {
+        return Err(KclError::Semantic(KclErrorDetails {
+            message: format!("Unknown font `{font}`, the only font is `{DEFAULT_FONT}`"),
+            source_ranges: vec![args.source_range],
+        }));
+    }
+    if size <= 0.0 {
+        return Err(KclError::Semantic(KclErrorDetails {
+            message: format!("Text size must be greater than zero, found {size}"),
+            source_ranges: vec![args.source_range],
+        }));
+    }
+
+    let outlines = outline(&value, size, position.unwrap_or_default()).map_err(|c| {
+        KclError::Semantic(KclErrorDetails {
+            message: format!("The default font has no character `{c}`"),
+            source_ranges: vec![args.source_range],
+        })
+    })?;
+    if outlines.is_empty() {
+        return Err(KclError::Semantic(KclErrorDetails {
+            message: "Text must have at least one character which isn't a space".to_owned(),
+            source_ranges: vec![args.source_range],
+        }));
+    }
+
+    let sketch_surface = match sketch_surface {
+        SketchOrSurface::SketchSurface(surface) => surface,
+        SketchOrSurface::Sketch(sketch) => sketch.on,
+    };
+    let mut sketches = Vec::with_capacity(outlines.len());
+    for vertices in outlines {
+        let sketch = sketch_closed_polyline(&vertices, sketch_surface.clone(), None, exec_state, args.clone()).await?;
+        sketches.push(Box::new(sketch));
+    }
+    Ok(sketches)
+}
+
+/// The outlines of the text's segments, scaled to the size and moved to the position.
+/// Returns the first character which isn't in the font, if any.
+fn outline(string: &str, size: f64, position: [f64; 2]) -> Result<Vec<Vec<[f64; 2]>>, char> {
+    let scale = size / HEIGHT;
+    let mut outlines = Vec::new();
+    for (i, c) in string.chars().enumerate() {
+        let x = position[0] + i as f64 * ADVANCE * scale;
+        for segment in glyph(c).ok_or(c)? {
+            let vertices = segment
+                .vertices()
+                .into_iter()
+                .map(|[u, v]| [x + u * scale, position[1] + v * scale])
+                .collect();
+            outlines.push(vertices);
+        }
+    }
+    Ok(outlines)
+}
+
+// The dimensions of a character, in font units.
+const WIDTH: f64 = 1.2;
+const HEIGHT: f64 = 2.0;
+/// How far apart characters start.
+const ADVANCE: f64 = WIDTH + 0.4;
+/// The thickness of the straight segments.
+const STROKE: f64 = 0.16;
+/// The gap between segments.
+const GAP: f64 = 0.04;
+/// The horizontal thickness of the diagonal segments.
+const DIAGONAL: f64 = 0.12;
+const MIDDLE: f64 = HEIGHT / 2.0;
+const CENTER: f64 = WIDTH / 2.0;
+
+/// A segment of a sixteen-segment display. Segments don't overlap, so each one is a separate
+/// profile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Segment {
+    /// The left half of the top bar.
+    TopLeft,
+    TopRight,
+    MiddleLeft,
+    MiddleRight,
+    BottomLeft,
+    BottomRight,
+    /// The upper half of the left side.
+    LeftUpper,
+    LeftLower,
+    RightUpper,
+    RightLower,
+    /// The upper half of the vertical bar through the middle.
+    CenterUpper,
+    CenterLower,
+    /// The diagonal from the top left corner to the center.
+    DiagonalUpperLeft,
+    DiagonalUpperRight,
+    DiagonalLowerLeft,
+    DiagonalLowerRight,
+    /// A full stop.
+    Dot,
+}
+
+impl Segment {
+    /// The outline, counterclockwise, in font units with the origin at the character's bottom left.
+    fn vertices(self) -> Vec<[f64; 2]> {
+        // Horizontal extents of the bars' halves, and of the spaces between the bars.
+        let left = (STROKE + GAP, CENTER - STROKE / 2.0 - GAP);
+        let right = (CENTER + STROKE / 2.0 + GAP, WIDTH - STROKE - GAP);
+        // Vertical extents of the spaces between the bars.
+        let upper = (MIDDLE + STROKE / 2.0 + GAP, HEIGHT - STROKE - GAP);
+        let lower = (STROKE + GAP, MIDDLE - STROKE / 2.0 - GAP);
+
+        let top = (HEIGHT - STROKE, HEIGHT);
+        let middle = (MIDDLE - STROKE / 2.0, MIDDLE + STROKE / 2.0);
+        let bottom = (0.0, STROKE);
+        let left_side = (0.0, STROKE);
+        let right_side = (WIDTH - STROKE, WIDTH);
+        let center = (CENTER - STROKE / 2.0, CENTER + STROKE / 2.0);
+
+        match self {
+            TopLeft => rectangle(left, top),
+            TopRight => rectangle(right, top),
+            MiddleLeft => rectangle(left, middle),
+            MiddleRight => rectangle(right, middle),
+            BottomLeft => rectangle(left, bottom),
+            BottomRight => rectangle(right, bottom),
+            LeftUpper => rectangle(left_side, (MIDDLE + GAP / 2.0, HEIGHT)),
+            LeftLower => rectangle(left_side, (0.0, MIDDLE - GAP / 2.0)),
+            RightUpper => rectangle(right_side, (MIDDLE + GAP / 2.0, HEIGHT)),
+            RightLower => rectangle(right_side, (0.0, MIDDLE - GAP / 2.0)),
+            CenterUpper => rectangle(center, upper),
+            CenterLower => rectangle(center, lower),
+            DiagonalUpperLeft => falling(left, upper),
+            DiagonalUpperRight => rising(right, upper),
+            DiagonalLowerLeft => rising(left, lower),
+            DiagonalLowerRight => falling(right, lower),
+            Dot => rectangle(center, bottom),
+        }
+    }
+}
+
+fn rectangle((x0, x1): (f64, f64), (y0, y1): (f64, f64)) -> Vec<[f64; 2]> {
+    vec![[x0, y0], [x1, y0], [x1, y1], [x0, y1]]
+}
+
+/// A diagonal from the top left to the bottom right of a space.
+fn falling((x0, x1): (f64, f64), (y0, y1): (f64, f64)) -> Vec<[f64; 2]> {
+    vec![[x1 - DIAGONAL, y0], [x1, y0], [x0 + DIAGONAL, y1], [x0, y1]]
+}
+
+/// A diagonal from the bottom left to the top right of a space.
+fn rising((x0, x1): (f64, f64), (y0, y1): (f64, f64)) -> Vec<[f64; 2]> {
+    vec![[x0, y0], [x0 + DIAGONAL, y0], [x1, y1], [x1 - DIAGONAL, y1]]
+}
+
+/// The segments which draw a character, if it's in the font.
+fn glyph(c: char) -> Option<&'static [Segment]> {
+    const BOX: [Segment; 8] = [
+        TopLeft,
+        TopRight,
+        RightUpper,
+        RightLower,
+        BottomRight,
+        BottomLeft,
+        LeftLower,
+        LeftUpper,
+    ];
+
+    Some(match c.to_ascii_uppercase() {
+        ' ' => &[],
+        '0' => &[
+            TopLeft,
+            TopRight,
+            RightUpper,
+            RightLower,
+            BottomRight,
+            BottomLeft,
+            LeftLower,
+            LeftUpper,
+            DiagonalUpperRight,
+            DiagonalLowerLeft,
+        ],
+        '1' => &[RightUpper, RightLower],
+        '2' => &[
+            TopLeft,
+            TopRight,
+            RightUpper,
+            MiddleRight,
+            MiddleLeft,
+            LeftLower,
+            BottomLeft,
+            BottomRight,
+        ],
+        '3' => &[
+            TopLeft,
+            TopRight,
+            RightUpper,
+            MiddleRight,
+            RightLower,
+            BottomRight,
+            BottomLeft,
+        ],
+        '4' => &[LeftUpper, MiddleLeft, MiddleRight, RightUpper, RightLower],
+        '5' | 'S' => &[
+            TopRight,
+            TopLeft,
+            LeftUpper,
+            MiddleLeft,
+            MiddleRight,
+            RightLower,
+            BottomRight,
+            BottomLeft,
+        ],
+        '6' => &[
+            TopRight,
+            TopLeft,
+            LeftUpper,
+            LeftLower,
+            BottomLeft,
+            BottomRight,
+            RightLower,
+            MiddleRight,
+            MiddleLeft,
+        ],
+        '7' => &[TopLeft, TopRight, RightUpper, RightLower],
+        '8' => &[
+            TopLeft,
+            TopRight,
+            RightUpper,
+            RightLower,
+            BottomRight,
+            BottomLeft,
+            LeftLower,
+            LeftUpper,
+            MiddleLeft,
+            MiddleRight,
+        ],
+        '9' => &[
+            MiddleRight,
+            MiddleLeft,
+            LeftUpper,
+            TopLeft,
+            TopRight,
+            RightUpper,
+            RightLower,
+            BottomRight,
+            BottomLeft,
+        ],
+        'A' => &[
+            LeftLower,
+            LeftUpper,
+            TopLeft,
+            TopRight,
+            RightUpper,
+            RightLower,
+            MiddleLeft,
+            MiddleRight,
+        ],
+        'B' => &[
+            TopLeft,
+            TopRight,
+            RightUpper,
+            RightLower,
+            BottomRight,
+            BottomLeft,
+            CenterUpper,
+            CenterLower,
+            MiddleRight,
+        ],
+        'C' => &[TopRight, TopLeft, LeftUpper, LeftLower, BottomLeft, BottomRight],
+        'D' => &[
+            TopLeft,
+            TopRight,
+            RightUpper,
+            RightLower,
+            BottomRight,
+            BottomLeft,
+            CenterUpper,
+            CenterLower,
+        ],
+        'E' => &[
+            TopRight,
+            TopLeft,
+            LeftUpper,
+            LeftLower,
+            BottomLeft,
+            BottomRight,
+            MiddleLeft,
+        ],
+        'F' => &[TopRight, TopLeft, LeftUpper, LeftLower, MiddleLeft],
+        'G' => &[
+            TopRight,
+            TopLeft,
+            LeftUpper,
+            LeftLower,
+            BottomLeft,
+            BottomRight,
+            RightLower,
+            MiddleRight,
+        ],
+        'H' => &[LeftUpper, LeftLower, RightUpper, RightLower, MiddleLeft, MiddleRight],
+        'I' => &[TopLeft, TopRight, CenterUpper, CenterLower, BottomLeft, BottomRight],
+        'J' => &[RightUpper, RightLower, BottomRight, BottomLeft, LeftLower],
+        'K' => &[LeftUpper, LeftLower, MiddleLeft, DiagonalUpperRight, DiagonalLowerRight],
+        'L' => &[LeftUpper, LeftLower, BottomLeft, BottomRight],
+        'M' => &[
+            LeftLower,
+            LeftUpper,
+            DiagonalUpperLeft,
+            DiagonalUpperRight,
+            RightUpper,
+            RightLower,
+        ],
+        'N' => &[
+            LeftLower,
+            LeftUpper,
+            DiagonalUpperLeft,
+            DiagonalLowerRight,
+            RightUpper,
+            RightLower,
+        ],
+        'O' => &BOX,
+        'P' => &[
+            LeftLower,
+            LeftUpper,
+            TopLeft,
+            TopRight,
+            RightUpper,
+            MiddleRight,
+            MiddleLeft,
+        ],
+        'Q' => &[
+            TopLeft,
+            TopRight,
+            RightUpper,
+            RightLower,
+            BottomRight,
+            BottomLeft,
+            LeftLower,
+            LeftUpper,
+            DiagonalLowerRight,
+        ],
+        'R' => &[
+            LeftLower,
+            LeftUpper,
+            TopLeft,
+            TopRight,
+            RightUpper,
+            MiddleRight,
+            MiddleLeft,
+            DiagonalLowerRight,
+        ],
+        'T' => &[TopLeft, TopRight, CenterUpper, CenterLower],
+        'U' => &[LeftUpper, LeftLower, BottomLeft, BottomRight, RightLower, RightUpper],
+        'V' => &[LeftUpper, LeftLower, DiagonalLowerLeft, DiagonalUpperRight],
+        'W' => &[
+            LeftUpper,
+            LeftLower,
+            DiagonalLowerLeft,
+            DiagonalLowerRight,
+            RightLower,
+            RightUpper,
+        ],
+        'X' => &[
+            DiagonalUpperLeft,
+            DiagonalUpperRight,
+            DiagonalLowerLeft,
+            DiagonalLowerRight,
+        ],
+        'Y' => &[DiagonalUpperLeft, DiagonalUpperRight, CenterLower],
+        'Z' => &[
+            TopLeft,
+            TopRight,
+            DiagonalUpperRight,
+            DiagonalLowerLeft,
+            BottomLeft,
+            BottomRight,
+        ],
+        '-' => &[MiddleLeft, MiddleRight],
+        '_' => &[BottomLeft, BottomRight],
+        '+' => &[MiddleLeft, MiddleRight, CenterUpper, CenterLower],
+        '/' => &[DiagonalUpperRight, DiagonalLowerLeft],
+        '.' => &[Dot],
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Twice the signed area of a polygon, which is positive when it's counterclockwise.
+    fn signed_area(vertices: &[[f64; 2]]) -> f64 {
+        let mut area = 0.0;
+        for (i, [x0, y0]) in vertices.iter().enumerate() {
+            let [x1, y1] = vertices[(i + 1) % vertices.len()];
+            area += x0 * y1 - x1 * y0;
+        }
+        area
+    }
+
+    #[test]
+    fn segments_are_counterclockwise_and_inside_the_character() {
+        for segment in glyph('8')
+            .unwrap()
+            .iter()
+            .chain(glyph('X').unwrap())
+            .chain(&[CenterUpper, CenterLower, Dot])
+        {
+            let vertices = segment.vertices();
+            assert!(signed_area(&vertices) > 0.0, "{segment:?} is clockwise");
+            for [x, y] in vertices {
+                assert!(
+                    (0.0..=WIDTH).contains(&x) && (0.0..=HEIGHT).contains(&y),
+                    "{segment:?} is outside"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn outline_is_scaled_and_placed() {
+        let outlines = outline("1 1", 10.0, [5.0, 2.0]).unwrap();
+        // Two segments for each 1, none for the space.
+        assert_eq!(outlines.len(), 4);
+        // The right side of the first 1 is at the right edge of the first character.
+        let xs: Vec<f64> = outlines[0].iter().map(|[x, _]| *x).collect();
+        assert_eq!(xs.iter().cloned().fold(f64::MIN, f64::max), 5.0 + WIDTH * 5.0);
+        // The second 1 is two characters along.
+        assert_eq!(outlines[2][0][0] - outlines[0][0][0], 2.0 * ADVANCE * 5.0);
+
+        assert_eq!(outline("a#", 1.0, [0.0, 0.0]), Err('#'));
+    }
+}
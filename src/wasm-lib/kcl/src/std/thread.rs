@@ -0,0 +1,336 @@
+//! Standard library threads, for holes and shafts.
+
+use anyhow::Result;
+use derive_docs::stdlib;
+
+use crate::{
+    errors::{KclError, KclErrorDetails},
+    execution::{
+        kcl_value::UnitLen, Artifact, CodeRef, ExecState, KclValue, Path, Solid, TagIdentifier,
+        Thread as ThreadArtifact, ThreadSubType,
+    },
+    std::Args,
+};
+
+/// Add a screw thread to a hole or shaft.
+pub async fn thread(exec_state: &mut ExecState, args: Args) -> Result<KclValue, KclError> {
+    let solid = args.get_unlabeled_kw_arg("solid")?;
+    let face = args.get_kw_arg("face")?;
+    let standard = args.get_kw_arg("standard")?;
+    let length = args.get_kw_arg("length")?;
+    let internal = args.get_kw_arg_opt("internal")?;
+    let cosmetic = args.get_kw_arg_opt("cosmetic")?;
+
+    let value = inner_thread(solid, face, standard, length, internal, cosmetic, exec_state, args).await?;
+    Ok(KclValue::Solid { value })
+}
+
+/// Add a standard screw thread to a hole or shaft.
+///
+/// The hole or shaft is the face extruded from a tagged circle. The thread starts at the end of
+/// the extrusion and runs back towards the sketch. All threads are right-handed, with the basic
+/// profile shared by ISO metric and Unified threads.
+///
+/// The standard is an ISO metric size, like `"M8"` (the coarse pitch) or `"M8x1"` (a fine pitch),
+/// or a Unified size, like `"1/4-20 UNC"`, `"#10-32 UNF"` or `"1/2-13"`.
+///
+/// The thread is cosmetic: it doesn't change the model, but it's recorded with the model, so
+/// drawings and exports can show it. A shaft is usually modeled at the major (nominal) diameter,
+/// and a hole at the tap drill diameter. Modeled threads need solids to be joined and cut, which
+/// isn't available yet.
+///
+/// ```no_run
+/// // A bolt with a thread.
+/// bolt = startSketchOn('XY')
+///   |> circle({ center = [0, 0], radius = 4 }, %, $shank)
+///   |> extrude(length = 30)
+///   |> thread(face = shank, standard = "M8", length = 20)
+/// ```
+///
+/// ```no_run
+/// // A nut with a thread.
+/// nut = startSketchOn('XY')
+///   |> polygon({ radius = 7.5, numSides = 6, center = [0, 0], inscribed = false }, %)
+///   |> hole(circle({ center = [0, 0], radius = 3.4 }, %, $bore), %)
+///   |> extrude(length = 6.5)
+///   |> thread(face = bore, standard = "M8x1.25", length = 6.5, internal = true)
+/// ```
+#[stdlib {
+    name = "thread",
+    // Unpublished while threads are only cosmetic: they're recorded for drawings and exports
+    // but nothing is drawn, so an example's render would just show the plain cylinder.
+    unpublished = true,
+    feature_tree_operation = true,
+    keywords = true,
+    unlabeled_first = true,
+    args = {
+        solid = { docs = "The solid with the hole or shaft." },
+        face = { docs = "The tag of the circle which was extruded into the hole or shaft." },
+        standard = { docs = "The thread size, like `\"M8x1.25\"` or `\"1/4-20 UNC\"`." },
+        length = { docs = "How far along the hole or shaft the thread goes." },
+        internal = { docs = "True for a thread in a hole, false for a thread on a shaft. Defaults to false." },
+        cosmetic = { docs = "If true, the thread is only recorded, not modeled. Only cosmetic threads are available, so this defaults to true." },
+    }
+}]
+#[allow(clippy::too_many_arguments)]
+async fn inner_thread(
+    solid: Box<Solid>,
+    face: TagIdentifier,
+    standard: String,
+    length: f64,
+    internal: Option<bool>,
+    cosmetic: Option<bool>,
+    exec_state: &mut ExecState,
+    args: Args,
+) -> Result<Box<Solid>, KclError> {
+    let internal = internal.unwrap_or_default();
+    // Modeling the ridge of a thread would give a solid overlapping the hole or shaft, which can't
+    // be joined to it or cut from it until there are boolean operations on solids.
+    if cosmetic == Some(false) {
+        return Err(KclError::Semantic(KclErrorDetails {
+            message: "Modeled threads aren't available yet, since solids can't be joined or cut. Use `cosmetic = true`"
+                .to_owned(),
+            source_ranges: vec![args.source_range],
+        }));
+    }
+    let size = ThreadSize::parse(&standard).ok_or_else(|| {
+        KclError::Semantic(KclErrorDetails {
+            message: format!(
+                "Unknown thread standard `{standard}`, expected an ISO metric size like `M8` or `M8x1`, or a Unified size like `1/4-20 UNC`"
+            ),
+            source_ranges: vec![args.source_range],
+        })
+    })?;
+    if length <= 0.0 {
+        return Err(KclError::Semantic(KclErrorDetails {
+            message: format!("Thread length must be greater than zero, found {length}"),
+            source_ranges: vec![args.source_range],
+        }));
+    }
+
+    let info = args.get_tag_engine_info(exec_state, &face)?.clone();
+    let Some(Path::Circle { radius, .. }) = info.path else {
+        return Err(KclError::Type(KclErrorDetails {
+            message: format!("Expected `{}` to be the tag of a circle", face.value),
+            source_ranges: vec![args.source_range],
+        }));
+    };
+
+    // Threads are specified in millimeters.
+    let mm = |value: f64| UnitLen::Mm.convert_to(value, solid.units);
+    let major_diameter = mm(size.major_diameter);
+    let minor_diameter = mm(size.minor_diameter());
+    let pitch = mm(size.pitch);
+    let diameter = 2.0 * radius;
+    let (smallest, largest) = (minor_diameter - pitch / 4.0, major_diameter + pitch / 4.0);
+    if diameter < smallest || diameter > largest {
+        return Err(KclError::Semantic(KclErrorDetails {
+            message: format!(
+                "A {standard} thread doesn't fit a diameter of {diameter}, which should be between {smallest:.3} and {largest:.3}"
+            ),
+            source_ranges: vec![args.source_range],
+        }));
+    }
+
+    let id = exec_state.next_uuid();
+    exec_state.add_artifact(Artifact::Thread(ThreadArtifact {
+        id: id.into(),
+        sub_type: ThreadSubType::Cosmetic,
+        standard,
+        internal,
+        major_diameter,
+        pitch,
+        length,
+        units: solid.units,
+        solid_id: solid.artifact_id,
+        face_id: info.surface.map(|surface| surface.face_id().into()),
+        code_ref: CodeRef {
+            range: args.source_range,
+            path_to_node: Vec::new(),
+        },
+    }));
+
+    Ok(solid)
+}
+
+/// The size of a standard thread, in millimeters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ThreadSize {
+    major_diameter: f64,
+    pitch: f64,
+}
+
+impl ThreadSize {
+    /// Look up a thread designation, like `M8x1.25` or `1/4-20 UNC`.
+    fn parse(standard: &str) -> Option<Self> {
+        let standard = standard.trim();
+        match standard.strip_prefix('M') {
+            Some(metric) => Self::parse_metric(metric),
+            None => Self::parse_unified(standard),
+        }
+    }
+
+    fn parse_metric(size: &str) -> Option<Self> {
+        let (diameter, pitch) = match size.split_once(['x', 'X', '×']) {
+            Some((diameter, pitch)) => (diameter, Some(pitch.trim().parse::<f64>().ok()?)),
+            None => (size, None),
+        };
+        let diameter: f64 = diameter.trim().parse().ok()?;
+        let size = METRIC.iter().find(|size| size.diameter == diameter)?;
+        let pitch = match pitch {
+            None => size.coarse,
+            Some(pitch) if pitch == size.coarse || size.fine.contains(&pitch) => pitch,
+            Some(_) => return None,
+        };
+        Some(Self {
+            major_diameter: size.diameter,
+            pitch,
+        })
+    }
+
+    fn parse_unified(standard: &str) -> Option<Self> {
+        let (size, series) = match standard.rsplit_once([' ', '-']) {
+            Some((size, "UNC")) => (size, Some(UnifiedSeries::Coarse)),
+            Some((size, "UNF")) => (size, Some(UnifiedSeries::Fine)),
+            _ => (standard, None),
+        };
+        let (name, threads_per_inch) = size.trim().rsplit_once('-')?;
+        let threads_per_inch: u32 = threads_per_inch.parse().ok()?;
+        let size = UNIFIED.iter().find(|size| size.name == name)?;
+        let matches = match series {
+            Some(UnifiedSeries::Coarse) => size.unc == Some(threads_per_inch),
+            Some(UnifiedSeries::Fine) => size.unf == Some(threads_per_inch),
+            None => size.unc == Some(threads_per_inch) || size.unf == Some(threads_per_inch),
+        };
+        matches.then(|| Self {
+            major_diameter: size.diameter * MM_PER_INCH,
+            pitch: MM_PER_INCH / threads_per_inch as f64,
+        })
+    }
+
+    /// The diameter at the roots of a shaft's thread and the crests of a hole's thread.
+    fn minor_diameter(&self) -> f64 {
+        // The fundamental triangle is sqrt(3) / 2 pitches high, and the basic profile is five
+        // eighths of it deep.
+        self.major_diameter - 2.0 * 5.0 / 8.0 * (3f64.sqrt() / 2.0) * self.pitch
+    }
+}
+
+const MM_PER_INCH: f64 = 25.4;
+
+/// An ISO metric thread size, from ISO 261.
+struct MetricSize {
+    diameter: f64,
+    coarse: f64,
+    fine: &'static [f64],
+}
+
+const fn metric(diameter: f64, coarse: f64, fine: &'static [f64]) -> MetricSize {
+    MetricSize { diameter, coarse, fine }
+}
+
+const METRIC: &[MetricSize] = &[
+    metric(1.0, 0.25, &[0.2]),
+    metric(1.2, 0.25, &[0.2]),
+    metric(1.6, 0.35, &[0.2]),
+    metric(2.0, 0.4, &[0.25]),
+    metric(2.5, 0.45, &[0.35]),
+    metric(3.0, 0.5, &[0.35]),
+    metric(4.0, 0.7, &[0.5]),
+    metric(5.0, 0.8, &[0.5]),
+    metric(6.0, 1.0, &[0.75]),
+    metric(8.0, 1.25, &[1.0, 0.75]),
+    metric(10.0, 1.5, &[1.25, 1.0, 0.75]),
+    metric(12.0, 1.75, &[1.5, 1.25, 1.0]),
+    metric(14.0, 2.0, &[1.5, 1.25, 1.0]),
+    metric(16.0, 2.0, &[1.5, 1.0]),
+    metric(18.0, 2.5, &[2.0, 1.5, 1.0]),
+    metric(20.0, 2.5, &[2.0, 1.5, 1.0]),
+    metric(22.0, 2.5, &[2.0, 1.5, 1.0]),
+    metric(24.0, 3.0, &[2.0, 1.5, 1.0]),
+    metric(27.0, 3.0, &[2.0, 1.5, 1.0]),
+    metric(30.0, 3.5, &[3.0, 2.0, 1.5, 1.0]),
+    metric(33.0, 3.5, &[3.0, 2.0, 1.5]),
+    metric(36.0, 4.0, &[3.0, 2.0, 1.5]),
+    metric(42.0, 4.5, &[4.0, 3.0, 2.0, 1.5]),
+    metric(48.0, 5.0, &[4.0, 3.0, 2.0, 1.5]),
+    metric(56.0, 5.5, &[4.0, 3.0, 2.0, 1.5]),
+    metric(64.0, 6.0, &[4.0, 3.0, 2.0, 1.5]),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum UnifiedSeries {
+    Coarse,
+    Fine,
+}
+
+/// A Unified thread size, from ASME B1.1, with the threads per inch of its coarse (UNC) and fine
+/// (UNF) series.
+struct UnifiedSize {
+    name: &'static str,
+    /// In inches.
+    diameter: f64,
+    unc: Option<u32>,
+    unf: Option<u32>,
+}
+
+const fn unified(name: &'static str, diameter: f64, unc: Option<u32>, unf: Option<u32>) -> UnifiedSize {
+    UnifiedSize {
+        name,
+        diameter,
+        unc,
+        unf,
+    }
+}
+
+const UNIFIED: &[UnifiedSize] = &[
+    unified("#0", 0.06, None, Some(80)),
+    unified("#1", 0.073, Some(64), Some(72)),
+    unified("#2", 0.086, Some(56), Some(64)),
+    unified("#3", 0.099, Some(48), Some(56)),
+    unified("#4", 0.112, Some(40), Some(48)),
+    unified("#5", 0.125, Some(40), Some(44)),
+    unified("#6", 0.138, Some(32), Some(40)),
+    unified("#8", 0.164, Some(32), Some(36)),
+    unified("#10", 0.19, Some(24), Some(32)),
+    unified("#12", 0.216, Some(24), Some(28)),
+    unified("1/4", 0.25, Some(20), Some(28)),
+    unified("5/16", 0.3125, Some(18), Some(24)),
+    unified("3/8", 0.375, Some(16), Some(24)),
+    unified("7/16", 0.4375, Some(14), Some(20)),
+    unified("1/2", 0.5, Some(13), Some(20)),
+    unified("9/16", 0.5625, Some(12), Some(18)),
+    unified("5/8", 0.625, Some(11), Some(18)),
+    unified("3/4", 0.75, Some(10), Some(16)),
+    unified("7/8", 0.875, Some(9), Some(14)),
+    unified("1", 1.0, Some(8), Some(12)),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_metric() {
+        let coarse = ThreadSize::parse("M8").unwrap();
+        assert_eq!(coarse, ThreadSize::parse("M8x1.25").unwrap());
+        assert_eq!(coarse.major_diameter, 8.0);
+        assert_eq!(coarse.pitch, 1.25);
+        assert!((coarse.minor_diameter() - 6.647).abs() < 1e-3);
+        assert_eq!(ThreadSize::parse("M10x1").unwrap().pitch, 1.0);
+        assert_eq!(ThreadSize::parse("M8x2"), None);
+        assert_eq!(ThreadSize::parse("M7"), None);
+    }
+
+    #[test]
+    fn parse_unified() {
+        let unc = ThreadSize::parse("1/4-20 UNC").unwrap();
+        assert_eq!(unc, ThreadSize::parse("1/4-20").unwrap());
+        assert_eq!(unc.major_diameter, 6.35);
+        assert_eq!(unc.pitch, 1.27);
+        assert_eq!(ThreadSize::parse("#10-32 UNF").unwrap().pitch, 25.4 / 32.0);
+        assert_eq!(ThreadSize::parse("1/4-28-UNF").unwrap().pitch, 25.4 / 28.0);
+        assert_eq!(ThreadSize::parse("1/4-28 UNC"), None);
+        assert_eq!(ThreadSize::parse("1/4"), None);
+    }
+}
@@ -0,0 +1,590 @@
+//! Standard library transforms: moving, rotating and scaling solids and sketches.
+
+use anyhow::Result;
+use derive_docs::stdlib;
+use kcmc::{
+    each_cmd as mcmd,
+    shared::{ComponentTransform, Point4d, TransformBy},
+    ModelingCmd,
+};
+use kittycad_modeling_cmds::{self as kcmc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    errors::{KclError, KclErrorDetails},
+    execution::{ExecState, KclValue, Path, PlaneType, Point3d, Sketch, SketchSet, SketchSurface, Solid, SolidSet},
+    std::Args,
+};
+
+/// Solids or sketches to be transformed.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, ts_rs::TS, JsonSchema)]
+#[ts(export)]
+#[serde(untagged)]
+pub enum SolidOrSketchSet {
+    Solids(SolidSet),
+    Sketches(SketchSet),
+}
+
+impl From<SolidOrSketchSet> for KclValue {
+    fn from(value: SolidOrSketchSet) -> Self {
+        match value {
+            SolidOrSketchSet::Solids(solids) => solids.into(),
+            SolidOrSketchSet::Sketches(sketches) => sketches.into(),
+        }
+    }
+}
+
+/// Move solids or sketches.
+pub async fn translate(exec_state: &mut ExecState, args: Args) -> Result<KclValue, KclError> {
+    let objects = args.get_unlabeled_kw_arg("objects")?;
+    let translate = args.get_kw_arg("translate")?;
+
+    let objects = inner_translate(objects, translate, exec_state, args).await?;
+    Ok(objects.into())
+}
+
+/// Move solids or sketches by a given offset.
+///
+/// The geometry's sketch planes move with it, so segment measurements like `segEnd` and
+/// `profileStart`, which are relative to the sketch plane, are unchanged.
+///
+/// ```no_run
+/// // Move a cube 10 units up.
+/// cube = startSketchOn('XY')
+///   |> startProfileAt([0, 0], %)
+///   |> line(end = [10, 0])
+///   |> line(end = [0, 10])
+///   |> line(end = [-10, 0])
+///   |> close()
+///   |> extrude(length = 10)
+///   |> translate(translate = [0, 0, 10])
+/// ```
+///
+/// ```no_run
+/// // Place two copies of a part side by side.
+/// fn bracket() {
+///   return startSketchOn('XZ')
+///     |> startProfileAt([0, 0], %)
+///     |> line(end = [20, 0])
+///     |> line(end = [0, 4])
+///     |> line(end = [-16, 0])
+///     |> line(end = [0, 16])
+///     |> line(end = [-4, 0])
+///     |> close()
+///     |> extrude(length = 10)
+/// }
+///
+/// left = bracket()
+/// right = bracket()
+///   |> translate(translate = [30, 0, 0])
+/// ```
+#[stdlib {
+    name = "translate",
+    // Unpublished until the examples have been rendered by the engine. The mock engine
+    // ignores transforms, so only the renders show the engine moves the solid to match.
+    unpublished = true,
+    keywords = true,
+    unlabeled_first = true,
+    args = {
+        objects = { docs = "The solids or sketches to move." },
+        translate = { docs = "How far to move them along the x, y and z axes." },
+    }
+}]
+async fn inner_translate(
+    objects: SolidOrSketchSet,
+    translate: [f64; 3],
+    exec_state: &mut ExecState,
+    args: Args,
+) -> Result<SolidOrSketchSet, KclError> {
+    let transform = Transform::Translate(translate.into());
+    transform.apply(objects, exec_state, &args).await
+}
+
+/// Rotate solids or sketches.
+pub async fn rotate(exec_state: &mut ExecState, args: Args) -> Result<KclValue, KclError> {
+    let objects = args.get_unlabeled_kw_arg("objects")?;
+    let axis = args.get_kw_arg("axis")?;
+    let angle = args.get_kw_arg("angle")?;
+    let origin = args.get_kw_arg_opt("origin")?;
+
+    let objects = inner_rotate(objects, axis, angle, origin, exec_state, args).await?;
+    Ok(objects.into())
+}
+
+/// Rotate solids or sketches about an axis.
+///
+/// The axis passes through the global origin, unless another point is given as the `origin`.
+/// The rotation is counter-clockwise when looking down the axis towards the origin.
+///
+/// ```no_run
+/// // Stand a plate on its edge.
+/// plate = startSketchOn('XY')
+///   |> startProfileAt([0, 0], %)
+///   |> line(end = [20, 0])
+///   |> line(end = [0, 10])
+///   |> line(end = [-20, 0])
+///   |> close()
+///   |> extrude(length = 2)
+///   |> rotate(axis = [1, 0, 0], angle = 90)
+/// ```
+///
+/// ```no_run
+/// // Turn a sketch about its own corner before extruding it.
+/// corner = [10, 10, 0]
+/// sketch001 = startSketchOn('XY')
+///   |> startProfileAt([10, 10], %)
+///   |> line(end = [10, 0])
+///   |> line(end = [0, 5])
+///   |> line(end = [-10, 0])
+///   |> close()
+///   |> rotate(axis = [0, 0, 1], angle = 45, origin = corner)
+///
+/// example = extrude(sketch001, length = 5)
+/// ```
+#[stdlib {
+    name = "rotate",
+    // Unpublished for the same reason as `translate`.
+    unpublished = true,
+    keywords = true,
+    unlabeled_first = true,
+    args = {
+        objects = { docs = "The solids or sketches to rotate." },
+        axis = { docs = "The direction of the axis to rotate about." },
+        angle = { docs = "How far to rotate, in degrees." },
+        origin = { docs = "A point the axis passes through. Defaults to the origin." },
+    }
+}]
+async fn inner_rotate(
+    objects: SolidOrSketchSet,
+    axis: [f64; 3],
+    angle: f64,
+    origin: Option<[f64; 3]>,
+    exec_state: &mut ExecState,
+    args: Args,
+) -> Result<SolidOrSketchSet, KclError> {
+    let axis = Point3d::from(axis);
+    let length = norm(axis);
+    if length == 0.0 {
+        return Err(KclError::Semantic(KclErrorDetails {
+            message: "The axis of rotation must not be zero".to_owned(),
+            source_ranges: vec![args.source_range],
+        }));
+    }
+
+    let transform = Transform::Rotate {
+        axis: scale_by(axis, 1.0 / length),
+        angle: angle.to_radians(),
+        origin: origin.map(Point3d::from).unwrap_or(Point3d::ZERO),
+    };
+    transform.apply(objects, exec_state, &args).await
+}
+
+/// Scale solids or sketches.
+pub async fn scale(exec_state: &mut ExecState, args: Args) -> Result<KclValue, KclError> {
+    let objects = args.get_unlabeled_kw_arg("objects")?;
+    let scale = args.get_kw_arg("scale")?;
+
+    let objects = inner_scale(objects, scale, exec_state, args).await?;
+    Ok(objects.into())
+}
+
+/// Scale solids or sketches about the origin, by a factor along each of the x, y and z axes.
+///
+/// A sketch must be scaled by the same amount along both axes of its sketch plane, since
+/// otherwise its arcs would become ellipses. For the same reason, solids with fillets or
+/// chamfers can only be scaled by the same amount along every axis.
+///
+/// ```no_run
+/// // Make a cube twice as big.
+/// cube = startSketchOn('XY')
+///   |> startProfileAt([0, 0], %)
+///   |> line(end = [10, 0])
+///   |> line(end = [0, 10])
+///   |> line(end = [-10, 0])
+///   |> close()
+///   |> extrude(length = 10)
+///   |> scale(scale = [2, 2, 2])
+/// ```
+///
+/// ```no_run
+/// // Stretch a cylinder to make it taller.
+/// cylinder = startSketchOn('XY')
+///   |> circle({ center = [0, 0], radius = 5 }, %)
+///   |> extrude(length = 10)
+///   |> scale(scale = [1, 1, 3])
+/// ```
+#[stdlib {
+    name = "scale",
+    // Unpublished for the same reason as `translate`.
+    unpublished = true,
+    keywords = true,
+    unlabeled_first = true,
+    args = {
+        objects = { docs = "The solids or sketches to scale." },
+        scale = { docs = "The factor to scale by along each of the x, y and z axes." },
+    }
+}]
+async fn inner_scale(
+    objects: SolidOrSketchSet,
+    scale: [f64; 3],
+    exec_state: &mut ExecState,
+    args: Args,
+) -> Result<SolidOrSketchSet, KclError> {
+    if scale.iter().any(|s| *s <= 0.0) {
+        return Err(KclError::Semantic(KclErrorDetails {
+            message: format!("Scale factors must be greater than zero, found {scale:?}"),
+            source_ranges: vec![args.source_range],
+        }));
+    }
+
+    let transform = Transform::Scale(scale.into());
+    transform.apply(objects, exec_state, &args).await
+}
+
+/// A transform of global space, which is applied to geometry in the engine and to the
+/// KCL-side copy of it.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Transform {
+    Translate(Point3d),
+    Rotate {
+        /// A unit vector.
+        axis: Point3d,
+        /// In radians.
+        angle: f64,
+        origin: Point3d,
+    },
+    Scale(Point3d),
+}
+
+impl Transform {
+    async fn apply(
+        &self,
+        objects: SolidOrSketchSet,
+        exec_state: &mut ExecState,
+        args: &Args,
+    ) -> Result<SolidOrSketchSet, KclError> {
+        match objects {
+            SolidOrSketchSet::Solids(solids) => {
+                let mut solids: Vec<Box<Solid>> = solids.into();
+                for solid in &mut solids {
+                    check_not_on_face(&solid.sketch, args)?;
+                    self.apply_to_solid(solid, exec_state, args).await?;
+                }
+                Ok(SolidOrSketchSet::Solids(solids.into()))
+            }
+            SolidOrSketchSet::Sketches(sketches) => {
+                let mut sketches: Vec<Box<Sketch>> = sketches.into();
+                for sketch in &mut sketches {
+                    check_not_on_face(sketch, args)?;
+                    self.transform_sketch(sketch, args)?;
+                    self.send(sketch.id, exec_state, args).await?;
+                }
+                Ok(SolidOrSketchSet::Sketches(sketches.into()))
+            }
+        }
+    }
+
+    pub(crate) async fn apply_to_solid(
+        &self,
+        solid: &mut Solid,
+        exec_state: &mut ExecState,
+        args: &Args,
+    ) -> Result<(), KclError> {
+        self.transform_solid(solid, args)?;
+        self.send(solid.id, exec_state, args).await
+    }
+
+    async fn send(&self, object_id: uuid::Uuid, exec_state: &mut ExecState, args: &Args) -> Result<(), KclError> {
+        if args.ctx.no_engine_commands() {
+            return Ok(());
+        }
+
+        args.batch_modeling_cmd(
+            exec_state.next_uuid(),
+            ModelingCmd::from(mcmd::SetObjectTransform {
+                object_id,
+                transforms: self.component_transforms(),
+            }),
+        )
+        .await
+    }
+
+    /// The transform as the engine expects it. Transforms are relative to the object's current
+    /// transform and in global space.
+    fn component_transforms(&self) -> Vec<ComponentTransform> {
+        fn by<T>(property: T) -> Option<TransformBy<T>> {
+            Some(TransformBy {
+                property,
+                set: false,
+                is_local: false,
+            })
+        }
+        fn translation(offset: Point3d) -> ComponentTransform {
+            ComponentTransform {
+                translate: by(offset.into()),
+                rotate_rpy: None,
+                rotate_angle_axis: None,
+                scale: None,
+            }
+        }
+
+        match *self {
+            Transform::Translate(offset) => vec![translation(offset)],
+            Transform::Rotate { axis, angle, origin } => {
+                // The engine rotates about the origin, so move the rotation's origin there and back.
+                let rotation = ComponentTransform {
+                    translate: None,
+                    rotate_rpy: None,
+                    rotate_angle_axis: by(Point4d {
+                        x: axis.x,
+                        y: axis.y,
+                        z: axis.z,
+                        w: angle.to_degrees(),
+                    }),
+                    scale: None,
+                };
+                if origin == Point3d::ZERO {
+                    vec![rotation]
+                } else {
+                    vec![translation(scale_by(origin, -1.0)), rotation, translation(origin)]
+                }
+            }
+            Transform::Scale(factors) => vec![ComponentTransform {
+                translate: None,
+                rotate_rpy: None,
+                rotate_angle_axis: None,
+                scale: by(factors.into()),
+            }],
+        }
+    }
+
+    fn point(&self, p: Point3d) -> Point3d {
+        match *self {
+            Transform::Translate(offset) => add(p, offset),
+            Transform::Rotate { origin, .. } => add(self.direction(sub(p, origin)), origin),
+            Transform::Scale(factors) => mul(p, factors),
+        }
+    }
+
+    /// Transform a direction, e.g. the axis of a plane.
+    fn direction(&self, d: Point3d) -> Point3d {
+        match *self {
+            Transform::Rotate { axis, angle, .. } => {
+                // Rodrigues' rotation formula.
+                let (sin, cos) = angle.sin_cos();
+                add(
+                    add(scale_by(d, cos), scale_by(cross(axis, d), sin)),
+                    scale_by(axis, dot(axis, d) * (1.0 - cos)),
+                )
+            }
+            // Scaling is only allowed when it doesn't change the direction of the axes.
+            Transform::Translate(_) | Transform::Scale(_) => d,
+        }
+    }
+
+    /// How much lengths along a direction are scaled by.
+    fn length_factor(&self, d: Point3d) -> f64 {
+        match *self {
+            Transform::Scale(factors) => norm(mul(d, factors)) / norm(d),
+            Transform::Translate(_) | Transform::Rotate { .. } => 1.0,
+        }
+    }
+
+    /// Whether the transform scales lengths the same in every direction.
+    fn is_uniform(&self) -> bool {
+        match self {
+            Transform::Scale(f) => f.x == f.y && f.y == f.z,
+            Transform::Translate(_) | Transform::Rotate { .. } => true,
+        }
+    }
+
+    fn transform_solid(&self, solid: &mut Solid, args: &Args) -> Result<(), KclError> {
+        if let Transform::Scale(factors) = self {
+            if !solid.edge_cuts.is_empty() && !self.is_uniform() {
+                return Err(KclError::Semantic(KclErrorDetails {
+                    message: "Solids with fillets or chamfers can only be scaled by the same amount along every axis"
+                        .to_owned(),
+                    source_ranges: vec![args.source_range],
+                }));
+            }
+            for cut in &mut solid.edge_cuts {
+                match cut {
+                    crate::execution::EdgeCut::Fillet { radius, .. } => *radius *= factors.x,
+                    crate::execution::EdgeCut::Chamfer { length, .. } => *length *= factors.x,
+                }
+            }
+        }
+
+        solid.height *= self.length_factor(solid.sketch.on.z_axis());
+        self.transform_sketch(&mut solid.sketch, args)
+    }
+
+    fn transform_sketch(&self, sketch: &mut Sketch, args: &Args) -> Result<(), KclError> {
+        // Sketch coordinates are relative to the sketch plane, so moving the plane moves the
+        // sketch. Only scaling changes the coordinates themselves.
+        if let Transform::Scale(_) = self {
+            let factor = self.in_plane_factor(&sketch.on, args)?;
+            if factor != 1.0 {
+                sketch.start.from = scale_2d(sketch.start.from, factor);
+                sketch.start.to = scale_2d(sketch.start.to, factor);
+                for path in &mut sketch.paths {
+                    scale_path(path, factor);
+                }
+                for tag in sketch.tags.values_mut() {
+                    if let Some(path) = tag.info.as_mut().and_then(|info| info.path.as_mut()) {
+                        scale_path(path, factor);
+                    }
+                }
+            }
+        }
+
+        // Geometry on a face is only ever moved along the face's normal, e.g. to center a
+        // symmetric extrusion, which leaves it on the face.
+        if let SketchSurface::Plane(plane) = &mut sketch.on {
+            let before = (plane.origin, plane.x_axis, plane.y_axis);
+            plane.origin = self.point(plane.origin);
+            plane.x_axis = self.direction(plane.x_axis);
+            plane.y_axis = self.direction(plane.y_axis);
+            plane.z_axis = self.direction(plane.z_axis);
+            if (plane.origin, plane.x_axis, plane.y_axis) != before {
+                // It's no longer one of the standard planes.
+                plane.value = PlaneType::Custom;
+            }
+        }
+        Ok(())
+    }
+
+    /// The factor a sketch on the given surface is scaled by, within its plane.
+    fn in_plane_factor(&self, surface: &SketchSurface, args: &Args) -> Result<f64, KclError> {
+        let x_axis = surface.x_axis();
+        let y_axis = surface.y_axis();
+        let x_factor = self.length_factor(x_axis);
+        let y_factor = self.length_factor(y_axis);
+        // Scaling along axes which aren't aligned with the plane would skew it.
+        let aligned = [x_axis, y_axis, surface.z_axis()]
+            .iter()
+            .all(|axis| [axis.x, axis.y, axis.z].iter().filter(|c| c.abs() > EPSILON).count() == 1);
+        if (!self.is_uniform() && !aligned) || (x_factor - y_factor).abs() > EPSILON {
+            return Err(KclError::Semantic(KclErrorDetails {
+                message: "A sketch must be scaled by the same amount along both axes of its sketch plane".to_owned(),
+                source_ranges: vec![args.source_range],
+            }));
+        }
+        Ok(x_factor)
+    }
+}
+
+const EPSILON: f64 = 1e-9;
+
+/// Geometry sketched on a face can't be moved, since the face it refers to would no longer be
+/// where the geometry is, and we can't describe where it has moved to.
+fn check_not_on_face(sketch: &Sketch, args: &Args) -> Result<(), KclError> {
+    match &sketch.on {
+        SketchSurface::Plane(_) => Ok(()),
+        SketchSurface::Face(face) => Err(KclError::Semantic(KclErrorDetails {
+            message: format!(
+                "Geometry sketched on a face (`{}`) can't be transformed, transform the solid the face is on instead",
+                face.value
+            ),
+            source_ranges: vec![args.source_range],
+        })),
+    }
+}
+
+fn scale_path(path: &mut Path, factor: f64) {
+    if let Some(base) = path.get_base_mut() {
+        base.from = scale_2d(base.from, factor);
+        base.to = scale_2d(base.to, factor);
+    }
+    match path {
+        Path::TangentialArcTo { center, .. } | Path::TangentialArc { center, .. } => {
+            *center = scale_2d(*center, factor);
+        }
+        Path::Circle { center, radius, .. } | Path::Arc { center, radius, .. } => {
+            *center = scale_2d(*center, factor);
+            *radius *= factor;
+        }
+        Path::Bezier { control1, control2, .. } => {
+            *control1 = scale_2d(*control1, factor);
+            *control2 = scale_2d(*control2, factor);
+        }
+        Path::Horizontal { x, .. } => *x *= factor,
+        Path::AngledLineTo { x, y, .. } => {
+            *x = x.map(|x| x * factor);
+            *y = y.map(|y| y * factor);
+        }
+        Path::ToPoint { .. } | Path::Base { .. } => {}
+    }
+}
+
+fn scale_2d(p: [f64; 2], factor: f64) -> [f64; 2] {
+    [p[0] * factor, p[1] * factor]
+}
+
+fn add(a: Point3d, b: Point3d) -> Point3d {
+    Point3d::new(a.x + b.x, a.y + b.y, a.z + b.z)
+}
+
+fn sub(a: Point3d, b: Point3d) -> Point3d {
+    Point3d::new(a.x - b.x, a.y - b.y, a.z - b.z)
+}
+
+/// Component-wise product.
+fn mul(a: Point3d, b: Point3d) -> Point3d {
+    Point3d::new(a.x * b.x, a.y * b.y, a.z * b.z)
+}
+
+fn scale_by(a: Point3d, factor: f64) -> Point3d {
+    Point3d::new(a.x * factor, a.y * factor, a.z * factor)
+}
+
+fn dot(a: Point3d, b: Point3d) -> f64 {
+    a.x * b.x + a.y * b.y + a.z * b.z
+}
+
+fn cross(a: Point3d, b: Point3d) -> Point3d {
+    Point3d::new(a.y * b.z - a.z * b.y, a.z * b.x - a.x * b.z, a.x * b.y - a.y * b.x)
+}
+
+fn norm(a: Point3d) -> f64 {
+    dot(a, a).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: Point3d, expected: Point3d) {
+        assert!(norm(sub(actual, expected)) < 1e-9, "{actual:?} != {expected:?}");
+    }
+
+    #[test]
+    fn rotate_about_point() {
+        let transform = Transform::Rotate {
+            axis: Point3d::new(0.0, 0.0, 1.0),
+            angle: 90f64.to_radians(),
+            origin: Point3d::new(1.0, 0.0, 0.0),
+        };
+        assert_close(
+            transform.point(Point3d::new(2.0, 0.0, 5.0)),
+            Point3d::new(1.0, 1.0, 5.0),
+        );
+        assert_close(
+            transform.direction(Point3d::new(1.0, 0.0, 0.0)),
+            Point3d::new(0.0, 1.0, 0.0),
+        );
+        assert_eq!(transform.component_transforms().len(), 3);
+    }
+
+    #[test]
+    fn scale_lengths() {
+        let transform = Transform::Scale(Point3d::new(2.0, 2.0, 3.0));
+        assert_close(
+            transform.point(Point3d::new(1.0, 1.0, 1.0)),
+            Point3d::new(2.0, 2.0, 3.0),
+        );
+        assert_eq!(transform.length_factor(Point3d::new(0.0, 0.0, 1.0)), 3.0);
+        assert_eq!(transform.length_factor(Point3d::new(1.0, 0.0, 0.0)), 2.0);
+    }
+}
@@ -5,9 +5,11 @@ use std::path::PathBuf;
 use crate::{
     engine::new_zoo_client,
     errors::ExecErrorWithState,
-    execution::{ExecState, ExecutorContext, ExecutorSettings},
+    execution::{
+        CameraView, ExecOutcome, ExecState, ExecutorContext, ExecutorSettings, ExportOptions, FileExportFormat,
+    },
     settings::types::UnitLength,
-    ConnectionError, ExecError, KclErrorWithOutputs, Program,
+    CompilationError, ConnectionError, ExecError, KclError, KclErrorWithOutputs, Program,
 };
 
 #[derive(serde::Deserialize, serde::Serialize)]
@@ -15,6 +17,100 @@ pub struct RequestBody {
     pub kcl_program: String,
     #[serde(default)]
     pub test_name: String,
+    /// Where to take the snapshot from.
+    #[serde(default)]
+    pub view: CameraView,
+}
+
+/// A program to execute and the file format to export the result to.
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct ExportRequestBody {
+    pub kcl_program: String,
+    #[serde(default)]
+    pub test_name: String,
+    pub format: FileExportFormat,
+    #[serde(default)]
+    pub options: ExportOptions,
+}
+
+/// A program to execute, and the views to take snapshots of the result from.
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct ExecuteRequestBody {
+    pub kcl_program: String,
+    #[serde(default)]
+    pub test_name: String,
+    #[serde(default)]
+    pub views: Vec<CameraView>,
+}
+
+/// A project of several KCL files to execute, and the views to take snapshots of the result from.
+#[derive(serde::Deserialize, serde::Serialize)]
+pub struct ProjectRequestBody {
+    /// A zip archive of the project directory, encoded as base64.
+    pub project_zip: String,
+    /// The file to execute, relative to the root of the archive.
+    #[serde(default = "default_main_file")]
+    pub main_file: String,
+    #[serde(default)]
+    pub test_name: String,
+    #[serde(default)]
+    pub views: Vec<CameraView>,
+}
+
+fn default_main_file() -> String {
+    "main.kcl".to_owned()
+}
+
+/// The outputs of a program which executed successfully.
+#[derive(serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecuteResponse {
+    #[serde(flatten)]
+    pub outcome: ExecOutcome,
+    /// Problems with the program which didn't stop it from executing.
+    pub warnings: Vec<CompilationError>,
+    /// One snapshot for each view in the request, in the same order.
+    pub snapshots: Vec<Snapshot>,
+}
+
+/// The error which stopped a program from executing, and the outputs from before it.
+#[derive(serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecuteErrorResponse {
+    #[serde(flatten)]
+    pub error: KclErrorWithOutputs,
+    /// Problems with the program which didn't stop it from executing.
+    pub warnings: Vec<CompilationError>,
+}
+
+/// A PNG image of the scene.
+#[derive(serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Snapshot {
+    pub view: CameraView,
+    /// The PNG, encoded as base64.
+    pub png: String,
+}
+
+/// Parses a program, and separates the warnings about it from the errors.
+/// If it doesn't parse, the first error is returned along with the warnings.
+pub fn parse_with_warnings(code: &str) -> Result<(Program, Vec<CompilationError>), Box<ExecuteErrorResponse>> {
+    let (program, errs) = Program::parse(code).map_err(|error| {
+        Box::new(ExecuteErrorResponse {
+            error: KclErrorWithOutputs::no_outputs(error),
+            warnings: Vec::new(),
+        })
+    })?;
+    let (errors, warnings): (Vec<_>, Vec<_>) = errs.into_iter().partition(|e| e.severity.is_err());
+    let error = match (program, errors.into_iter().next()) {
+        (Some(program), None) => return Ok((program, warnings)),
+        (_, Some(err)) => KclError::Syntax(err.into()),
+        (None, None) => KclError::internal("Unknown parsing error".to_owned()),
+    };
+    Err(Box::new(ExecuteErrorResponse {
+        error: KclErrorWithOutputs::no_outputs(error),
+        warnings,
+    }))
 }
 
 /// Executes a kcl program and takes a snapshot of the result.
@@ -111,6 +207,7 @@ pub async fn new_context(
         replay: None,
         project_directory: None,
         current_file: None,
+        cache_directory: None,
     };
     if let Some(current_file) = current_file {
         settings.with_current_file(current_file);
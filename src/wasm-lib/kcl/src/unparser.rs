@@ -3,11 +3,11 @@ use std::fmt::Write;
 use crate::parsing::{
     ast::types::{
         Annotation, ArrayExpression, ArrayRangeExpression, BinaryExpression, BinaryOperator, BinaryPart, BodyItem,
-        CallExpression, CallExpressionKw, CommentStyle, DefaultParamVal, Expr, FnArgType, FormatOptions,
+        CallExpression, CallExpressionKw, CommentStyle, DefaultParamVal, Expr, FnArgType, ForExpression, FormatOptions,
         FunctionExpression, IfExpression, ImportSelector, ImportStatement, ItemVisibility, LabeledArg, Literal,
         LiteralIdentifier, LiteralValue, MemberExpression, MemberObject, Node, NonCodeNode, NonCodeValue,
-        ObjectExpression, Parameter, PipeExpression, Program, TagDeclarator, UnaryExpression, VariableDeclaration,
-        VariableKind,
+        ObjectExpression, Parameter, PipeExpression, Program, TagDeclarator, TypeDeclaration, UnaryExpression,
+        VariableDeclaration, VariableKind,
     },
     token::NumericSuffix,
     PIPE_OPERATOR,
@@ -37,18 +37,23 @@ impl Program {
             .map(|body_item| {
                 let mut result = String::new();
                 for attr in body_item.get_attrs() {
+                    result.push_str(&indentation);
                     result.push_str(&attr.recast(options, indentation_level));
                 }
                 result.push_str(&match body_item.clone() {
                     BodyItem::ImportStatement(stmt) => stmt.recast(options, indentation_level),
                     BodyItem::ExpressionStatement(expression_statement) => {
-                        expression_statement
-                            .expression
-                            .recast(options, indentation_level, ExprContext::Other)
+                        // Not every expression indents itself, e.g., the final expression in a block.
+                        let expr =
+                            expression_statement
+                                .expression
+                                .recast(options, indentation_level, ExprContext::Other);
+                        format!("{}{}", indentation, expr.trim_start())
                     }
                     BodyItem::VariableDeclaration(variable_declaration) => {
                         variable_declaration.recast(options, indentation_level)
                     }
+                    BodyItem::TypeDeclaration(type_declaration) => type_declaration.recast(options, indentation_level),
                     BodyItem::ReturnStatement(return_statement) => {
                         format!(
                             "{}return {}",
@@ -181,14 +186,15 @@ impl Node<Annotation> {
             result.push_str(
                 &properties
                     .iter()
-                    .map(|prop| {
-                        format!(
+                    .map(|prop| match &prop.value {
+                        Expr::Identifier(id) if id.name == prop.key.name => prop.key.name.clone(),
+                        _ => format!(
                             "{} = {}",
                             prop.key.name,
                             prop.value
                                 .recast(options, indentation_level + 1, ExprContext::Other)
                                 .trim()
-                        )
+                        ),
                     })
                     .collect::<Vec<String>>()
                     .join(", "),
@@ -274,6 +280,7 @@ impl Expr {
             Expr::PipeExpression(pipe_exp) => pipe_exp.recast(options, indentation_level),
             Expr::UnaryExpression(unary_exp) => unary_exp.recast(options),
             Expr::IfExpression(e) => e.recast(options, indentation_level, ctxt),
+            Expr::ForExpression(e) => e.recast(options, indentation_level, ctxt),
             Expr::PipeSubstitution(_) => crate::parsing::PIPE_SUBSTITUTION_OPERATOR.to_string(),
             Expr::LabelledExpression(e) => {
                 let mut result = e.expr.recast(options, indentation_level, ctxt);
@@ -517,6 +524,7 @@ fn expr_is_trivial(expr: &Expr) -> bool {
         | Expr::MemberExpression(_)
         | Expr::UnaryExpression(_)
         | Expr::IfExpression(_)
+        | Expr::ForExpression(_)
         | Expr::LabelledExpression(_) => false,
     }
 }
@@ -707,6 +715,22 @@ impl IfExpression {
     }
 }
 
+impl ForExpression {
+    fn recast(&self, options: &FormatOptions, indentation_level: usize, ctxt: ExprContext) -> String {
+        let iterable = self.iterable.recast(options, indentation_level, ctxt);
+        let lines = [
+            (0, format!("for {} in {iterable} {{", self.var.name)),
+            (1, self.body.recast(options, indentation_level + 1)),
+            (0, "}".to_owned()),
+        ];
+        lines
+            .into_iter()
+            .map(|(ind, line)| format!("{}{}", options.get_indentation(indentation_level + ind), line.trim()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
 impl Node<PipeExpression> {
     fn recast(&self, options: &FormatOptions, indentation_level: usize) -> String {
         let pipe = self
@@ -797,24 +821,39 @@ impl FnArgType {
         match self {
             FnArgType::Primitive(t) => t.to_string(),
             FnArgType::Array(t) => format!("{t}[]"),
-            FnArgType::Object { properties } => {
-                let mut result = "{".to_owned();
-                for p in properties {
-                    result += " ";
-                    result += &p.recast(options, indentation_level);
-                    result += ",";
-                }
+            FnArgType::Object { properties } => recast_fields(properties, options, indentation_level),
+            FnArgType::Named { name } => name.name.clone(),
+        }
+    }
+}
 
-                if result.ends_with(',') {
-                    result.pop();
-                    result += " ";
-                }
-                result += "}";
+impl TypeDeclaration {
+    pub fn recast(&self, options: &FormatOptions, indentation_level: usize) -> String {
+        format!(
+            "{}type {} = {}",
+            options.get_indentation(indentation_level),
+            self.name.name,
+            recast_fields(&self.fields, options, indentation_level)
+        )
+    }
+}
 
-                result
-            }
-        }
+/// Recast the fields of an object or record type, e.g. `{ x: number, y: number }`.
+fn recast_fields(fields: &[Parameter], options: &FormatOptions, indentation_level: usize) -> String {
+    let mut result = "{".to_owned();
+    for p in fields {
+        result += " ";
+        result += &p.recast(options, indentation_level);
+        result += ",";
     }
+
+    if result.ends_with(',') {
+        result.pop();
+        result += " ";
+    }
+    result += "}";
+
+    result
 }
 
 #[cfg(test)]
@@ -845,6 +884,22 @@ mod tests {
         assert_eq!(output, input);
     }
 
+    #[test]
+    fn test_recast_allow_annotations() {
+        let input = r#"@allow(Z0001)
+foo_bar = 1
+fn myFunc() {
+  x = 2
+  @allow(Z0001, Z0002)
+  snake_case = 3
+  return snake_case
+}
+"#;
+        let program = crate::parsing::top_level_parse(input).unwrap();
+        let output = program.recast(&Default::default(), 0);
+        assert_eq!(output, input);
+    }
+
     #[test]
     fn test_recast_annotations_in_function_body_without_items() {
         let input = r#"fn myFunc() {
@@ -884,6 +939,31 @@ mod tests {
         assert_eq!(output, input);
     }
 
+    #[test]
+    fn test_recast_for_same() {
+        let input = r#"b = for i in [0..3] {
+  x = i * 2
+  x + 1
+}
+"#;
+        let program = crate::parsing::top_level_parse(input).unwrap();
+        let output = program.recast(&Default::default(), 0);
+        assert_eq!(output, input);
+    }
+
+    #[test]
+    fn test_recast_type_declaration_same() {
+        let input = r#"type Bolt = { diameter: number, length?: number, label: string }
+
+fn area(bolt: Bolt): number {
+  return bolt.diameter * 2
+}
+"#;
+        let program = crate::parsing::top_level_parse(input).unwrap();
+        let output = program.recast(&Default::default(), 0);
+        assert_eq!(output, input);
+    }
+
     #[test]
     fn test_recast_import() {
         let input = r#"import a from "a.kcl"
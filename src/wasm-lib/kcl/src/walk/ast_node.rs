@@ -12,6 +12,7 @@ pub enum Node<'a> {
     ImportStatement(NodeRef<'a, types::ImportStatement>),
     ExpressionStatement(NodeRef<'a, types::ExpressionStatement>),
     VariableDeclaration(NodeRef<'a, types::VariableDeclaration>),
+    TypeDeclaration(NodeRef<'a, types::TypeDeclaration>),
     ReturnStatement(NodeRef<'a, types::ReturnStatement>),
 
     VariableDeclarator(NodeRef<'a, types::VariableDeclarator>),
@@ -32,6 +33,7 @@ pub enum Node<'a> {
     UnaryExpression(NodeRef<'a, types::UnaryExpression>),
     IfExpression(NodeRef<'a, types::IfExpression>),
     ElseIf(&'a types::ElseIf),
+    ForExpression(NodeRef<'a, types::ForExpression>),
     LabelledExpression(NodeRef<'a, types::LabelledExpression>),
 
     Parameter(&'a types::Parameter),
@@ -52,6 +54,7 @@ impl Node<'_> {
             Node::ImportStatement(n) => n.digest,
             Node::ExpressionStatement(n) => n.digest,
             Node::VariableDeclaration(n) => n.digest,
+            Node::TypeDeclaration(n) => n.digest,
             Node::ReturnStatement(n) => n.digest,
             Node::VariableDeclarator(n) => n.digest,
             Node::Literal(n) => n.digest,
@@ -71,6 +74,7 @@ impl Node<'_> {
             Node::Parameter(p) => p.digest,
             Node::ObjectProperty(n) => n.digest,
             Node::IfExpression(n) => n.digest,
+            Node::ForExpression(n) => n.digest,
             Node::ElseIf(n) => n.digest,
             Node::KclNone(n) => n.digest,
             Node::LabelledExpression(n) => n.digest,
@@ -94,6 +98,7 @@ impl Node<'_> {
             Node::ImportStatement(n) => *n as *const _ as *const (),
             Node::ExpressionStatement(n) => *n as *const _ as *const (),
             Node::VariableDeclaration(n) => *n as *const _ as *const (),
+            Node::TypeDeclaration(n) => *n as *const _ as *const (),
             Node::ReturnStatement(n) => *n as *const _ as *const (),
             Node::VariableDeclarator(n) => *n as *const _ as *const (),
             Node::Literal(n) => *n as *const _ as *const (),
@@ -113,6 +118,7 @@ impl Node<'_> {
             Node::Parameter(p) => *p as *const _ as *const (),
             Node::ObjectProperty(n) => *n as *const _ as *const (),
             Node::IfExpression(n) => *n as *const _ as *const (),
+            Node::ForExpression(n) => *n as *const _ as *const (),
             Node::ElseIf(n) => *n as *const _ as *const (),
             Node::KclNone(n) => *n as *const _ as *const (),
             Node::LabelledExpression(n) => *n as *const _ as *const (),
@@ -136,6 +142,7 @@ impl TryFrom<&Node<'_>> for SourceRange {
             Node::ImportStatement(n) => SourceRange::from(*n),
             Node::ExpressionStatement(n) => SourceRange::from(*n),
             Node::VariableDeclaration(n) => SourceRange::from(*n),
+            Node::TypeDeclaration(n) => SourceRange::from(*n),
             Node::ReturnStatement(n) => SourceRange::from(*n),
             Node::VariableDeclarator(n) => SourceRange::from(*n),
             Node::Literal(n) => SourceRange::from(*n),
@@ -155,6 +162,7 @@ impl TryFrom<&Node<'_>> for SourceRange {
             Node::Parameter(p) => SourceRange::from(&p.identifier),
             Node::ObjectProperty(n) => SourceRange::from(*n),
             Node::IfExpression(n) => SourceRange::from(*n),
+            Node::ForExpression(n) => SourceRange::from(*n),
             Node::LabelledExpression(n) => SourceRange::from(*n),
 
             // This is broken too
@@ -173,6 +181,7 @@ impl<'tree> From<&'tree types::BodyItem> for Node<'tree> {
             types::BodyItem::ImportStatement(v) => v.as_ref().into(),
             types::BodyItem::ExpressionStatement(v) => v.into(),
             types::BodyItem::VariableDeclaration(v) => v.as_ref().into(),
+            types::BodyItem::TypeDeclaration(v) => v.as_ref().into(),
             types::BodyItem::ReturnStatement(v) => v.into(),
         }
     }
@@ -196,6 +205,7 @@ impl<'tree> From<&'tree types::Expr> for Node<'tree> {
             types::Expr::MemberExpression(me) => me.as_ref().into(),
             types::Expr::UnaryExpression(ue) => ue.as_ref().into(),
             types::Expr::IfExpression(e) => e.as_ref().into(),
+            types::Expr::ForExpression(e) => e.as_ref().into(),
             types::Expr::LabelledExpression(e) => e.as_ref().into(),
             types::Expr::None(n) => n.into(),
         }
@@ -259,6 +269,7 @@ impl_from!(Node, Program);
 impl_from!(Node, ImportStatement);
 impl_from!(Node, ExpressionStatement);
 impl_from!(Node, VariableDeclaration);
+impl_from!(Node, TypeDeclaration);
 impl_from!(Node, ReturnStatement);
 impl_from!(Node, VariableDeclarator);
 impl_from!(Node, Literal);
@@ -278,6 +289,7 @@ impl_from!(Node, UnaryExpression);
 impl_from!(Node, ObjectProperty);
 impl_from_ref!(Node, Parameter);
 impl_from!(Node, IfExpression);
+impl_from!(Node, ForExpression);
 impl_from!(Node, ElseIf);
 impl_from!(Node, LabelledExpression);
 impl_from!(Node, KclNone);
@@ -108,7 +108,11 @@ impl<'tree> Visitable<'tree> for Node<'tree> {
                 children.push(n.final_else.as_ref().into());
                 children
             }
+            Node::ForExpression(n) => {
+                vec![(&n.var).into(), n.iterable.as_ref().into(), n.body.as_ref().into()]
+            }
             Node::VariableDeclaration(n) => vec![(&n.declaration).into()],
+            Node::TypeDeclaration(n) => n.fields.iter().map(|v| v.into()).collect(),
             Node::ReturnStatement(n) => {
                 vec![(&n.argument).into()]
             }
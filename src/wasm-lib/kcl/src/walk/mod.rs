@@ -1,7 +1,9 @@
 mod ast_node;
 mod ast_visitor;
 mod ast_walk;
+mod scope;
 
 pub use ast_node::Node;
 pub use ast_visitor::{Visitable, Visitor};
 pub use ast_walk::walk;
+pub use scope::{Reference, Symbol, SymbolKind, SymbolTable};
@@ -0,0 +1,457 @@
+use std::{cell::RefCell, collections::HashMap};
+
+use crate::{
+    parsing::ast::types::{
+        Expr, ImportPath, ImportSelector, ItemVisibility, LiteralIdentifier, MemberObject, NodeRef, Program,
+    },
+    source_range::SourceRange,
+    walk::{Node, Visitable, Visitor},
+};
+
+/// What sort of binding a [Symbol] is.
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum SymbolKind {
+    /// A variable declared with `x = ...`.
+    Variable,
+    /// A user-defined function, `fn x() { ... }`.
+    Function,
+    /// A parameter of a user-defined function.
+    Parameter,
+    /// The variable of a `for` loop, bound to each item in turn.
+    LoopVariable,
+    /// A tag declared with `$x`.
+    Tag,
+    /// A name introduced by labelling an expression, `... as x`.
+    Label,
+    /// A name brought in from another module by an import statement.
+    Import {
+        /// Where the module is being imported from.
+        path: ImportPath,
+        /// The name of the item in the other module, `None` if the module itself is imported.
+        original_name: Option<String>,
+    },
+}
+
+/// A name declared somewhere in a KCL program.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Symbol {
+    /// The name the symbol is bound to.
+    pub name: String,
+    /// What sort of binding this is.
+    pub kind: SymbolKind,
+    /// The source range of the name in the declaration.
+    pub declaration: SourceRange,
+    /// Is the symbol declared at the top level of the program?
+    pub top_level: bool,
+    /// Is the symbol exported from the module with `export`?
+    pub exported: bool,
+}
+
+/// A use of a name somewhere in a KCL program.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reference {
+    /// The name being referenced.
+    pub name: String,
+    /// The source range of the name at the use site.
+    pub range: SourceRange,
+    /// The index of the [Symbol] this resolves to, if it resolves to a
+    /// symbol declared in the program (rather than, say, the std lib).
+    pub symbol: Option<usize>,
+}
+
+/// All the declarations in a program and every use of those names, resolved
+/// with KCL's lexical scoping rules.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SymbolTable {
+    pub symbols: Vec<Symbol>,
+    pub references: Vec<Reference>,
+}
+
+impl SymbolTable {
+    /// Build the symbol table for a program.
+    pub fn new(program: NodeRef<'_, Program>) -> Self {
+        let builder = SymbolTableBuilder::default();
+        let node: Node = program.into();
+        // The builder never fails.
+        let _ = node.visit(&builder);
+        SymbolTable {
+            symbols: builder.symbols.into_inner(),
+            references: builder.references.into_inner(),
+        }
+    }
+
+    /// Get the index of the symbol declared or referenced at the given position.
+    pub fn symbol_at(&self, pos: usize) -> Option<usize> {
+        if let Some(index) = self.symbols.iter().position(|s| s.declaration.contains(pos)) {
+            return Some(index);
+        }
+
+        self.references
+            .iter()
+            .find(|r| r.range.contains(pos))
+            .and_then(|r| r.symbol)
+    }
+
+    /// Get the reference at the given position, whether or not it resolves.
+    pub fn reference_at(&self, pos: usize) -> Option<&Reference> {
+        self.references.iter().find(|r| r.range.contains(pos))
+    }
+
+    /// Iterate over all the references to the given symbol.
+    pub fn references_to(&self, symbol: usize) -> impl Iterator<Item = &Reference> {
+        self.references.iter().filter(move |r| r.symbol == Some(symbol))
+    }
+
+    /// Find the exported top level symbol with the given name.
+    pub fn exported(&self, name: &str) -> Option<usize> {
+        self.symbols
+            .iter()
+            .position(|s| s.top_level && s.exported && s.name == name)
+    }
+}
+
+#[derive(Debug, Default)]
+struct SymbolTableBuilder {
+    symbols: RefCell<Vec<Symbol>>,
+    references: RefCell<Vec<Reference>>,
+    /// A stack of scopes, mapping names to indices into `symbols`.
+    scopes: RefCell<Vec<HashMap<String, usize>>>,
+}
+
+impl SymbolTableBuilder {
+    fn push_scope(&self) {
+        self.scopes.borrow_mut().push(HashMap::new());
+    }
+
+    fn pop_scope(&self) {
+        self.scopes.borrow_mut().pop();
+    }
+
+    fn declare(&self, name: &str, kind: SymbolKind, declaration: SourceRange, exported: bool) {
+        let mut symbols = self.symbols.borrow_mut();
+        let mut scopes = self.scopes.borrow_mut();
+        let top_level = scopes.len() == 1;
+        symbols.push(Symbol {
+            name: name.to_owned(),
+            kind,
+            declaration,
+            top_level,
+            exported,
+        });
+        if let Some(scope) = scopes.last_mut() {
+            scope.insert(name.to_owned(), symbols.len() - 1);
+        }
+    }
+
+    fn refer(&self, name: &str, range: SourceRange) {
+        let symbol = self
+            .scopes
+            .borrow()
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name).copied());
+        self.references.borrow_mut().push(Reference {
+            name: name.to_owned(),
+            range,
+            symbol,
+        });
+    }
+
+    /// Resolve `sketch.tags.foo` to the tag `foo`, wherever it was declared.
+    fn refer_to_tag(&self, name: &str, range: SourceRange) {
+        let symbol = self
+            .symbols
+            .borrow()
+            .iter()
+            .rposition(|s| s.kind == SymbolKind::Tag && s.name == name);
+        self.references.borrow_mut().push(Reference {
+            name: name.to_owned(),
+            range,
+            symbol,
+        });
+    }
+
+    fn visit_children(&self, node: Node<'_>) -> Result<bool, std::convert::Infallible> {
+        for child in node.children() {
+            child.visit(self)?;
+        }
+        Ok(true)
+    }
+}
+
+impl<'tree> Visitor<'tree> for &SymbolTableBuilder {
+    type Error = std::convert::Infallible;
+
+    fn visit_node(&self, node: Node<'tree>) -> Result<bool, Self::Error> {
+        match node {
+            Node::Program(_) => {
+                self.push_scope();
+                self.visit_children(node)?;
+                self.pop_scope();
+                Ok(true)
+            }
+            Node::VariableDeclaration(decl) => {
+                let declarator = &decl.declaration;
+                let exported = decl.visibility == ItemVisibility::Export;
+                // Functions can refer to themselves, other variables can not.
+                if let Expr::FunctionExpression(_) = declarator.init {
+                    self.declare(
+                        &declarator.id.name,
+                        SymbolKind::Function,
+                        (&declarator.id).into(),
+                        exported,
+                    );
+                    Node::from(&declarator.init).visit(*self)?;
+                } else {
+                    Node::from(&declarator.init).visit(*self)?;
+                    self.declare(
+                        &declarator.id.name,
+                        SymbolKind::Variable,
+                        (&declarator.id).into(),
+                        exported,
+                    );
+                }
+                Ok(true)
+            }
+            Node::ImportStatement(stmt) => {
+                let exported = stmt.visibility == ItemVisibility::Export;
+                match &stmt.selector {
+                    ImportSelector::List { items } => {
+                        for item in items {
+                            let declaration = match &item.alias {
+                                Some(alias) => alias.into(),
+                                None => (&item.name).into(),
+                            };
+                            self.declare(
+                                item.identifier(),
+                                SymbolKind::Import {
+                                    path: stmt.path.clone(),
+                                    original_name: Some(item.name.name.clone()),
+                                },
+                                declaration,
+                                exported,
+                            );
+                        }
+                    }
+                    ImportSelector::Glob(_) => {}
+                    ImportSelector::None { alias } => {
+                        if let Some(name) = stmt.module_name() {
+                            let declaration = match alias {
+                                Some(alias) => alias.into(),
+                                None => stmt.into(),
+                            };
+                            self.declare(
+                                &name,
+                                SymbolKind::Import {
+                                    path: stmt.path.clone(),
+                                    original_name: None,
+                                },
+                                declaration,
+                                exported,
+                            );
+                        }
+                    }
+                }
+                Ok(true)
+            }
+            Node::FunctionExpression(func) => {
+                self.push_scope();
+                for param in &func.params {
+                    self.declare(
+                        &param.identifier.name,
+                        SymbolKind::Parameter,
+                        (&param.identifier).into(),
+                        false,
+                    );
+                }
+                Node::from(&func.body).visit(*self)?;
+                self.pop_scope();
+                Ok(true)
+            }
+            Node::ForExpression(expr) => {
+                Node::from(expr.iterable.as_ref()).visit(*self)?;
+                self.push_scope();
+                self.declare(&expr.var.name, SymbolKind::LoopVariable, (&expr.var).into(), false);
+                Node::from(&*expr.body).visit(*self)?;
+                self.pop_scope();
+                Ok(true)
+            }
+            // Types live in their own namespace, and field names aren't variables.
+            Node::TypeDeclaration(_) => Ok(true),
+            Node::TagDeclarator(tag) => {
+                self.declare(&tag.name, SymbolKind::Tag, tag.into(), false);
+                Ok(true)
+            }
+            Node::LabelledExpression(expr) => {
+                Node::from(&expr.expr).visit(*self)?;
+                self.declare(&expr.label.name, SymbolKind::Label, (&expr.label).into(), false);
+                Ok(true)
+            }
+            Node::Identifier(id) => {
+                self.refer(&id.name, id.into());
+                Ok(true)
+            }
+            Node::CallExpression(call) => {
+                self.refer(&call.callee.name, (&call.callee).into());
+                for arg in &call.arguments {
+                    Node::from(arg).visit(*self)?;
+                }
+                Ok(true)
+            }
+            Node::CallExpressionKw(call) => {
+                self.refer(&call.callee.name, (&call.callee).into());
+                for arg in call.iter_arguments() {
+                    Node::from(arg).visit(*self)?;
+                }
+                Ok(true)
+            }
+            Node::MemberExpression(member) => {
+                Node::from(&member.object).visit(*self)?;
+                match &member.property {
+                    LiteralIdentifier::Identifier(id) if member.computed => {
+                        self.refer(&id.name, id.as_ref().into());
+                    }
+                    LiteralIdentifier::Identifier(id) => {
+                        // `sketch.tags.foo` refers to the tag `foo`.
+                        if let MemberObject::MemberExpression(inner) = &member.object {
+                            if let LiteralIdentifier::Identifier(inner_prop) = &inner.property {
+                                if !inner.computed && inner_prop.name == "tags" {
+                                    self.refer_to_tag(&id.name, id.as_ref().into());
+                                }
+                            }
+                        }
+                    }
+                    LiteralIdentifier::Literal(_) => {}
+                }
+                Ok(true)
+            }
+            _ => self.visit_children(node),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    macro_rules! kcl {
+        ( $kcl:expr ) => {{
+            $crate::parsing::top_level_parse($kcl).unwrap()
+        }};
+    }
+
+    fn names_referencing(table: &SymbolTable, name: &str) -> Vec<(usize, usize)> {
+        let symbol = table.symbols.iter().position(|s| s.name == name).unwrap();
+        table
+            .references_to(symbol)
+            .map(|r| (r.range.start(), r.range.end()))
+            .collect()
+    }
+
+    #[test]
+    fn resolves_top_level_variables() {
+        let program = kcl!(
+            "\
+foo = 1
+bar = foo + 1
+baz = [foo, bar]
+"
+        );
+        let table = SymbolTable::new(&program);
+        assert_eq!(table.symbols.len(), 3);
+        assert_eq!(names_referencing(&table, "foo"), vec![(14, 17), (29, 32)]);
+        assert_eq!(names_referencing(&table, "bar"), vec![(34, 37)]);
+    }
+
+    #[test]
+    fn params_shadow_outer_names() {
+        let program = kcl!(
+            "\
+x = 1
+fn f(x) {
+  return x + 1
+}
+y = f(x)
+"
+        );
+        let table = SymbolTable::new(&program);
+        let outer = 0;
+        let param = table
+            .symbols
+            .iter()
+            .position(|s| s.kind == SymbolKind::Parameter)
+            .unwrap();
+        assert_eq!(table.references_to(param).count(), 1);
+        assert_eq!(table.references_to(outer).count(), 1);
+        // `f` is a reference to the function.
+        let f = table.symbols.iter().position(|s| s.name == "f").unwrap();
+        assert_eq!(table.symbols[f].kind, SymbolKind::Function);
+        assert_eq!(table.references_to(f).count(), 1);
+    }
+
+    #[test]
+    fn loop_variables_are_scoped_to_the_body() {
+        let program = kcl!(
+            "\
+i = [1, 2]
+x = for i in i {
+  i * 2
+}
+"
+        );
+        let table = SymbolTable::new(&program);
+        let outer = 0;
+        let var = table
+            .symbols
+            .iter()
+            .position(|s| s.kind == SymbolKind::LoopVariable)
+            .unwrap();
+        assert!(!table.symbols[var].top_level);
+        assert_eq!(table.references_to(var).count(), 1);
+        assert_eq!(table.references_to(outer).count(), 1);
+    }
+
+    #[test]
+    fn resolves_tags() {
+        let program = kcl!(
+            "\
+sketch001 = startSketchOn('XY')
+  |> startProfileAt([0, 0], %)
+  |> line([10, 0], %, $seg01)
+  |> line([0, segLen(seg01)], %)
+sketch002 = sketch001.tags.seg01
+"
+        );
+        let table = SymbolTable::new(&program);
+        let tag = table.symbols.iter().position(|s| s.kind == SymbolKind::Tag).unwrap();
+        assert_eq!(table.symbols[tag].name, "seg01");
+        assert_eq!(table.references_to(tag).count(), 2);
+        // Std lib calls don't resolve to anything.
+        let start = table.references.iter().find(|r| r.name == "startSketchOn").unwrap();
+        assert_eq!(start.symbol, None);
+    }
+
+    #[test]
+    fn resolves_imports() {
+        let program = kcl!(
+            "\
+import foo as bar from 'other.kcl'
+x = bar(1)
+"
+        );
+        let table = SymbolTable::new(&program);
+        let import = table.symbols.iter().position(|s| s.name == "bar").unwrap();
+        assert_eq!(
+            table.symbols[import].kind,
+            SymbolKind::Import {
+                path: ImportPath::Kcl {
+                    filename: "other.kcl".to_owned()
+                },
+                original_name: Some("foo".to_owned()),
+            }
+        );
+        assert_eq!(table.references_to(import).count(), 1);
+        assert_eq!(table.symbol_at(41), Some(import));
+    }
+}
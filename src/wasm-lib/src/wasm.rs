@@ -47,9 +47,13 @@ pub async fn execute_with_engine(
 
     let program: Program = serde_json::from_str(program_ast_json).map_err(|e| e.to_string())?;
     let config: kcl_lib::Configuration = serde_json::from_str(settings).map_err(|e| e.to_string())?;
+    let cache_imports = config.settings.modeling.cache_imports;
     let mut settings: kcl_lib::ExecutorSettings = config.into();
     if let Some(path) = path {
         settings.with_current_file(std::path::PathBuf::from(path));
+        if cache_imports {
+            settings.with_disk_cache();
+        }
     }
 
     let ctx = kcl_lib::ExecutorContext::new(engine_manager, fs_manager, settings.into()).await?;
@@ -76,9 +80,13 @@ pub async fn execute_mock(
     let program: Program = serde_json::from_str(program_ast_json).map_err(|e| e.to_string())?;
     let variables = serde_json::from_str(variables).map_err(|e| e.to_string())?;
     let config: kcl_lib::Configuration = serde_json::from_str(settings).map_err(|e| e.to_string())?;
+    let cache_imports = config.settings.modeling.cache_imports;
     let mut settings: kcl_lib::ExecutorSettings = config.into();
     if let Some(path) = path {
         settings.with_current_file(std::path::PathBuf::from(path));
+        if cache_imports {
+            settings.with_disk_cache();
+        }
     }
 
     let ctx = kcl_lib::ExecutorContext::new_mock(fs_manager, settings.into()).await?;
@@ -180,7 +188,12 @@ pub fn deserialize_files(data: &[u8]) -> Result<JsValue, JsError> {
 pub fn parse_wasm(kcl_program_source: &str) -> Result<JsValue, String> {
     console_error_panic_hook::set_once();
 
-    let (program, errs) = Program::parse(kcl_program_source).map_err(String::from)?;
+    let (program, mut errs) = Program::parse(kcl_program_source).map_err(String::from)?;
+    if let Some(program) = &program {
+        if !errs.iter().any(|e| e.severity.is_err()) {
+            errs.extend(program.typecheck());
+        }
+    }
     // The serde-wasm-bindgen does not work here because of weird HashMap issues so we use the
     // gloo-serialize crate instead.
     JsValue::from_serde(&(program, errs)).map_err(|e| e.to_string())